@@ -1,5 +1,17 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode};
-use kimchi::bench::BenchmarkCtx;
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode, Throughput,
+};
+use kimchi::{
+    alphas::Alphas,
+    bench::{BenchmarkCtx, ConstraintsEvaluationCtx},
+    circuits::{
+        argument::{Argument, ArgumentType},
+        expr::compiled::CompiledExpr,
+        gate::GateType,
+        polynomials::{poseidon::Poseidon, varbasemul::VarbaseMul},
+    },
+};
+use mina_curves::pasta::fp::Fp;
 
 pub fn bench_proof_creation(c: &mut Criterion) {
     let mut group = c.benchmark_group("Proof creation");
@@ -21,5 +33,72 @@ pub fn bench_proof_verification(c: &mut Criterion) {
         b.iter(|| ctx.batch_verification(black_box(vec![proof.clone()])))
     });
 }
-criterion_group!(benches, bench_proof_creation, bench_proof_verification);
+
+pub fn bench_batch_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Batch verification");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    let ctx = BenchmarkCtx::new(1 << 4);
+    let proof = ctx.create_proof();
+    for size in [1, 16, 128] {
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| ctx.batch_verification(black_box(vec![proof.clone(); size])))
+        });
+    }
+}
+
+pub fn bench_par_batch_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Parallel batch verification");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    let ctx = BenchmarkCtx::new(1 << 4);
+    let proof = ctx.create_proof();
+    for size in [1, 16, 128] {
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| ctx.par_batch_verification(black_box(vec![proof.clone(); size])))
+        });
+    }
+}
+
+pub fn bench_constraints_evaluation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Constraints evaluation");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    let ctx = ConstraintsEvaluationCtx::new(1 << 14);
+    let env = ctx.env();
+
+    let mut alphas = Alphas::<Fp>::default();
+    alphas.register(
+        ArgumentType::Gate(GateType::VarBaseMul),
+        VarbaseMul::<Fp>::CONSTRAINTS,
+    );
+    let constraints = [
+        ("poseidon", Poseidon::combined_constraints(&alphas)),
+        ("varbasemul", VarbaseMul::combined_constraints(&alphas)),
+    ];
+
+    for (name, expr) in constraints {
+        group.bench_with_input(BenchmarkId::new("interpreted", name), &expr, |b, expr| {
+            b.iter(|| black_box(expr.evaluations(&env)))
+        });
+
+        let compiled = CompiledExpr::compile_expr(&expr);
+        group.bench_with_input(
+            BenchmarkId::new("compiled", name),
+            &compiled,
+            |b, compiled| b.iter(|| black_box(compiled.evaluations(&env))),
+        );
+    }
+}
+
+criterion_group!(
+    benches,
+    bench_proof_creation,
+    bench_proof_verification,
+    bench_batch_verification,
+    bench_par_batch_verification,
+    bench_constraints_evaluation
+);
 criterion_main!(benches);
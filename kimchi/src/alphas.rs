@@ -22,13 +22,16 @@
 
 use crate::circuits::{argument::ArgumentType, gate::GateType};
 use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use std::{
     collections::HashMap,
-    fmt::Display,
+    fmt::{Display, Write},
     iter::{Cloned, Skip, Take},
     ops::Range,
     slice::Iter,
+    sync::Mutex,
     thread,
 };
 
@@ -38,27 +41,84 @@ use std::{
 /// See [Self::default] to create one,
 /// and [Self::register] to register a new mapping.
 /// Once you know the alpha value, you can convert this type to a [Alphas].
+#[serde_as]
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(bound = "F: CanonicalSerialize + CanonicalDeserialize")]
 pub struct Alphas<F> {
     /// The next power of alpha to use
     /// the end result will be [1, alpha^{next_power - 1}]
     next_power: u32,
     /// The mapping between constraint types and powers of alpha
     mapping: HashMap<ArgumentType, (u32, u32)>,
+    /// The number of powers of alpha that each argument declared it uses.
+    /// Unlike [Self::mapping], gates are not merged together here.
+    #[serde(default)]
+    declared: HashMap<ArgumentType, u32>,
     /// The powers of alpha: 1, alpha, alpha^2, etc.
     /// If set to [Some], you can't register new constraints.
+    #[serde_as(as = "Option<Vec<o1_utils::serialization::SerdeAs>>")]
     alphas: Option<Vec<F>>,
+    /// The powers of alpha requested so far
+    #[serde(skip)]
+    usage: Usage,
+}
+
+/// For each registered range of powers of alpha,
+/// the [ArgumentType]s that requested powers in it, along with the number of powers they requested.
+#[derive(Debug, Default)]
+struct Usage(Mutex<HashMap<ArgumentType, Vec<(ArgumentType, u32)>>>);
+
+impl Usage {
+    fn record(&self, range: ArgumentType, ty: ArgumentType, num: u32) {
+        self.0
+            .lock()
+            .unwrap()
+            .entry(range)
+            .or_default()
+            .push((ty, num));
+    }
+
+    fn requests(&self, range: ArgumentType) -> Vec<(ArgumentType, u32)> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(&range)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl Clone for Usage {
+    fn clone(&self) -> Self {
+        Usage(Mutex::new(self.0.lock().unwrap().clone()))
+    }
+}
+
+impl<F: PartialEq> PartialEq for Alphas<F> {
+    fn eq(&self, other: &Self) -> bool {
+        // the usage does not matter
+        self.next_power == other.next_power
+            && self.mapping == other.mapping
+            && self.declared == other.declared
+            && self.alphas == other.alphas
+    }
 }
 
 impl<F: Field> Alphas<F> {
     /// Registers a new [ArgumentType],
     /// associating it with a number `powers` of powers of alpha.
     /// This function will panic if you register the same type twice.
+    /// Arguments that are not gates also declare that they use exactly `powers` powers of alpha
+    /// (see [Self::declare]).
     pub fn register(&mut self, ty: ArgumentType, powers: u32) {
         if self.alphas.is_some() {
             panic!("you cannot register new constraints once initialized with a field element");
         }
 
+        if !matches!(ty, ArgumentType::Gate(_)) {
+            self.declared.insert(ty, powers);
+        }
+
         // gates are a special case, as we reuse the same power of alpha
         // across all of them (they're mutually exclusive)
         let ty = if matches!(ty, ArgumentType::Gate(_)) {
@@ -78,6 +138,32 @@ impl<F: Field> Alphas<F> {
             .expect("too many powers of alphas were registered");
     }
 
+    /// Declares that the argument `ty` uses exactly `powers` powers of alpha,
+    /// which is checked by [Self::assert_usage].
+    /// This is useful for gates, as they share a single registered range.
+    /// This function will panic if the range registered for `ty` is too small.
+    pub fn declare(&mut self, ty: ArgumentType, powers: u32) {
+        let range = if matches!(ty, ArgumentType::Gate(_)) {
+            ArgumentType::Gate(GateType::Zero)
+        } else {
+            ty
+        };
+
+        let registered = self
+            .mapping
+            .get(&range)
+            .unwrap_or_else(|| panic!("constraint {:?} was not registered", range))
+            .1;
+        if powers > registered {
+            panic!(
+                "{:?} declared {powers} powers of alpha, but only {registered} were registered",
+                ty
+            );
+        }
+
+        self.declared.insert(ty, powers);
+    }
+
     /// Returns a range of exponents, for a given [ArgumentType], upperbounded by `num`.
     /// Note that this function will panic if you did not register enough powers of alpha.
     pub fn get_exponents(
@@ -85,6 +171,7 @@ impl<F: Field> Alphas<F> {
         ty: ArgumentType,
         num: u32,
     ) -> MustConsumeIterator<Range<u32>, u32> {
+        let requested = ty;
         let ty = if matches!(ty, ArgumentType::Gate(_)) {
             ArgumentType::Gate(GateType::Zero)
         } else {
@@ -103,6 +190,8 @@ impl<F: Field> Alphas<F> {
             );
         }
 
+        self.usage.record(ty, requested, num);
+
         let start = range.0;
         let end = start + num;
 
@@ -112,8 +201,150 @@ impl<F: Field> Alphas<F> {
         }
     }
 
+    /// Returns the registered [ArgumentType]s along with their ranges of powers of alpha,
+    /// ordered by their first power.
+    /// Note that all the gates share the range registered for `ArgumentType::Gate(GateType::Zero)`.
+    pub fn ranges(&self) -> Vec<(ArgumentType, Range<u32>)> {
+        let mut ranges: Vec<_> = self
+            .mapping
+            .iter()
+            .map(|(ty, (start, powers))| (*ty, *start..start + powers))
+            .collect();
+        ranges.sort_by_key(|(_, range)| range.start);
+        ranges
+    }
+
+    /// Returns the parts of the registered ranges of powers of alpha (see [Self::ranges])
+    /// that were not requested via [Self::get_exponents] or [Self::get_alphas]
+    /// since the creation of `self`, or since it was instantiated.
+    pub fn unused_ranges(&self) -> Vec<(ArgumentType, Range<u32>)> {
+        self.ranges()
+            .into_iter()
+            .filter_map(|(ty, range)| {
+                let used = self
+                    .usage
+                    .requests(ty)
+                    .iter()
+                    .map(|(_, num)| *num)
+                    .max()
+                    .unwrap_or(0);
+                let unused = range.start + used..range.end;
+                (!unused.is_empty()).then(|| (ty, unused))
+            })
+            .collect()
+    }
+
+    /// Returns a copy of `self` that has not recorded any request of powers of alpha yet.
+    pub fn without_usage(&self) -> Self {
+        Alphas {
+            usage: Usage::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Adds the requests of powers of alpha recorded by `other` to the ones recorded by `self`,
+    /// for instance when part of the constraints of a proof were combined once with `other`.
+    pub fn extend_usage(&self, other: &Self) {
+        let requests = other.usage.0.lock().unwrap().clone();
+        let mut usage = self.usage.0.lock().unwrap();
+        for (range, requests) in requests {
+            usage.entry(range).or_default().extend(requests);
+        }
+    }
+
+    /// Panics if, since the creation of `self` (or since it was instantiated),
+    /// a registered range of powers of alpha was not requested,
+    /// a declared argument (see [Self::declare]) did not request powers of alpha,
+    /// or an argument requested a different number of powers than it declared.
+    pub fn assert_usage(&self) {
+        for (ty, _) in self.ranges() {
+            if self.usage.requests(ty).is_empty() {
+                panic!(
+                    "the powers of alpha registered for {:?} were never used",
+                    ty
+                );
+            }
+        }
+
+        let requests: Vec<_> = self
+            .ranges()
+            .into_iter()
+            .flat_map(|(ty, _)| self.usage.requests(ty))
+            .collect();
+
+        for (ty, declared) in &self.declared {
+            if !requests.iter().any(|(user, _)| user == ty) {
+                panic!(
+                    "{:?} declared {declared} powers of alpha, but never used them",
+                    ty
+                );
+            }
+        }
+
+        for (ty, num) in requests {
+            match self.declared.get(&ty) {
+                None => panic!(
+                    "{:?} used {num} powers of alpha, but did not declare how many it uses",
+                    ty
+                ),
+                Some(declared) if *declared != num => panic!(
+                    "{:?} declared {declared} powers of alpha, but used {num} of them",
+                    ty
+                ),
+                Some(_) => (),
+            }
+        }
+    }
+
+    /// Renders the registered ranges of powers of alpha as a Markdown table,
+    /// along with the arguments that requested them and the number of powers they requested.
+    pub fn table(&self) -> String {
+        let mut table = String::new();
+        writeln!(table, "| argument | powers of alpha | used by |").unwrap();
+        writeln!(table, "|----------|-----------------|---------|").unwrap();
+
+        for (ty, range) in self.ranges() {
+            let name = if matches!(ty, ArgumentType::Gate(_)) {
+                "gates".to_string()
+            } else {
+                format!("{:?}", ty)
+            };
+
+            // each argument is listed once, with the largest number of powers it requested
+            let mut users: Vec<(ArgumentType, u32)> = vec![];
+            for (user, num) in self.usage.requests(ty) {
+                match users.iter_mut().find(|(u, _)| *u == user) {
+                    Some((_, max)) => *max = (*max).max(num),
+                    None => users.push((user, num)),
+                }
+            }
+            let users = if users.is_empty() {
+                "(unused)".to_string()
+            } else {
+                users
+                    .iter()
+                    .map(|(user, num)| match user {
+                        ArgumentType::Gate(gate) => format!("{:?} ({num})", gate),
+                        _ => format!("{:?} ({num})", user),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+
+            writeln!(
+                table,
+                "| {name} | {}..{} | {users} |",
+                range.start, range.end
+            )
+            .unwrap();
+        }
+
+        table
+    }
+
     /// Instantiates the ranges with an actual field element `alpha`.
     /// Once you call this function, you cannot register new constraints via [Self::register].
+    /// This also resets the record of the powers of alpha requested so far.
     pub fn instantiate(&mut self, alpha: F) {
         let mut last_power = F::one();
         let mut alphas = Vec::with_capacity(self.next_power as usize);
@@ -123,6 +354,7 @@ impl<F: Field> Alphas<F> {
             alphas.push(last_power);
         }
         self.alphas = Some(alphas);
+        self.usage = Usage::default();
     }
 
     /// This function allows us to retrieve the powers of alpha, upperbounded by `num`
@@ -131,6 +363,7 @@ impl<F: Field> Alphas<F> {
         ty: ArgumentType,
         num: u32,
     ) -> MustConsumeIterator<Cloned<Take<Skip<Iter<F>>>>, F> {
+        let requested = ty;
         let ty = if matches!(ty, ArgumentType::Gate(_)) {
             ArgumentType::Gate(GateType::Zero)
         } else {
@@ -149,6 +382,8 @@ impl<F: Field> Alphas<F> {
             );
         }
 
+        self.usage.record(ty, requested, num);
+
         match &self.alphas {
             None => panic!("you must call instantiate with an actual field element first"),
             Some(alphas) => {
@@ -311,6 +546,157 @@ mod tests {
         assert_eq!(alphas.next(), Some(8.into()));
     }
 
+    #[test]
+    fn usage_of_alpha_powers() {
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(ArgumentType::Gate(GateType::VarBaseMul), 21);
+        alphas.register(ArgumentType::Permutation, 3);
+        alphas.register(ArgumentType::Lookup, 7);
+        alphas.instantiate(Fp::from(2));
+
+        assert_eq!(
+            alphas
+                .get_alphas(ArgumentType::Gate(GateType::Poseidon), 15)
+                .count(),
+            15
+        );
+        assert_eq!(
+            alphas
+                .get_alphas(ArgumentType::Gate(GateType::Generic), 2)
+                .count(),
+            2
+        );
+        assert_eq!(alphas.get_alphas(ArgumentType::Permutation, 3).count(), 3);
+
+        assert_eq!(
+            alphas.ranges(),
+            vec![
+                (ArgumentType::Gate(GateType::Zero), 0..21),
+                (ArgumentType::Permutation, 21..24),
+                (ArgumentType::Lookup, 24..31),
+            ]
+        );
+        assert_eq!(
+            alphas.unused_ranges(),
+            vec![
+                (ArgumentType::Gate(GateType::Zero), 15..21),
+                (ArgumentType::Lookup, 24..31),
+            ]
+        );
+        assert_eq!(
+            alphas.table(),
+            "| argument | powers of alpha | used by |\n\
+             |----------|-----------------|---------|\n\
+             | gates | 0..21 | Poseidon (15), Generic (2) |\n\
+             | Permutation | 21..24 | Permutation (3) |\n\
+             | Lookup | 24..31 | (unused) |\n"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn unused_alpha_powers() {
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(ArgumentType::Gate(GateType::Poseidon), 15);
+        alphas.register(ArgumentType::Permutation, 3);
+        assert_eq!(
+            alphas
+                .get_exponents(ArgumentType::Gate(GateType::Poseidon), 15)
+                .count(),
+            15
+        );
+        alphas.assert_usage();
+    }
+
+    #[test]
+    #[should_panic]
+    fn partially_used_alpha_powers() {
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(ArgumentType::Permutation, 3);
+        assert_eq!(
+            alphas.get_exponents(ArgumentType::Permutation, 2).count(),
+            2
+        );
+        alphas.assert_usage();
+    }
+
+    #[test]
+    fn declared_alpha_powers() {
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(ArgumentType::Gate(GateType::VarBaseMul), 21);
+        alphas.declare(ArgumentType::Gate(GateType::Poseidon), 15);
+        alphas.declare(ArgumentType::Gate(GateType::Generic), 2);
+        alphas.register(ArgumentType::Permutation, 3);
+        alphas.instantiate(Fp::from(2));
+
+        assert_eq!(
+            alphas
+                .get_alphas(ArgumentType::Gate(GateType::Poseidon), 15)
+                .count(),
+            15
+        );
+        assert_eq!(
+            alphas
+                .get_alphas(ArgumentType::Gate(GateType::Generic), 2)
+                .count(),
+            2
+        );
+        assert_eq!(alphas.get_alphas(ArgumentType::Permutation, 3).count(), 3);
+        alphas.assert_usage();
+    }
+
+    #[test]
+    #[should_panic]
+    fn gate_used_fewer_alpha_powers_than_declared() {
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(ArgumentType::Gate(GateType::VarBaseMul), 21);
+        alphas.declare(ArgumentType::Gate(GateType::Poseidon), 15);
+        assert_eq!(
+            alphas
+                .get_exponents(ArgumentType::Gate(GateType::Poseidon), 14)
+                .count(),
+            14
+        );
+        alphas.assert_usage();
+    }
+
+    #[test]
+    #[should_panic]
+    fn gate_used_undeclared_alpha_powers() {
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(ArgumentType::Gate(GateType::VarBaseMul), 21);
+        alphas.declare(ArgumentType::Gate(GateType::Poseidon), 15);
+        assert_eq!(
+            alphas
+                .get_exponents(ArgumentType::Gate(GateType::Poseidon), 15)
+                .count(),
+            15
+        );
+        assert_eq!(
+            alphas
+                .get_exponents(ArgumentType::Gate(GateType::CompleteAdd), 7)
+                .count(),
+            7
+        );
+        alphas.assert_usage();
+    }
+
+    #[test]
+    #[should_panic]
+    fn declared_gate_never_used_alpha_powers() {
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(ArgumentType::Gate(GateType::VarBaseMul), 21);
+        alphas.declare(ArgumentType::Gate(GateType::Poseidon), 15);
+        alphas.declare(ArgumentType::Gate(GateType::Generic), 2);
+        assert_eq!(
+            alphas
+                .get_exponents(ArgumentType::Gate(GateType::Poseidon), 15)
+                .count(),
+            15
+        );
+        alphas.assert_usage();
+    }
+
     // useful for the spec
 
     use crate::{
@@ -325,7 +711,9 @@ mod tests {
         let index = new_index_for_test(gates, 0);
         let (_linearization, powers_of_alpha) = expr_linearization(
             index.cs.domain.d1,
-            index.cs.chacha8.is_some(),
+            index.cs.zk_rows,
+            index.cs.width,
+            &index.gate_types,
             index
                 .cs
                 .lookup_constraint_system
@@ -1,16 +1,21 @@
 use crate::{
     circuits::{
-        gate::CircuitGate,
-        polynomials::generic::GenericGateSpec,
+        domains::EvaluationDomains,
+        expr::{l0_1, Constants, Environment},
+        gate::{CircuitGate, GateType},
+        polynomials::{generic::GenericGateSpec, permutation::ZK_ROWS},
         wires::{Wire, COLUMNS},
     },
     proof::ProverProof,
     prover_index::{testing::new_index_for_test, ProverIndex},
-    verifier::batch_verify,
+    verifier::{batch_verify, par_batch_verify},
     verifier_index::VerifierIndex,
 };
 use ark_ff::UniformRand;
-use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
+    UVPolynomial,
+};
 use array_init::array_init;
 use commitment_dlog::commitment::{b_poly_coefficients, CommitmentCurve};
 use groupmap::{BWParameters, GroupMap};
@@ -21,6 +26,7 @@ use oracle::{
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
 use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
 
 use o1_utils::math;
 
@@ -85,7 +91,7 @@ impl BenchmarkCtx {
         let rng = &mut StdRng::from_seed([0u8; 32]);
 
         // create witness
-        let witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![1u32.into(); CIRCUIT_SIZE]);
+        let witness: Vec<Vec<Fp>> = vec![vec![1u32.into(); CIRCUIT_SIZE]; COLUMNS];
 
         // previous opening for recursion
         let prev = {
@@ -115,7 +121,104 @@ impl BenchmarkCtx {
             .iter()
             .map(|proof| (&self.verifier_index, proof))
             .collect();
-        batch_verify::<Affine, BaseSponge, ScalarSponge>(&self.group_map, &batch).unwrap();
+        batch_verify::<Affine, BaseSponge, ScalarSponge, _>(&self.group_map, &batch).unwrap();
+    }
+
+    /// Same as [BenchmarkCtx::batch_verification], with the per-proof work done in parallel
+    pub fn par_batch_verification(&self, batch: Vec<ProverProof<Affine>>) {
+        let batch: Vec<_> = batch
+            .iter()
+            .map(|proof| (&self.verifier_index, proof))
+            .collect();
+        par_batch_verify::<Affine, BaseSponge, ScalarSponge, _>(&self.group_map, &batch).unwrap();
+    }
+}
+
+/// A context to benchmark the evaluation of constraints over the domains of a circuit.
+/// The columns of the circuit are filled with random values.
+pub struct ConstraintsEvaluationCtx {
+    domain: EvaluationDomains<Fp>,
+    witness: Vec<Evaluations<Fp, D<Fp>>>,
+    coefficient: Vec<Evaluations<Fp, D<Fp>>>,
+    z: Evaluations<Fp, D<Fp>>,
+    vanishes_on_last_4_rows: Evaluations<Fp, D<Fp>>,
+    index: HashMap<GateType, Evaluations<Fp, D<Fp>>>,
+    challenges: [Fp; 4],
+    mds: Vec<Vec<Fp>>,
+}
+
+impl ConstraintsEvaluationCtx {
+    /// Creates a context for a circuit of `n` rows.
+    pub fn new(n: usize) -> Self {
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let domain = EvaluationDomains::<Fp>::create(n).unwrap();
+        let mut random = |d: D<Fp>| {
+            let evals = (0..d.size()).map(|_| Fp::rand(rng)).collect();
+            Evaluations::from_vec_and_domain(evals, d)
+        };
+
+        let witness = (0..COLUMNS).map(|_| random(domain.d8)).collect();
+        let coefficient = (0..COLUMNS).map(|_| random(domain.d8)).collect();
+        let z = random(domain.d8);
+        let vanishes_on_last_4_rows = random(domain.d8);
+        let index = [
+            GateType::Poseidon,
+            GateType::CompleteAdd,
+            GateType::VarBaseMul,
+            GateType::EndoMul,
+            GateType::EndoMulScalar,
+            GateType::ChaCha0,
+            GateType::ChaCha1,
+            GateType::ChaCha2,
+            GateType::ChaChaFinal,
+        ]
+        .into_iter()
+        .map(|typ| {
+            // the complete addition selector is only evaluated over d4
+            let d = if typ == GateType::CompleteAdd {
+                domain.d4
+            } else {
+                domain.d8
+            };
+            (typ, random(d))
+        })
+        .collect();
+        let challenges = array_init(|_| Fp::rand(rng));
+
+        ConstraintsEvaluationCtx {
+            domain,
+            witness,
+            coefficient,
+            z,
+            vanishes_on_last_4_rows,
+            index,
+            challenges,
+            mds: oracle::pasta::fp_kimchi::params().mds,
+        }
+    }
+
+    /// Returns the environment to evaluate constraints against.
+    pub fn env(&self) -> Environment<Fp> {
+        let [alpha, beta, gamma, endo_coefficient] = self.challenges;
+        Environment {
+            constants: Constants {
+                alpha,
+                beta,
+                gamma,
+                joint_combiner: None,
+                endo_coefficient,
+                mds: self.mds.clone(),
+                zk_rows: ZK_ROWS,
+            },
+            witness: &self.witness,
+            coefficient: &self.coefficient,
+            vanishes_on_last_4_rows: &self.vanishes_on_last_4_rows,
+            z: &self.z,
+            l0_1: l0_1(self.domain.d1),
+            domain: self.domain,
+            index: self.index.iter().map(|(typ, e)| (*typ, e)).collect(),
+            lookup: None,
+        }
     }
 }
 
@@ -4,9 +4,12 @@ use crate::{
         domain_constant_evaluation::DomainConstantEvaluations,
         domains::EvaluationDomains,
         gate::{CircuitGate, GateType},
-        lookup::{index::LookupConstraintSystem, tables::LookupTable},
+        lookup::{index::LookupConstraintSystem, lookups::LookupInfo, tables::LookupTable},
         polynomial::{WitnessEvals, WitnessOverDomains, WitnessShifts},
-        polynomials::permutation::ZK_ROWS,
+        polynomials::{
+            generic::GENERIC_REGISTERS,
+            permutation::{MAX_ZK_ROWS, ZK_ROWS},
+        },
         wires::*,
     },
     error::SetupError,
@@ -23,6 +26,7 @@ use once_cell::sync::OnceCell;
 use oracle::poseidon::ArithmeticSpongeParams;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 
 //
@@ -42,18 +46,21 @@ pub struct ConstraintSystem<F: FftField> {
     /// circuit gates
     #[serde(bound = "CircuitGate<F>: Serialize + DeserializeOwned")]
     pub gates: Vec<CircuitGate<F>>,
+    /// number of rows at the end of the domain reserved for zero-knowledge
+    #[serde(default = "default_zk_rows")]
+    pub zk_rows: u64,
+    /// whether the proofs hide the witness
+    #[serde(default)]
+    pub zk_mode: ZkMode,
+    /// number of witness columns and of permuted columns
+    #[serde(default)]
+    pub width: Width,
 
     // Polynomials over the monomial base
     // ----------------------------------
     /// permutation polynomial array
-    #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
-    pub sigmam: [DP<F>; PERMUTS],
-
-    // Coefficient polynomials. These define constant that gates can use as they like.
-    // ---------------------------------------
-    /// coefficients polynomials in evaluation form
-    #[serde_as(as = "[o1_utils::serialization::SerdeAs; COLUMNS]")]
-    pub coefficients8: [E<F, D<F>>; COLUMNS],
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub sigmam: Vec<DP<F>>,
 
     // Generic constraint selector polynomials
     // ---------------------------------------
@@ -66,66 +73,125 @@ pub struct ConstraintSystem<F: FftField> {
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub psm: DP<F>,
 
-    // Generic constraint selector polynomials
-    // ---------------------------------------
-    /// multiplication evaluations over domain.d4
-    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
-    pub generic4: E<F, D<F>>,
-
     // permutation polynomials
     // -----------------------
     /// permutation polynomial array evaluations over domain d1
-    #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
-    pub sigmal1: [E<F, D<F>>; PERMUTS],
-    /// permutation polynomial array evaluations over domain d8
-    #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
-    pub sigmal8: [E<F, D<F>>; PERMUTS],
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub sigmal1: Vec<E<F, D<F>>>,
     /// SID polynomial
     #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
     pub sid: Vec<F>,
 
-    // Poseidon selector polynomials
-    // -----------------------------
-    /// poseidon selector over domain.d8
+    /// wire coordinate shifts
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub shift: Vec<F>,
+    /// coefficient for the group endomorphism
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    pub endo: F,
+
+    /// random oracle argument parameters
+    #[serde(skip)]
+    pub fr_sponge_params: ArithmeticSpongeParams<F>,
+
+    /// lookup constraint system
+    #[serde(bound = "LookupConstraintSystem<F>: Serialize + DeserializeOwned")]
+    pub lookup_constraint_system: Option<LookupConstraintSystem<F>>,
+
+    /// precomputes
+    #[serde(skip)]
+    precomputations: OnceCell<Arc<DomainConstantEvaluations<F>>>,
+
+    /// evaluations of the index polynomials over the larger domains,
+    /// computed the first time they are needed (see [ConstraintSystem::column_evaluations])
+    #[serde(skip)]
+    column_evaluations: OnceCell<Arc<ColumnEvaluations<F>>>,
+}
+
+/// The evaluations of the index polynomials over the domains $d_4$ and $d_8$, used by the prover.
+/// Each of them takes 4 or 8 times the size of the circuit,
+/// so they are not part of the serialized [ConstraintSystem],
+/// and can be recomputed from the gates, argument by argument, instead of being kept in memory
+/// (see [MemoryMode](crate::prover_index::MemoryMode)).
+#[derive(Clone, Debug)]
+pub struct ColumnEvaluations<F: FftField> {
+    /// coefficients polynomials in evaluation form, one per coefficient column of the circuit
+    /// (see [Width::coefficients])
+    pub coefficients8: Vec<E<F, D<F>>>,
+
+    /// generic selector evaluations over domain.d4
+    pub generic4: E<F, D<F>>,
+
+    /// permutation polynomial array evaluations over the domain of the permutation argument
+    /// (see [ConstraintSystem::permutation_domain])
+    pub sigmal8: Vec<E<F, D<F>>>,
+
+    /// poseidon selector over domain.d8
     pub ps8: E<F, D<F>>,
 
-    // ECC arithmetic selector polynomials
-    // -----------------------------------
     /// EC point addition selector evaluations w over domain.d4
-    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub complete_addl4: E<F, D<F>>,
     /// scalar multiplication selector evaluations over domain.d8
-    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub mull8: E<F, D<F>>,
     /// endoscalar multiplication selector evaluations over domain.d8
-    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub emull: E<F, D<F>>,
     /// ChaCha indexes
-    #[serde_as(as = "Option<[o1_utils::serialization::SerdeAs; 4]>")]
     pub chacha8: Option<[E<F, D<F>>; 4]>,
     /// EC point addition selector evaluations w over domain.d8
-    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub endomul_scalar8: E<F, D<F>>,
+}
 
-    /// wire coordinate shifts
-    #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
-    pub shift: [F; PERMUTS],
-    /// coefficient for the group endomorphism
-    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
-    pub endo: F,
+/// Whether the proofs created for a [ConstraintSystem] hide the witness.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ZkMode {
+    /// The last `zk_rows` rows of the domain are reserved for zero-knowledge:
+    /// they are randomized in the witness, the permutation and the lookup polynomials,
+    /// and the commitments are blinded.
+    ZeroKnowledge,
+    /// No row is reserved for zero-knowledge, so the circuit can use the whole domain
+    /// (but for its last row if it uses lookups), and the commitments are not blinded.
+    /// This is only sound to use when the witness is public:
+    /// the proofs still convince the verifier, but might reveal the witness.
+    /// Note that the opening proof keeps its own randomness,
+    /// which prevents its group elements from being the point at infinity.
+    NonHiding,
+}
 
-    /// random oracle argument parameters
-    #[serde(skip)]
-    pub fr_sponge_params: ArithmeticSpongeParams<F>,
+impl Default for ZkMode {
+    fn default() -> Self {
+        ZkMode::ZeroKnowledge
+    }
+}
 
-    /// lookup constraint system
-    #[serde(bound = "LookupConstraintSystem<F>: Serialize + DeserializeOwned")]
-    pub lookup_constraint_system: Option<LookupConstraintSystem<F>>,
+/// How the domain and the columns of a [ConstraintSystem] are laid out (see [ConstraintSystem::create_with_configuration]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DomainConfiguration {
+    /// Whether the proofs hide the witness.
+    pub zk_mode: ZkMode,
+    /// The number of rows at the end of the domain reserved for zero-knowledge in the [ZkMode::ZeroKnowledge] mode.
+    /// The proofs only hide the witness with at least [ZK_ROWS] of them.
+    /// It is ignored in the [ZkMode::NonHiding] mode, which reserves no row.
+    pub zk_rows: u64,
+    /// The minimum size of the domain.
+    /// Forcing a domain larger than the circuit needs keeps the verifier index stable as the circuit grows.
+    pub min_domain_size: Option<usize>,
+    /// The number of witness columns of the circuit, and how many of them are permuted.
+    pub width: Width,
+}
 
-    /// precomputes
-    #[serde(skip)]
-    precomputations: OnceCell<Arc<DomainConstantEvaluations<F>>>,
+impl Default for DomainConfiguration {
+    fn default() -> Self {
+        DomainConfiguration {
+            zk_mode: ZkMode::default(),
+            zk_rows: ZK_ROWS,
+            min_domain_size: None,
+            width: Width::default(),
+        }
+    }
+}
+
+/// The number of zero-knowledge rows of the indexes serialized before it was configurable.
+pub(crate) fn default_zk_rows() -> u64 {
+    ZK_ROWS
 }
 
 // TODO: move Shifts, and permutation-related functions to the permutation module
@@ -134,45 +200,60 @@ pub struct ConstraintSystem<F: FftField> {
 /// It also caches the shifted powers of omega for optimization purposes.
 pub struct Shifts<F> {
     /// The coefficients `k` (in the Plonk paper) that create a coset when multiplied with the generator of our domain.
-    shifts: [F; PERMUTS],
+    shifts: Vec<F>,
     /// A matrix that maps all cells coordinates `{col, row}` to their shifted field element.
     /// For example the cell `{col:2, row:1}` will map to `omega * k2`,
     /// which lives in `map[2][1]`
-    map: [Vec<F>; PERMUTS],
+    map: Vec<Vec<F>>,
 }
 
 impl<F> Shifts<F>
 where
     F: FftField + SquareRootField,
 {
-    /// Generates the shifts for a given domain
+    /// Generates the `PERMUTS` shifts for a given domain
     pub fn new(domain: &D<F>) -> Self {
-        let mut shifts = [F::zero(); PERMUTS];
+        Self::with_count(domain, PERMUTS)
+    }
+
+    /// Generates `count` shifts for a given domain.
+    /// The shifts are sampled in the same order for any `count`,
+    /// so that the first `PERMUTS` ones are the ones returned by [Shifts::new].
+    pub fn with_count(domain: &D<F>, count: usize) -> Self {
+        let mut shifts = Vec::with_capacity(count);
 
         // first shift is the identity
-        shifts[0] = F::one();
+        if count > 0 {
+            shifts.push(F::one());
+        }
 
         // sample the other shifts
         let mut i: u32 = 7;
-        for idx in 1..(PERMUTS) {
-            let mut shift = Self::sample(domain, &mut i);
-            // they have to be distincts
-            while shifts.contains(&shift) {
-                shift = Self::sample(domain, &mut i);
+        while shifts.len() < count {
+            let shift = Self::sample(domain, &mut i);
+            // they have to create distinct cosets,
+            // which is the case if their quotient with every previous shift is not in the domain
+            let distinct = shifts.iter().all(|prev: &F| {
+                let quotient = shift * prev.inverse().expect("shifts are not zero");
+                !domain.evaluate_vanishing_polynomial(quotient).is_zero()
+            });
+            if distinct {
+                shifts.push(shift);
             }
-            shifts[idx] = shift;
         }
 
         // create a map of cells to their shifted value
-        let map: [Vec<F>; PERMUTS] =
-            array_init(|i| domain.elements().map(|elm| shifts[i] * elm).collect());
+        let map = shifts
+            .iter()
+            .map(|&shift| domain.elements().map(|elm| shift * elm).collect())
+            .collect();
 
         //
         Self { shifts, map }
     }
 
     /// retrieve the shifts
-    pub fn shifts(&self) -> &[F; PERMUTS] {
+    pub fn shifts(&self) -> &[F] {
         &self.shifts
     }
 
@@ -232,22 +313,76 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
 
     /// similar to create. but this fn creates a constraint system with a shared precomputation previously created elsewhere
     pub fn create_with_shared_precomputations(
+        gates: Vec<CircuitGate<F>>,
+        lookup_tables: Vec<LookupTable<F>>,
+        fr_sponge_params: ArithmeticSpongeParams<F>,
+        public: usize,
+        precomputations: Option<Arc<DomainConstantEvaluations<F>>>,
+    ) -> Result<Self, SetupError> {
+        ConstraintSystem::<F>::create_with_configuration(
+            gates,
+            lookup_tables,
+            fr_sponge_params,
+            public,
+            precomputations,
+            DomainConfiguration::default(),
+        )
+    }
+
+    /// similar to create_with_shared_precomputations, but with a custom zero-knowledge mode, number of zero-knowledge rows, minimum domain size and width.
+    /// The shared precomputations, if any, must have been created for the same domain and number of zero-knowledge rows,
+    /// otherwise [SetupError::Precomputations] is returned.
+    /// An unsupported width returns [SetupError::Width].
+    pub fn create_with_configuration(
         mut gates: Vec<CircuitGate<F>>,
         lookup_tables: Vec<LookupTable<F>>,
         fr_sponge_params: ArithmeticSpongeParams<F>,
         public: usize,
         precomputations: Option<Arc<DomainConstantEvaluations<F>>>,
+        configuration: DomainConfiguration,
     ) -> Result<Self, SetupError> {
         //~ 1. If the circuit is less than 2 gates, abort.
         // for some reason we need more than 1 gate for the circuit to work, see TODO below
         assert!(gates.len() > 1);
 
+        let zk_mode = configuration.zk_mode;
+        let zk_rows = match zk_mode {
+            ZkMode::ZeroKnowledge => configuration.zk_rows,
+            ZkMode::NonHiding => 0,
+        };
+        if zk_mode == ZkMode::ZeroKnowledge && (zk_rows == 0 || zk_rows > MAX_ZK_ROWS) {
+            return Err(SetupError::ZkRows(zk_rows, MAX_ZK_ROWS));
+        }
+
+        // the double generic gate, which every circuit can use, reads the first columns
+        let width = configuration.width;
+        if width.columns < 2 * GENERIC_REGISTERS
+            || width.permuts == 0
+            || width.permuts > width.columns
+            || width.permuts > COLUMNS
+        {
+            return Err(SetupError::Width(width));
+        }
+
         //~ 2. Create a domain for the circuit. That is,
         //~    compute the smallest subgroup of the field that
-        //~    has order greater or equal to `n + ZK_ROWS` elements.
-        let domain = EvaluationDomains::<F>::create(gates.len() + ZK_ROWS as usize)?;
+        //~    has order greater or equal to `n + zk_rows` elements,
+        //~    where `zk_rows` is the number of rows reserved for zero-knowledge (`ZK_ROWS` by default),
+        //~    and to the minimum domain size, if one is configured.
+        //~    In the non-hiding mode, no row is reserved for zero-knowledge,
+        //~    but the last row of the domain is left to a zero gate if the circuit uses lookups,
+        //~    as the lookup argument does not apply to it.
+        let reserved_rows = match zk_mode {
+            ZkMode::NonHiding if LookupInfo::<F>::create().lookup_used(&gates).is_some() => 1,
+            _ => zk_rows,
+        };
+        let domain_size = std::cmp::max(
+            gates.len() + reserved_rows as usize,
+            configuration.min_domain_size.unwrap_or(0),
+        );
+        let domain = EvaluationDomains::<F>::create(domain_size)?;
 
-        assert!(domain.d1.size > ZK_ROWS);
+        assert!(domain.d1.size > zk_rows);
 
         //~ 3. Pad the circuit: add zero gates to reach the domain size.
         let d1_size = domain.d1.size();
@@ -261,8 +396,8 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
             .collect();
         gates.append(&mut padding);
 
-        //~ 4. sample the `PERMUTS` shifts.
-        let shifts = Shifts::new(&domain.d1);
+        //~ 4. sample one shift per permuted column (`PERMUTS` of them by default).
+        let shifts = Shifts::with_count(&domain.d1, width.permuts);
 
         // Precomputations
         // ===============
@@ -272,32 +407,37 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         // Permutation
         // -----------
 
+        // with more than `PERMUTS` permuted columns, the permutation argument is computed over a larger domain
+        if width.permuts > PERMUTS {
+            D::<F>::new(16 * d1_size).ok_or(SetupError::DomainCreation(
+                "construction of the domain of the permutation argument did not work as intended",
+            ))?;
+        }
+
         // compute permutation polynomials
-        let mut sigmal1: [Vec<F>; PERMUTS] =
-            array_init(|_| vec![F::zero(); domain.d1.size as usize]);
+        let mut sigmal1 = vec![vec![F::zero(); d1_size]; width.permuts];
 
         for (row, gate) in gates.iter().enumerate() {
-            for (cell, sigma) in gate.wires.iter().zip(sigmal1.iter_mut()) {
+            for (col, (cell, sigma)) in gate.wires.iter().zip(sigmal1.iter_mut()).enumerate() {
+                if cell.col >= width.permuts {
+                    return Err(SetupError::ConstraintSystem(format!(
+                        "the cell of row {row} and column {col} is wired to column {}, but only the first {} columns are permuted",
+                        cell.col, width.permuts
+                    )));
+                }
                 sigma[row] = shifts.cell_to_field(cell);
             }
         }
 
-        let sigmal1: [_; PERMUTS] = {
-            let [s0, s1, s2, s3, s4, s5, s6] = sigmal1;
-            [
-                E::<F, D<F>>::from_vec_and_domain(s0, domain.d1),
-                E::<F, D<F>>::from_vec_and_domain(s1, domain.d1),
-                E::<F, D<F>>::from_vec_and_domain(s2, domain.d1),
-                E::<F, D<F>>::from_vec_and_domain(s3, domain.d1),
-                E::<F, D<F>>::from_vec_and_domain(s4, domain.d1),
-                E::<F, D<F>>::from_vec_and_domain(s5, domain.d1),
-                E::<F, D<F>>::from_vec_and_domain(s6, domain.d1),
-            ]
-        };
-
-        let sigmam: [DP<F>; PERMUTS] = array_init(|i| sigmal1[i].clone().interpolate());
+        let sigmal1: Vec<_> = sigmal1
+            .into_iter()
+            .map(|sigma| E::<F, D<F>>::from_vec_and_domain(sigma, domain.d1))
+            .collect();
 
-        let sigmal8 = array_init(|i| sigmam[i].evaluate_over_domain_by_ref(domain.d8));
+        let sigmam: Vec<DP<F>> = sigmal1
+            .iter()
+            .map(|sigma| sigma.clone().interpolate())
+            .collect();
 
         // Gates
         // -----
@@ -306,6 +446,8 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         // the polynomial that evaluates to 1 at $g^i$
         // where $i$ is the row where a gate is active.
         // Note: gates must be mutually exclusive.
+        // The evaluations of the other selectors are computed on demand
+        // (see [ConstraintSystem::compute_column_evaluations]).
 
         // poseidon gate
         let psm = E::<F, D<F>>::from_vec_and_domain(
@@ -313,42 +455,6 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
             domain.d1,
         )
         .interpolate();
-        let ps8 = psm.evaluate_over_domain_by_ref(domain.d8);
-
-        // ECC gates
-        let complete_addm = E::<F, D<F>>::from_vec_and_domain(
-            gates
-                .iter()
-                .map(|gate| F::from((gate.typ == GateType::CompleteAdd) as u64))
-                .collect(),
-            domain.d1,
-        )
-        .interpolate();
-        let complete_addl4 = complete_addm.evaluate_over_domain_by_ref(domain.d4);
-
-        let mulm = E::<F, D<F>>::from_vec_and_domain(
-            gates.iter().map(|gate| gate.vbmul()).collect(),
-            domain.d1,
-        )
-        .interpolate();
-        let mull8 = mulm.evaluate_over_domain_by_ref(domain.d8);
-
-        let emulm = E::<F, D<F>>::from_vec_and_domain(
-            gates.iter().map(|gate| gate.endomul()).collect(),
-            domain.d1,
-        )
-        .interpolate();
-        let emull = emulm.evaluate_over_domain_by_ref(domain.d8);
-
-        let endomul_scalarm = E::<F, D<F>>::from_vec_and_domain(
-            gates
-                .iter()
-                .map(|gate| F::from((gate.typ == GateType::EndoMulScalar) as u64))
-                .collect(),
-            domain.d1,
-        )
-        .interpolate();
-        let endomul_scalar8 = endomul_scalarm.evaluate_over_domain_by_ref(domain.d8);
 
         // double generic gate
         let genericm = E::<F, D<F>>::from_vec_and_domain(
@@ -365,61 +471,12 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
             domain.d1,
         )
         .interpolate();
-        let generic4 = genericm.evaluate_over_domain_by_ref(domain.d4);
-
-        // chacha gate
-        let chacha8 = {
-            use GateType::*;
-            let has_chacha_gate = gates
-                .iter()
-                .any(|gate| matches!(gate.typ, ChaCha0 | ChaCha1 | ChaCha2 | ChaChaFinal));
-            if !has_chacha_gate {
-                None
-            } else {
-                let a: [_; 4] = array_init(|i| {
-                    let g = match i {
-                        0 => ChaCha0,
-                        1 => ChaCha1,
-                        2 => ChaCha2,
-                        3 => ChaChaFinal,
-                        _ => panic!("Invalid index"),
-                    };
-                    E::<F, D<F>>::from_vec_and_domain(
-                        gates
-                            .iter()
-                            .map(|gate| if gate.typ == g { F::one() } else { F::zero() })
-                            .collect(),
-                        domain.d1,
-                    )
-                    .interpolate()
-                    .evaluate_over_domain(domain.d8)
-                });
-                Some(a)
-            }
-        };
-
-        //
-        // Coefficient
-        // -----------
-        //
-
-        // coefficient polynomial
-        let coefficientsm: [_; COLUMNS] = array_init(|i| {
-            let padded = gates
-                .iter()
-                .map(|gate| gate.coeffs.get(i).cloned().unwrap_or_else(F::zero))
-                .collect();
-            let eval = E::from_vec_and_domain(padded, domain.d1);
-            eval.interpolate()
-        });
-        // TODO: This doesn't need to be degree 8 but that would require some changes in expr
-        let coefficients8 = array_init(|i| coefficientsm[i].evaluate_over_domain_by_ref(domain.d8));
 
         //
         // Lookup
         // ------
         let lookup_constraint_system =
-            LookupConstraintSystem::create(&gates, lookup_tables, &domain)
+            LookupConstraintSystem::create(&gates, lookup_tables, &domain, zk_rows)
                 .map_err(|e| SetupError::ConstraintSystem(e.to_string()))?;
 
         let sid = shifts.map[0].clone();
@@ -430,32 +487,33 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         let domain_constant_evaluation = OnceCell::new();
 
         let constraints = ConstraintSystem {
-            chacha8,
-            endomul_scalar8,
             domain,
             public,
             sid,
             sigmal1,
-            sigmal8,
             sigmam,
             genericm,
-            generic4,
-            coefficients8,
-            ps8,
             psm,
-            complete_addl4,
-            mull8,
-            emull,
             gates,
+            zk_rows,
+            zk_mode,
+            width,
             shift: shifts.shifts,
             endo,
             fr_sponge_params,
             lookup_constraint_system,
             precomputations: domain_constant_evaluation,
+            column_evaluations: OnceCell::new(),
         };
 
         match precomputations {
             Some(t) => {
+                if t.poly_x_d1.domain() != domain.d8 {
+                    return Err(SetupError::Precomputations("domain"));
+                }
+                if t.zk_rows != zk_rows {
+                    return Err(SetupError::Precomputations("number of zero-knowledge rows"));
+                }
                 constraints.set_precomputations(t);
             }
             None => {
@@ -467,8 +525,9 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     }
 
     pub fn precomputations(&self) -> &Arc<DomainConstantEvaluations<F>> {
-        self.precomputations
-            .get_or_init(|| Arc::new(DomainConstantEvaluations::create(self.domain).unwrap()))
+        self.precomputations.get_or_init(|| {
+            Arc::new(DomainConstantEvaluations::create(self.domain, self.zk_rows).unwrap())
+        })
     }
 
     pub fn set_precomputations(&self, precomputations: Arc<DomainConstantEvaluations<F>>) {
@@ -477,31 +536,152 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
             .expect("Precomputation has been set before");
     }
 
+    /// Returns the evaluations of the index polynomials over the larger domains,
+    /// computing them the first time they are needed and keeping them for the next calls.
+    pub fn column_evaluations(&self) -> &Arc<ColumnEvaluations<F>> {
+        self.column_evaluations
+            .get_or_init(|| Arc::new(self.compute_column_evaluations()))
+    }
+
+    /// Drops the evaluations kept by [ConstraintSystem::column_evaluations], if any.
+    pub fn clear_column_evaluations(&mut self) {
+        self.column_evaluations.take();
+    }
+
+    /// Computes the evaluations of the index polynomials over the larger domains,
+    /// without keeping them in the constraint system.
+    pub fn compute_column_evaluations(&self) -> ColumnEvaluations<F> {
+        self.evaluate_columns(self.domain.d4, self.domain.d8, self.permutation_domain())
+    }
+
+    /// Returns the values of the index polynomials on the rows of the circuit,
+    /// laid out as [ColumnEvaluations] but over $d_1$.
+    /// This is all the linearization needs, as it only reads the index polynomials on the rows.
+    pub fn row_evaluations(&self) -> ColumnEvaluations<F> {
+        self.evaluate_columns(self.domain.d1, self.domain.d1, self.domain.d1)
+    }
+
+    /// Returns the domain over which the permutation argument is computed by the prover:
+    /// $d_8$, unless more than [PERMUTS] columns are permuted,
+    /// in which case the degree of the argument requires a domain of size $16n$.
+    pub fn permutation_domain(&self) -> D<F> {
+        if self.width.permuts <= PERMUTS {
+            self.domain.d8
+        } else {
+            D::<F>::new(16 * self.domain.d1.size())
+                .expect("the domain of the permutation argument is checked at creation")
+        }
+    }
+
+    /// Evaluates the index polynomials over `d4`, `d8` and `dp`,
+    /// which stand for the domains $d_4$ and $d_8$ and the domain of the permutation argument of [ColumnEvaluations].
+    fn evaluate_columns(&self, d4: D<F>, d8: D<F>, dp: D<F>) -> ColumnEvaluations<F> {
+        use GateType::*;
+
+        // chacha gate
+        let has_chacha_gate = self
+            .gates
+            .iter()
+            .any(|gate| matches!(gate.typ, ChaCha0 | ChaCha1 | ChaCha2 | ChaChaFinal));
+        let chacha8 = if has_chacha_gate {
+            Some(array_init(|i| {
+                self.selector_evaluations([ChaCha0, ChaCha1, ChaCha2, ChaChaFinal][i], d8)
+            }))
+        } else {
+            None
+        };
+
+        ColumnEvaluations {
+            // TODO: This doesn't need to be degree 8 but that would require some changes in expr
+            coefficients8: (0..self.width.coefficients())
+                .map(|i| self.coefficient_evaluations(i, d8))
+                .collect(),
+            generic4: self.selector_evaluations(Generic, d4),
+            sigmal8: self.sigma_evaluations(dp),
+            ps8: self.selector_evaluations(Poseidon, d8),
+            complete_addl4: self.selector_evaluations(CompleteAdd, d4),
+            mull8: self.selector_evaluations(VarBaseMul, d8),
+            emull: self.selector_evaluations(EndoMul, d8),
+            chacha8,
+            endomul_scalar8: self.selector_evaluations(EndoMulScalar, d8),
+        }
+    }
+
+    /// Evaluates over `domain` the polynomial taking the value `f(gate)` on the row of each gate.
+    fn evaluate_rows(&self, f: impl Fn(&CircuitGate<F>) -> F, domain: D<F>) -> E<F, D<F>> {
+        let rows =
+            E::<F, D<F>>::from_vec_and_domain(self.gates.iter().map(f).collect(), self.domain.d1);
+        if domain == self.domain.d1 {
+            rows
+        } else {
+            rows.interpolate().evaluate_over_domain(domain)
+        }
+    }
+
+    /// Evaluates the `i`-th coefficient polynomial over `domain`.
+    pub fn coefficient_evaluations(&self, i: usize, domain: D<F>) -> E<F, D<F>> {
+        self.evaluate_rows(
+            |gate| gate.coeffs.get(i).cloned().unwrap_or_else(F::zero),
+            domain,
+        )
+    }
+
+    /// Evaluates the selector polynomial of a gate type over `domain`.
+    pub fn selector_evaluations(&self, gate_type: GateType, domain: D<F>) -> E<F, D<F>> {
+        match gate_type {
+            // these selectors are kept in coefficient form
+            GateType::Generic => self.genericm.evaluate_over_domain_by_ref(domain),
+            GateType::Poseidon => self.psm.evaluate_over_domain_by_ref(domain),
+            _ => self.evaluate_rows(|gate| F::from((gate.typ == gate_type) as u64), domain),
+        }
+    }
+
+    /// Evaluates the permutation polynomials over `domain`.
+    pub fn sigma_evaluations(&self, domain: D<F>) -> Vec<E<F, D<F>>> {
+        self.sigmam
+            .iter()
+            .map(|sigma| sigma.evaluate_over_domain_by_ref(domain))
+            .collect()
+    }
+
+    /// Whether the evaluations returned by [ConstraintSystem::column_evaluations] are kept.
+    #[cfg(test)]
+    pub(crate) fn has_column_evaluations(&self) -> bool {
+        self.column_evaluations.get().is_some()
+    }
+
+    /// Returns the set of gate types used in the circuit.
+    pub fn gate_types(&self) -> BTreeSet<GateType> {
+        self.gates.iter().map(|gate| gate.typ).collect()
+    }
+
     /// This function verifies the consistency of the wire
     /// assignements (witness) against the constraints
-    ///     witness: wire assignement witness
+    ///     witness: wire assignement witness, one column per column of the circuit at least
     ///     RETURN: verification status
-    pub fn verify(&self, witness: &[Vec<F>; COLUMNS], public: &[F]) -> Result<(), GateError> {
-        // pad the witness
-        let pad = vec![F::zero(); self.domain.d1.size as usize - witness[0].len()];
-        let witness: [Vec<F>; COLUMNS] = array_init(|i| {
-            let mut w = witness[i].to_vec();
-            w.extend_from_slice(&pad);
-            w
-        });
+    pub fn verify(&self, witness: &[Vec<F>], public: &[F]) -> Result<(), GateError> {
+        // pad the witness, with zero columns up to the default width read by the gates
+        let d1_size = self.domain.d1.size as usize;
+        let witness: Vec<Vec<F>> = (0..std::cmp::max(witness.len(), COLUMNS))
+            .map(|i| {
+                let mut w = witness.get(i).cloned().unwrap_or_default();
+                w.resize(d1_size, F::zero());
+                w
+            })
+            .collect();
 
         // check each rows' wiring
         for (row, gate) in self.gates.iter().enumerate() {
             // check if wires are connected
-            for col in 0..PERMUTS {
+            for col in 0..self.width.permuts {
                 let wire = gate.wires[col];
 
-                if wire.col >= PERMUTS {
+                if wire.col >= self.width.permuts {
                     return Err(GateError::Custom {
                         row,
                         err: format!(
                             "a wire can only be connected to the first {} columns",
-                            PERMUTS
+                            self.width.permuts
                         ),
                     });
                 }
@@ -532,26 +712,32 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     }
 
     /// evaluate witness polynomials over domains
-    pub fn evaluate(&self, w: &[DP<F>; COLUMNS], z: &DP<F>) -> WitnessOverDomains<F> {
+    /// (one per column of the circuit, the others are not read)
+    pub fn evaluate(&self, w: &[DP<F>], z: &DP<F>) -> WitnessOverDomains<F> {
         // compute shifted witness polynomials
-        let w8: [E<F, D<F>>; COLUMNS] =
-            array_init(|i| w[i].evaluate_over_domain_by_ref(self.domain.d8));
+        let w8: Vec<E<F, D<F>>> = w[..self.width.columns]
+            .iter()
+            .map(|w| w.evaluate_over_domain_by_ref(self.domain.d8))
+            .collect();
         let z8 = z.evaluate_over_domain_by_ref(self.domain.d8);
 
-        let w4: [E<F, D<F>>; COLUMNS] = array_init(|i| {
-            E::<F, D<F>>::from_vec_and_domain(
-                (0..self.domain.d4.size)
-                    .map(|j| w8[i].evals[2 * j as usize])
-                    .collect(),
-                self.domain.d4,
-            )
-        });
+        let w4: Vec<E<F, D<F>>> = w8
+            .iter()
+            .map(|w8| {
+                E::<F, D<F>>::from_vec_and_domain(
+                    (0..self.domain.d4.size)
+                        .map(|j| w8.evals[2 * j as usize])
+                        .collect(),
+                    self.domain.d4,
+                )
+            })
+            .collect();
         let z4 = DP::<F>::zero().evaluate_over_domain_by_ref(D::<F>::new(1).unwrap());
 
         WitnessOverDomains {
             d4: WitnessShifts {
                 next: WitnessEvals {
-                    w: array_init(|i| w4[i].shift(4)),
+                    w: w4.iter().map(|w4| w4.shift(4)).collect(),
                     // TODO(mimoo): change z to an Option? Or maybe not, we might actually need this dummy evaluation in the aggregated evaluation proof
                     z: z4.clone(), // dummy evaluation
                 },
@@ -562,7 +748,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
             },
             d8: WitnessShifts {
                 next: WitnessEvals {
-                    w: array_init(|i| w8[i].shift(8)),
+                    w: w8.iter().map(|w8| w8.shift(8)).collect(),
                     z: z8.shift(8),
                 },
                 this: WitnessEvals { w: w8, z: z8 },
@@ -594,4 +780,24 @@ pub mod tests {
             Self::for_testing(fp_sponge_params, gates)
         }
     }
+
+    #[test]
+    fn test_shifts_with_count() {
+        let domain = D::<Fp>::new(1 << 4).unwrap();
+        let shifts = Shifts::new(&domain);
+        let wide = Shifts::with_count(&domain, 3 * PERMUTS);
+
+        // the first shifts do not depend on the count
+        assert_eq!(shifts.shifts(), &wide.shifts()[..PERMUTS]);
+
+        // all the shifts create distinct cosets
+        let wide = wide.shifts();
+        assert_eq!(wide.len(), 3 * PERMUTS);
+        for (i, a) in wide.iter().enumerate() {
+            for b in &wide[i + 1..] {
+                let quotient = *a / *b;
+                assert!(!domain.evaluate_vanishing_polynomial(quotient).is_zero());
+            }
+        }
+    }
 }
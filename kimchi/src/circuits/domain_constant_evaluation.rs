@@ -2,7 +2,6 @@
 
 use crate::circuits::domains::EvaluationDomains;
 use crate::circuits::polynomials::permutation::zk_polynomial;
-use crate::circuits::polynomials::permutation::ZK_ROWS;
 use ark_ff::FftField;
 use ark_poly::UVPolynomial;
 use ark_poly::{univariate::DensePolynomial as DP, Evaluations as E, Radix2EvaluationDomain as D};
@@ -25,7 +24,7 @@ pub struct DomainConstantEvaluations<F: FftField> {
     /// 0-th Lagrange evaluated over domain.d8
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub constant_1_d8: E<F, D<F>>,
-    /// the polynomial that vanishes on the last four rows
+    /// the polynomial that vanishes on the zero-knowledge rows and on the row preceding them
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub vanishes_on_last_4_rows: E<F, D<F>>,
     /// zero-knowledge polynomial over domain.d8
@@ -33,10 +32,12 @@ pub struct DomainConstantEvaluations<F: FftField> {
     pub zkpl: E<F, D<F>>,
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub zkpm: DP<F>,
+    /// the number of zero-knowledge rows these evaluations were created for
+    pub zk_rows: u64,
 }
 
 impl<F: FftField> DomainConstantEvaluations<F> {
-    pub fn create(domain: EvaluationDomains<F>) -> Option<Self> {
+    pub fn create(domain: EvaluationDomains<F>, zk_rows: u64) -> Option<Self> {
         let poly_x_d1 = DP::from_coefficients_slice(&[F::zero(), F::one()])
             .evaluate_over_domain_by_ref(domain.d8);
         let constant_1_d4 =
@@ -45,12 +46,11 @@ impl<F: FftField> DomainConstantEvaluations<F> {
             E::<F, D<F>>::from_vec_and_domain(vec![F::one(); domain.d8.size as usize], domain.d8);
 
         let vanishes_on_last_4_rows =
-            vanishes_on_last_4_rows(domain.d1).evaluate_over_domain(domain.d8);
+            vanishes_on_last_4_rows(domain.d1, zk_rows).evaluate_over_domain(domain.d8);
 
-        assert!(domain.d1.size > ZK_ROWS);
+        assert!(domain.d1.size > zk_rows);
 
-        // x^3 - x^2(w1+w2+w3) + x(w1w2+w1w3+w2w3) - w1w2w3
-        let zkpm = zk_polynomial(domain.d1);
+        let zkpm = zk_polynomial(domain.d1, zk_rows);
         let zkpl = zkpm.evaluate_over_domain_by_ref(domain.d8);
 
         Some(DomainConstantEvaluations {
@@ -60,6 +60,7 @@ impl<F: FftField> DomainConstantEvaluations<F> {
             vanishes_on_last_4_rows,
             zkpl,
             zkpm,
+            zk_rows,
         })
     }
 }
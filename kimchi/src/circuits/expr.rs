@@ -2,27 +2,31 @@ use crate::{
     circuits::{
         domains::EvaluationDomains,
         gate::{CurrOrNext, GateType},
-        polynomials::permutation::eval_vanishes_on_last_4_rows,
-        wires::COLUMNS,
+        polynomials::permutation::{eval_vanishes_on_last_4_rows, MAX_ZK_ROWS},
     },
     proof::ProofEvaluations,
 };
-use ark_ff::{FftField, Field, One, PrimeField, Zero};
+use ark_ff::{BigInteger, FftField, Field, One, PrimeField, Zero};
 use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
 };
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use itertools::Itertools;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use std::iter::FromIterator;
 use std::ops::{Add, AddAssign, Mul, Neg, Sub};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     ops::MulAssign,
 };
 use CurrOrNext::{Curr, Next};
 
+pub mod compiled;
+
 /// The collection of constants required to evaluate an `Expr`.
+#[derive(Clone)]
 pub struct Constants<F> {
     /// The challenge alpha from the PLONK IOP.
     pub alpha: F,
@@ -37,11 +41,14 @@ pub struct Constants<F> {
     pub endo_coefficient: F,
     /// The MDS matrix
     pub mds: Vec<Vec<F>>,
+    /// The number of rows at the end of the domain reserved for zero-knowledge
+    pub zk_rows: u64,
 }
 
 /// The polynomials specific to the lookup argument.
 ///
 /// All are evaluations over the D8 domain
+#[derive(Clone)]
 pub struct LookupEnvironment<'a, F: FftField> {
     /// The sorted lookup table polynomials.
     pub sorted: &'a Vec<Evaluations<F, D<F>>>,
@@ -59,10 +66,10 @@ pub struct LookupEnvironment<'a, F: FftField> {
 /// All are evaluations.
 pub struct Environment<'a, F: FftField> {
     /// The witness column polynomials
-    pub witness: &'a [Evaluations<F, D<F>>; COLUMNS],
+    pub witness: &'a [Evaluations<F, D<F>>],
     /// The coefficient column polynomials
-    pub coefficient: &'a [Evaluations<F, D<F>>; COLUMNS],
-    /// The polynomial which vanishes on the last 4 elements of the domain.
+    pub coefficient: &'a [Evaluations<F, D<F>>],
+    /// The polynomial which vanishes on the zero-knowledge rows and on the row preceding them.
     pub vanishes_on_last_4_rows: &'a Evaluations<F, D<F>>,
     /// The permutation aggregation polynomial.
     pub z: &'a Evaluations<F, D<F>>,
@@ -80,6 +87,11 @@ pub struct Environment<'a, F: FftField> {
 }
 
 impl<'a, F: FftField> Environment<'a, F> {
+    /// Returns the domain over which the evaluations of a column are expected.
+    pub(crate) fn column_domain(&self, col: &Column) -> D<F> {
+        get_domain(col.domain(), self)
+    }
+
     fn get_column(&self, col: &Column) -> Option<&'a Evaluations<F, D<F>>> {
         use Column::*;
         let lookup = self.lookup.as_ref();
@@ -152,6 +164,19 @@ impl Column {
             Column::Coefficient(i) => format!("c_{{{}}}", i),
         }
     }
+
+    fn text(&self) -> String {
+        match self {
+            Column::Witness(i) => format!("w{i}"),
+            Column::Z => "z".to_string(),
+            Column::LookupSorted(i) => format!("s{i}"),
+            Column::LookupAggreg => "a".to_string(),
+            Column::LookupTable => "t".to_string(),
+            Column::LookupKindIndex(i) => format!("k{i}"),
+            Column::Index(gate) => format!("{:?}", gate),
+            Column::Coefficient(i) => format!("c{i}"),
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
@@ -176,9 +201,38 @@ impl Variable {
             Next => format!("\\tilde{{{col}}}"),
         }
     }
+
+    fn text(&self) -> String {
+        let col = self.col.text();
+        match self.row {
+            Curr => col,
+            Next => format!("next({col})"),
+        }
+    }
+
+    /// The Rust code evaluating the variable, as [Variable::evaluate] does.
+    fn rust(&self) -> String {
+        let evals = format!("evals[{}]", self.row.shift());
+        let lookup =
+            format!("{evals}.lookup.as_ref().ok_or(\"Lookup should not have been used\")?");
+        use Column::*;
+        match self.col {
+            Witness(i) => format!("{evals}.w[{i}]"),
+            Z => format!("{evals}.z"),
+            LookupSorted(i) => format!("{lookup}.sorted[{i}]"),
+            LookupAggreg => format!("{lookup}.aggreg"),
+            LookupTable => format!("{lookup}.table"),
+            Index(GateType::Poseidon) => format!("{evals}.poseidon_selector"),
+            Index(GateType::Generic) => format!("{evals}.generic_selector"),
+            Coefficient(_) | LookupKindIndex(_) | Index(_) => {
+                "Err::<F, _>(\"Cannot get index evaluation (should have been linearized away)\")?"
+                    .to_string()
+            }
+        }
+    }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 /// An arithmetic expression over
 ///
 /// - the operations *, +, -, ^
@@ -328,7 +382,7 @@ impl Cache {
 }
 
 /// A binary operation
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Op2 {
     Add,
     Mul,
@@ -363,6 +417,8 @@ pub enum Expr<C> {
     Double(Box<Expr<C>>),
     Square(Box<Expr<C>>),
     BinOp(Op2, Box<Expr<C>>, Box<Expr<C>>),
+    /// The polynomial vanishing on the zero-knowledge rows and on the row preceding them
+    /// (the last 4 rows with the default number of zero-knowledge rows)
     VanishesOnLast4Rows,
     /// UnnormalizedLagrangeBasis(i) is
     /// (x^n - 1) / (x - omega^i)
@@ -374,7 +430,9 @@ pub enum Expr<C> {
 /// For efficiency of evaluation, we compile expressions to
 /// [reverse Polish notation](https://en.wikipedia.org/wiki/Reverse_Polish_notation)
 /// expressions, which are vectors of the below tokens.
+#[serde_as]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "F: CanonicalSerialize + CanonicalDeserialize")]
 pub enum PolishToken<F> {
     Alpha,
     Beta,
@@ -382,7 +440,7 @@ pub enum PolishToken<F> {
     JointCombiner,
     EndoCoefficient,
     Mds { row: usize, col: usize },
-    Literal(F),
+    Literal(#[serde_as(as = "o1_utils::serialization::SerdeAs")] F),
     Cell(Variable),
     Dup,
     Pow(u64),
@@ -445,7 +503,7 @@ impl<F: FftField> PolishToken<F> {
                 }
                 EndoCoefficient => stack.push(c.endo_coefficient),
                 Mds { row, col } => stack.push(c.mds[*row][*col]),
-                VanishesOnLast4Rows => stack.push(eval_vanishes_on_last_4_rows(d, pt)),
+                VanishesOnLast4Rows => stack.push(eval_vanishes_on_last_4_rows(d, c.zk_rows, pt)),
                 UnnormalizedLagrangeBasis(i) => stack.push(
                     d.evaluate_vanishing_polynomial(pt) / (pt - d.group_gen.pow(&[*i as u64])),
                 ),
@@ -506,12 +564,15 @@ impl<C> Expr<C> {
         Expr::Constant(c)
     }
 
-    fn degree(&self, d1_size: u64) -> u64 {
+    /// Returns an upper bound on the degree of the polynomial corresponding to the expression,
+    /// for a domain of size `d1_size` (columns are considered to be of degree `d1_size`).
+    pub fn degree(&self, d1_size: u64) -> u64 {
         use Expr::*;
         match self {
             Double(x) => x.degree(d1_size),
             Constant(_) => 0,
-            VanishesOnLast4Rows => 4,
+            // the number of zero-knowledge rows is only known when evaluating the expression
+            VanishesOnLast4Rows => MAX_ZK_ROWS + 1,
             UnnormalizedLagrangeBasis(_) => d1_size,
             Cell(_) => d1_size,
             Square(x) => 2 * x.degree(d1_size),
@@ -523,6 +584,38 @@ impl<C> Expr<C> {
             Cache(_, e) => e.degree(d1_size),
         }
     }
+
+    /// Returns the number of witness columns that the expression reads,
+    /// that is one more than the largest index of the witness columns it uses (or 0 if it uses none).
+    pub fn witness_columns(&self) -> usize {
+        use Expr::*;
+        match self {
+            Cell(Variable {
+                col: Column::Witness(i),
+                ..
+            }) => i + 1,
+            Double(x) | Square(x) | Pow(x, _) | Cache(_, x) => x.witness_columns(),
+            BinOp(_, x, y) => std::cmp::max(x.witness_columns(), y.witness_columns()),
+            Constant(_) | Cell(_) | VanishesOnLast4Rows | UnnormalizedLagrangeBasis(_) => 0,
+        }
+    }
+
+    /// Returns the largest degree that the expression can have for a domain of size `d1_size`,
+    /// given the domains over which the columns it uses are evaluated.
+    /// This is at most the size of the `d8` domain.
+    pub fn supported_degree(&self, d1_size: u64) -> u64 {
+        use Expr::*;
+        match self {
+            Cell(v) => v.col.domain() as u64 * d1_size,
+            Double(x) | Square(x) | Pow(x, _) | Cache(_, x) => x.supported_degree(d1_size),
+            BinOp(_, x, y) => {
+                std::cmp::min(x.supported_degree(d1_size), y.supported_degree(d1_size))
+            }
+            Constant(_) | VanishesOnLast4Rows | UnnormalizedLagrangeBasis(_) => {
+                Domain::D8 as u64 * d1_size
+            }
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, FromPrimitive, ToPrimitive)]
@@ -1069,6 +1162,19 @@ impl<'a, F: FftField> EvalResult<'a, F> {
     }
 }
 
+/// Returns the smallest domain over which a polynomial of degree `deg` can be evaluated.
+fn evaluation_domain(deg: u64, d1_size: u64) -> Domain {
+    if deg <= d1_size {
+        Domain::D1
+    } else if deg <= 4 * d1_size {
+        Domain::D4
+    } else if deg <= 8 * d1_size {
+        Domain::D8
+    } else {
+        panic!("constraint had degree {deg} > d8 ({})", 8 * d1_size);
+    }
+}
+
 fn get_domain<F: FftField>(d: Domain, env: &Environment<F>) -> D<F> {
     match d {
         Domain::D1 => env.domain.d1,
@@ -1217,7 +1323,7 @@ impl<F: FftField> Expr<ConstantExpr<F>> {
                 let y = (*y).evaluate_(d, pt, evals, c)?;
                 Ok(x - y)
             }
-            VanishesOnLast4Rows => Ok(eval_vanishes_on_last_4_rows(d, pt)),
+            VanishesOnLast4Rows => Ok(eval_vanishes_on_last_4_rows(d, c.zk_rows, pt)),
             UnnormalizedLagrangeBasis(i) => {
                 Ok(d.evaluate_vanishing_polynomial(pt) / (pt - d.group_gen.pow(&[*i as u64])))
             }
@@ -1243,51 +1349,49 @@ enum Either<A, B> {
 }
 
 impl<F: FftField> Expr<F> {
-    /// Evaluate an expression into a field element.
-    pub fn evaluate(&self, d: D<F>, pt: F, evals: &[ProofEvaluations<F>]) -> Result<F, &str> {
+    /// Evaluate an expression into a field element,
+    /// for a domain `d` whose last `zk_rows` rows are reserved for zero-knowledge.
+    pub fn evaluate(
+        &self,
+        d: D<F>,
+        zk_rows: u64,
+        pt: F,
+        evals: &[ProofEvaluations<F>],
+    ) -> Result<F, &str> {
         use Expr::*;
         match self {
             Constant(x) => Ok(*x),
-            Pow(x, p) => Ok(x.evaluate(d, pt, evals)?.pow(&[*p as u64])),
-            Double(x) => x.evaluate(d, pt, evals).map(|x| x.double()),
-            Square(x) => x.evaluate(d, pt, evals).map(|x| x.square()),
+            Pow(x, p) => Ok(x.evaluate(d, zk_rows, pt, evals)?.pow(&[*p as u64])),
+            Double(x) => x.evaluate(d, zk_rows, pt, evals).map(|x| x.double()),
+            Square(x) => x.evaluate(d, zk_rows, pt, evals).map(|x| x.square()),
             BinOp(Op2::Mul, x, y) => {
-                let x = (*x).evaluate(d, pt, evals)?;
-                let y = (*y).evaluate(d, pt, evals)?;
+                let x = (*x).evaluate(d, zk_rows, pt, evals)?;
+                let y = (*y).evaluate(d, zk_rows, pt, evals)?;
                 Ok(x * y)
             }
             BinOp(Op2::Add, x, y) => {
-                let x = (*x).evaluate(d, pt, evals)?;
-                let y = (*y).evaluate(d, pt, evals)?;
+                let x = (*x).evaluate(d, zk_rows, pt, evals)?;
+                let y = (*y).evaluate(d, zk_rows, pt, evals)?;
                 Ok(x + y)
             }
             BinOp(Op2::Sub, x, y) => {
-                let x = (*x).evaluate(d, pt, evals)?;
-                let y = (*y).evaluate(d, pt, evals)?;
+                let x = (*x).evaluate(d, zk_rows, pt, evals)?;
+                let y = (*y).evaluate(d, zk_rows, pt, evals)?;
                 Ok(x - y)
             }
-            VanishesOnLast4Rows => Ok(eval_vanishes_on_last_4_rows(d, pt)),
+            VanishesOnLast4Rows => Ok(eval_vanishes_on_last_4_rows(d, zk_rows, pt)),
             UnnormalizedLagrangeBasis(i) => {
                 Ok(d.evaluate_vanishing_polynomial(pt) / (pt - d.group_gen.pow(&[*i as u64])))
             }
             Cell(v) => v.evaluate(evals),
-            Cache(_, e) => e.evaluate(d, pt, evals),
+            Cache(_, e) => e.evaluate(d, zk_rows, pt, evals),
         }
     }
 
     /// Compute the polynomial corresponding to this expression, in evaluation form.
     pub fn evaluations<'a>(&self, env: &Environment<'a, F>) -> Evaluations<F, D<F>> {
         let d1_size = env.domain.d1.size;
-        let deg = self.degree(d1_size);
-        let d = if deg <= d1_size {
-            Domain::D1
-        } else if deg <= 4 * d1_size {
-            Domain::D4
-        } else if deg <= 8 * d1_size {
-            Domain::D8
-        } else {
-            panic!("constraint had degree {deg} > d8 ({})", 8 * d1_size);
-        };
+        let d = evaluation_domain(self.degree(d1_size), d1_size);
 
         let mut cache = HashMap::new();
 
@@ -1441,7 +1545,7 @@ impl<F: FftField> Expr<F> {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 /// A "linearization", which is linear combination with `E` coefficients of
 /// columns.
 pub struct Linearization<E> {
@@ -1550,13 +1654,13 @@ impl<F: One> Expr<F> {
     }
 }
 
-type Monomials<F> = HashMap<Vec<Variable>, Expr<F>>;
+type Monomials<F> = BTreeMap<Vec<Variable>, Expr<F>>;
 
 fn mul_monomials<F: Neg<Output = F> + Clone + One + Zero + PartialEq>(
     e1: &Monomials<F>,
     e2: &Monomials<F>,
 ) -> Monomials<F> {
-    let mut res: HashMap<_, Expr<F>> = HashMap::new();
+    let mut res: Monomials<F> = BTreeMap::new();
     for (m1, c1) in e1.iter() {
         for (m2, c2) in e2.iter() {
             let mut m = m1.clone();
@@ -1590,9 +1694,9 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
         }
     }
 
-    fn monomials(&self, ev: &HashSet<Column>) -> HashMap<Vec<Variable>, Expr<F>> {
+    fn monomials(&self, ev: &HashSet<Column>) -> Monomials<F> {
         let sing = |v: Vec<Variable>, c: Expr<F>| {
-            let mut h = HashMap::new();
+            let mut h = BTreeMap::new();
             h.insert(v, c);
             h
         };
@@ -1625,7 +1729,7 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
                 acc
             }
             Double(e) => {
-                HashMap::from_iter(e.monomials(ev).into_iter().map(|(m, c)| (m, c.double())))
+                BTreeMap::from_iter(e.monomials(ev).into_iter().map(|(m, c)| (m, c.double())))
             }
             Cache(_, e) => e.monomials(ev),
             UnnormalizedLagrangeBasis(i) => constant(UnnormalizedLagrangeBasis(*i)),
@@ -1692,8 +1796,9 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
     /// this function computes `lin_or_err(factor_{V_0}(e))`, although it does not
     /// compute it in that way. Instead, it computes it by reducing the expression into
     /// a sum of monomials with `F` coefficients, and then factors the monomials.
+    /// The monomials and the columns are processed in order, so that the result is deterministic.
     pub fn linearize(&self, evaluated: HashSet<Column>) -> Result<Linearization<Expr<F>>, &str> {
-        let mut res: HashMap<Column, Expr<F>> = HashMap::new();
+        let mut res: BTreeMap<Column, Expr<F>> = BTreeMap::new();
         let mut constant_term: Expr<F> = Self::zero();
         let monomials = self.monomials(&evaluated);
 
@@ -1726,7 +1831,7 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
                         // with the above that moves v out of the map with .remove and
                         // into v + c.
                         //
-                        // I'm not sure if there's a way to do it with the HashMap API
+                        // I'm not sure if there's a way to do it with the map API
                         // without calling remove.
                     }
                 }
@@ -1741,6 +1846,259 @@ impl<F: Neg<Output = F> + Clone + One + Zero + PartialEq> Expr<F> {
     }
 }
 
+//
+// Optimization
+//
+
+/// The number of [PolishToken]s of an expression before and after [Expr::optimize].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OptimizationStats {
+    pub tokens_before: usize,
+    pub tokens_after: usize,
+}
+
+impl AddAssign for OptimizationStats {
+    fn add_assign(&mut self, other: Self) {
+        self.tokens_before += other.tokens_before;
+        self.tokens_after += other.tokens_after;
+    }
+}
+
+impl<F: Field> ConstantExpr<F> {
+    /// Folds the literals of a constant expression.
+    fn simplify(&self) -> Self {
+        use ConstantExpr::*;
+        match self {
+            Add(x, y) => x.simplify() + y.simplify(),
+            Sub(x, y) => x.simplify() - y.simplify(),
+            Mul(x, y) => {
+                let (x, y) = (x.simplify(), y.simplify());
+                if x.is_zero() || y.is_zero() {
+                    Self::zero()
+                } else {
+                    x * y
+                }
+            }
+            Pow(x, 1) => x.simplify(),
+            Pow(x, p) => x.simplify().pow(*p),
+            x => x.clone(),
+        }
+    }
+}
+
+/// A node of an expression in which every distinct subexpression is only stored once,
+/// and refered to by its index.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Node<F> {
+    Constant(ConstantExpr<F>),
+    Cell(Variable),
+    VanishesOnLast4Rows,
+    UnnormalizedLagrangeBasis(usize),
+    Double(usize),
+    Square(usize),
+    Pow(usize, u64),
+    BinOp(Op2, usize, usize),
+}
+
+/// The directed acyclic graph of the distinct subexpressions of an expression.
+#[derive(Default)]
+struct ExprDag<F> {
+    ids: HashMap<Node<F>, usize>,
+    nodes: Vec<Node<F>>,
+    /// the number of tokens of each node, once compiled without caching
+    tokens: Vec<usize>,
+}
+
+impl<F: FftField> ExprDag<F> {
+    /// Adds all the subexpressions of `e` to the graph (ignoring existing caches),
+    /// and returns the index of `e`.
+    fn add(&mut self, e: &Expr<ConstantExpr<F>>) -> usize {
+        let (node, tokens) = match e {
+            Expr::Constant(c) => {
+                let mut res = vec![];
+                c.to_polish_(&mut res);
+                (Node::Constant(c.clone()), res.len())
+            }
+            Expr::Cell(v) => (Node::Cell(*v), 1),
+            Expr::VanishesOnLast4Rows => (Node::VanishesOnLast4Rows, 1),
+            Expr::UnnormalizedLagrangeBasis(i) => (Node::UnnormalizedLagrangeBasis(*i), 1),
+            Expr::Double(x) => {
+                let x = self.add(x);
+                (Node::Double(x), self.tokens[x] + 2)
+            }
+            Expr::Square(x) => {
+                let x = self.add(x);
+                (Node::Square(x), self.tokens[x] + 2)
+            }
+            Expr::Pow(x, d) => {
+                let x = self.add(x);
+                (Node::Pow(x, *d), self.tokens[x] + 1)
+            }
+            Expr::BinOp(op, x, y) => {
+                let x = self.add(x);
+                let y = self.add(y);
+                (
+                    Node::BinOp(op.clone(), x, y),
+                    self.tokens[x] + self.tokens[y] + 1,
+                )
+            }
+            Expr::Cache(_, x) => return self.add(x),
+        };
+
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.tokens.push(tokens);
+        id
+    }
+
+    /// Counts the number of times each node is computed,
+    /// assuming that a node is computed only once if it is used several times.
+    fn count_uses(&self, id: usize, uses: &mut Vec<usize>) {
+        uses[id] += 1;
+        if uses[id] > 1 {
+            return;
+        }
+        match &self.nodes[id] {
+            Node::Double(x) | Node::Square(x) | Node::Pow(x, _) => self.count_uses(*x, uses),
+            Node::BinOp(_, x, y) => {
+                self.count_uses(*x, uses);
+                self.count_uses(*y, uses);
+            }
+            _ => (),
+        }
+    }
+
+    /// Rebuilds the expression of a node, caching the nodes that are worth caching.
+    fn build(
+        &self,
+        id: usize,
+        uses: &[usize],
+        cache: &mut Cache,
+        built: &mut HashMap<usize, Expr<ConstantExpr<F>>>,
+    ) -> Expr<ConstantExpr<F>> {
+        if let Some(e) = built.get(&id) {
+            return e.clone();
+        }
+
+        let mut build = |x: usize| Box::new(self.build(x, uses, cache, built));
+        let e = match &self.nodes[id] {
+            Node::Constant(c) => Expr::Constant(c.clone()),
+            Node::Cell(v) => Expr::Cell(*v),
+            Node::VanishesOnLast4Rows => Expr::VanishesOnLast4Rows,
+            Node::UnnormalizedLagrangeBasis(i) => Expr::UnnormalizedLagrangeBasis(*i),
+            Node::Double(x) => Expr::Double(build(*x)),
+            Node::Square(x) => Expr::Square(build(*x)),
+            Node::Pow(x, d) => Expr::Pow(build(*x), *d),
+            Node::BinOp(op, x, y) => Expr::BinOp(op.clone(), build(*x), build(*y)),
+        };
+
+        // computing a node `k` times costs `k * tokens` tokens,
+        // whereas caching it costs `tokens + 1` tokens and `k - 1` loads
+        let e = if uses[id] > 1 && (uses[id] - 1) * (self.tokens[id] - 1) > 1 {
+            cache.cache(e)
+        } else {
+            e
+        };
+        built.insert(id, e.clone());
+        e
+    }
+}
+
+impl<F: FftField> Expr<ConstantExpr<F>> {
+    /// Simplifies an expression by folding its constants
+    /// and removing the additions of zero and the multiplications by zero or one.
+    pub fn simplify(&self) -> Self {
+        use Expr::*;
+        match self {
+            Constant(c) => Constant(c.simplify()),
+            Cell(_) | VanishesOnLast4Rows | UnnormalizedLagrangeBasis(_) => self.clone(),
+            Cache(_, e) => e.simplify(),
+            Double(x) => match x.simplify() {
+                Constant(ConstantExpr::Literal(x)) => Self::literal(x.double()),
+                x if x.is_zero() => x,
+                x => Double(Box::new(x)),
+            },
+            Square(x) => match x.simplify() {
+                Constant(ConstantExpr::Literal(x)) => Self::literal(x.square()),
+                x if x.is_zero() || x.is_one() => x,
+                x => Square(Box::new(x)),
+            },
+            Pow(_, 0) => Self::one(),
+            Pow(x, 1) => x.simplify(),
+            Pow(x, d) => match x.simplify() {
+                Constant(c) => Constant(c.pow(*d)),
+                x if x.is_zero() || x.is_one() => x,
+                x => Pow(Box::new(x), *d),
+            },
+            BinOp(op, x, y) => match (op, x.simplify(), y.simplify()) {
+                (Op2::Add, Constant(x), Constant(y)) => Constant(x + y),
+                (Op2::Sub, Constant(x), Constant(y)) => Constant(x - y),
+                (Op2::Mul, Constant(x), Constant(y)) => {
+                    if x.is_zero() || y.is_zero() {
+                        Self::zero()
+                    } else {
+                        Constant(x * y)
+                    }
+                }
+                (Op2::Add, x, y) => x + y,
+                (Op2::Sub, x, y) if x == y => Self::zero(),
+                (Op2::Sub, x, y) => x - y,
+                (Op2::Mul, x, y) => x * y,
+            },
+        }
+    }
+
+    /// Hoists the subexpressions that are computed several times into [Expr::Cache] entries,
+    /// whenever doing so reduces the size of the compiled expression.
+    /// The existing caches of the expression are discarded.
+    pub fn eliminate_common_subexpressions(&self) -> Self {
+        let mut dag = ExprDag::default();
+        let root = dag.add(self);
+
+        let mut uses = vec![0; dag.nodes.len()];
+        dag.count_uses(root, &mut uses);
+
+        dag.build(root, &uses, &mut Cache::default(), &mut HashMap::new())
+    }
+
+    /// Simplifies an expression and eliminates its common subexpressions
+    /// (see [Expr::simplify] and [Expr::eliminate_common_subexpressions]).
+    /// The returned statistics compare the number of [PolishToken]s before and after.
+    pub fn optimize(&self) -> (Self, OptimizationStats) {
+        let optimized = self.simplify().eliminate_common_subexpressions();
+        let stats = OptimizationStats {
+            tokens_before: self.to_polish().len(),
+            tokens_after: optimized.to_polish().len(),
+        };
+        (optimized, stats)
+    }
+}
+
+impl<F: FftField> Linearization<Expr<ConstantExpr<F>>> {
+    /// Optimizes each coefficient of the linearization (see [Expr::optimize]).
+    pub fn optimize(&self) -> (Self, OptimizationStats) {
+        let (constant_term, mut stats) = self.constant_term.optimize();
+        let index_terms = self
+            .index_terms
+            .iter()
+            .map(|(col, e)| {
+                let (e, e_stats) = e.optimize();
+                stats += e_stats;
+                (*col, e)
+            })
+            .collect();
+        let linearization = Linearization {
+            constant_term,
+            index_terms,
+        };
+        (linearization, stats)
+    }
+}
+
 // Trait implementations
 
 impl<F: Field> Zero for ConstantExpr<F> {
@@ -2004,22 +2362,128 @@ impl<F: PrimeField> ConstantExpr<F> {
             Sub(x, y) => format!("({} - {})", x.ocaml(), y.ocaml()),
         }
     }
+
+    fn text(&self, prec: Precedence) -> String {
+        use ConstantExpr::*;
+        match self {
+            Alpha => "alpha".to_string(),
+            Beta => "beta".to_string(),
+            Gamma => "gamma".to_string(),
+            JointCombiner => "joint_combiner".to_string(),
+            EndoCoefficient => "endo_coefficient".to_string(),
+            Mds { row, col } => format!("mds[{row}][{col}]"),
+            Literal(x) => match (small_literal(*x), small_literal(-*x)) {
+                (Some(n), _) => n.to_string(),
+                (None, Some(n)) => parenthesize(format!("-{n}"), Precedence::Sum, prec),
+                (None, None) => format!("0x{}", x.into_repr()),
+            },
+            Pow(x, n) => format!("{}^{n}", x.text(Precedence::Atom)),
+            Add(x, y) => parenthesize(
+                format!("{} + {}", x.text(Precedence::Sum), y.text(Precedence::Sum)),
+                Precedence::Sum,
+                prec,
+            ),
+            Mul(x, y) => parenthesize(
+                format!(
+                    "{} * {}",
+                    x.text(Precedence::Product),
+                    y.text(Precedence::Product)
+                ),
+                Precedence::Product,
+                prec,
+            ),
+            Sub(x, y) => parenthesize(
+                format!(
+                    "{} - {}",
+                    x.text(Precedence::Sum),
+                    y.text(Precedence::Product)
+                ),
+                Precedence::Sum,
+                prec,
+            ),
+        }
+    }
+
+    /// The Rust code evaluating the constant, given the [Constants] `c`.
+    fn rust(&self) -> String {
+        use ConstantExpr::*;
+        match self {
+            Alpha => "c.alpha".to_string(),
+            Beta => "c.beta".to_string(),
+            Gamma => "c.gamma".to_string(),
+            JointCombiner => {
+                "c.joint_combiner.ok_or(\"no joint lookup was expected\")?".to_string()
+            }
+            EndoCoefficient => "c.endo_coefficient".to_string(),
+            Mds { row, col } => format!("c.mds[{row}][{col}]"),
+            Literal(x) => match (small_literal(*x), small_literal(-*x)) {
+                (Some(n), _) => format!("F::from({n}u64)"),
+                (None, Some(n)) => format!("(-F::from({n}u64))"),
+                (None, None) => format!(
+                    "F::from_le_bytes_mod_order(&{:?})",
+                    x.into_repr().to_bytes_le()
+                ),
+            },
+            Pow(x, n) => format!("{}.pow([{n}u64])", x.rust()),
+            Add(x, y) => format!("({} + {})", x.rust(), y.rust()),
+            Mul(x, y) => format!("({} * {})", x.rust(), y.rust()),
+            Sub(x, y) => format!("({} - {})", x.rust(), y.rust()),
+        }
+    }
+}
+
+/// The precedence of the operators in the text output,
+/// to only print the parentheses that are needed.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Sum,
+    Product,
+    Atom,
+}
+
+/// Wraps `s`, an expression whose outermost operator has precedence `op`,
+/// in parentheses if it appears in a context of higher precedence `prec`.
+fn parenthesize(s: String, op: Precedence, prec: Precedence) -> String {
+    if op < prec {
+        format!("({s})")
+    } else {
+        s
+    }
+}
+
+/// Returns the field element as an integer, if it fits in a [u64].
+fn small_literal<F: PrimeField>(x: F) -> Option<u64> {
+    let repr = x.into_repr();
+    let limbs = repr.as_ref();
+    limbs[1..].iter().all(|limb| *limb == 0).then(|| limbs[0])
 }
 
 impl<F> Expr<ConstantExpr<F>>
 where
     F: PrimeField,
 {
-    /// Converts the expression in OCaml code
-    pub fn ocaml_str(&self) -> String {
+    /// Prints the expression with `print`, which prints the cached subexpressions as variables
+    /// and stores them in the `cache` it is given.
+    /// Returns the cached subexpressions, ordered by [CacheId], along with the printed expression.
+    fn print_with_cache(
+        &self,
+        print: impl FnOnce(&Self, &mut HashMap<CacheId, Self>) -> String,
+    ) -> (Vec<(CacheId, Self)>, String) {
         let mut env = HashMap::new();
-        let e = self.ocaml(&mut env);
+        let e = print(self, &mut env);
 
         let mut env: Vec<_> = env.into_iter().collect();
         // HashMap deliberately uses an unstable order; here we sort to ensure that the output is
         // consistent when printing.
         env.sort_by(|(x, _), (y, _)| x.cmp(y));
 
+        (env, e)
+    }
+
+    /// Converts the expression in OCaml code
+    pub fn ocaml_str(&self) -> String {
+        let (env, e) = self.print_with_cache(Self::ocaml);
+
         let mut res = String::new();
         for (k, v) in env.into_iter() {
             let rhs = v.ocaml_str();
@@ -2055,13 +2519,7 @@ where
 
     /// Converts the expression in LaTeX
     pub fn latex_str(&self) -> Vec<String> {
-        let mut env = HashMap::new();
-        let e = self.latex(&mut env);
-
-        let mut env: Vec<_> = env.into_iter().collect();
-        // HashMap deliberately uses an unstable order; here we sort to ensure that the output is
-        // consistent when printing.
-        env.sort_by(|(x, _), (y, _)| x.cmp(y));
+        let (env, e) = self.print_with_cache(Self::latex);
 
         let mut res = vec![];
         for (k, v) in env.into_iter() {
@@ -2093,6 +2551,150 @@ where
             }
         }
     }
+
+    /// Converts the expression in plain text, with one line per cached subexpression
+    /// (defined before they are used) followed by the expression itself.
+    pub fn text_str(&self) -> Vec<String> {
+        let (env, e) = self.print_with_cache(|e, cache| e.text(cache, Precedence::Sum));
+
+        let mut res = vec![];
+        for (k, v) in env.into_iter() {
+            let mut rhs = v.text_str();
+            let last = rhs.pop().expect("returned an empty expression");
+            res.extend(rhs);
+            res.push(format!("{} = {last}", k.var_name()));
+        }
+        res.push(e);
+        res
+    }
+
+    /// Converts a list of constraints in a numbered Markdown list,
+    /// as found in the book (see `book/src/kimchi/gates.md`).
+    /// The cached subexpressions of a constraint are listed under it.
+    pub fn markdown_str(constraints: &[Self]) -> String {
+        let mut res = String::new();
+        for (i, constraint) in constraints.iter().enumerate() {
+            let mut lines = constraint.text_str();
+            let e = lines.pop().expect("returned an empty expression");
+            res.push_str(&format!("{}. `{e} = 0`\n", i + 1));
+            for line in lines {
+                res.push_str(&format!("    - where `{line}`\n"));
+            }
+        }
+        res
+    }
+
+    fn text(
+        &self,
+        cache: &mut HashMap<CacheId, Expr<ConstantExpr<F>>>,
+        prec: Precedence,
+    ) -> String {
+        use Expr::*;
+        use Precedence::{Atom, Product, Sum};
+        match self {
+            Double(x) => parenthesize(format!("2 * {}", x.text(cache, Product)), Product, prec),
+            Constant(x) => x.text(prec),
+            Cell(v) => v.text(),
+            UnnormalizedLagrangeBasis(i) => format!("unnormalized_lagrange_basis({})", *i),
+            VanishesOnLast4Rows => "vanishes_on_last_4_rows".to_string(),
+            BinOp(Op2::Add, x, y) => parenthesize(
+                format!("{} + {}", x.text(cache, Sum), y.text(cache, Sum)),
+                Sum,
+                prec,
+            ),
+            BinOp(Op2::Mul, x, y) => parenthesize(
+                format!("{} * {}", x.text(cache, Product), y.text(cache, Product)),
+                Product,
+                prec,
+            ),
+            BinOp(Op2::Sub, x, y) => parenthesize(
+                format!("{} - {}", x.text(cache, Sum), y.text(cache, Product)),
+                Sum,
+                prec,
+            ),
+            Pow(x, d) => format!("{}^{d}", x.text(cache, Atom)),
+            Square(x) => format!("{}^2", x.text(cache, Atom)),
+            Cache(id, e) => {
+                cache.insert(*id, e.as_ref().clone());
+                id.var_name()
+            }
+        }
+    }
+
+    /// Converts the expression in the source code of a standalone Rust function named `name`,
+    /// which evaluates the expression like [PolishToken::evaluate] does, but without an interpreter.
+    /// The function has the following signature:
+    ///
+    /// ```ignore
+    /// pub fn name<F: ark_ff::PrimeField>(
+    ///     d: ark_poly::Radix2EvaluationDomain<F>,
+    ///     pt: F,
+    ///     evals: &[kimchi::proof::ProofEvaluations<F>],
+    ///     c: &kimchi::circuits::expr::Constants<F>,
+    /// ) -> Result<F, &'static str>
+    /// ```
+    pub fn rust_str(&self, name: &str) -> String {
+        let (env, e) = self.print_with_cache(Self::rust);
+
+        let mut res = format!(
+            "#[allow(unused_variables, unused_imports, unused_parens)]\n\
+             pub fn {name}<F: ark_ff::PrimeField>(\n    \
+                 d: ark_poly::Radix2EvaluationDomain<F>,\n    \
+                 pt: F,\n    \
+                 evals: &[kimchi::proof::ProofEvaluations<F>],\n    \
+                 c: &kimchi::circuits::expr::Constants<F>,\n\
+             ) -> Result<F, &'static str> {{\n    \
+                 use ark_ff::Field;\n    \
+                 use ark_poly::EvaluationDomain;\n\n"
+        );
+        for (k, v) in env.into_iter() {
+            res.push_str(&format!("    let {} = {};\n", k.var_name(), v.rust_block()));
+        }
+        res.push_str(&format!("    Ok({e})\n}}\n"));
+        res
+    }
+
+    /// Converts the expression in a Rust expression,
+    /// which is a block defining its cached subexpressions if it has any.
+    fn rust_block(&self) -> String {
+        let (env, e) = self.print_with_cache(Self::rust);
+        if env.is_empty() {
+            return e;
+        }
+
+        let mut res = "{ ".to_string();
+        for (k, v) in env.into_iter() {
+            res.push_str(&format!("let {} = {}; ", k.var_name(), v.rust_block()));
+        }
+        res.push_str(&e);
+        res.push_str(" }");
+        res
+    }
+
+    fn rust(&self, cache: &mut HashMap<CacheId, Expr<ConstantExpr<F>>>) -> String {
+        use Expr::*;
+        match self {
+            Double(x) => format!("{}.double()", x.rust(cache)),
+            Constant(x) => x.rust(),
+            Cell(v) => v.rust(),
+            UnnormalizedLagrangeBasis(i) => {
+                format!("(d.evaluate_vanishing_polynomial(pt) / (pt - d.group_gen.pow([{i}u64])))")
+            }
+            VanishesOnLast4Rows => {
+                "kimchi::circuits::polynomials::permutation::eval_vanishes_on_last_4_rows(d, c.zk_rows, pt)"
+                    .to_string()
+            }
+            BinOp(Op2::Add, x, y) => format!("({} + {})", x.rust(cache), y.rust(cache)),
+            BinOp(Op2::Mul, x, y) => format!("({} * {})", x.rust(cache), y.rust(cache)),
+            BinOp(Op2::Sub, x, y) => format!("({} - {})", x.rust(cache), y.rust(cache)),
+            Pow(x, d) => format!("{}.pow([{d}u64])", x.rust(cache)),
+            Square(x) => format!("{}.square()", x.rust(cache)),
+            Cache(id, e) => {
+                cache.insert(*id, e.as_ref().clone());
+                id.var_name()
+            }
+        }
+    }
 }
 
 //
@@ -2154,11 +2756,18 @@ pub mod prologue {
 pub mod test {
     use super::*;
     use crate::circuits::{
-        constraints::ConstraintSystem, gate::CircuitGate, polynomials::generic::GenericGateSpec,
-        wires::Wire,
+        constraints::ConstraintSystem,
+        gate::CircuitGate,
+        polynomials::generic::GenericGateSpec,
+        polynomials::permutation::ZK_ROWS,
+        wires::{Width, Wire, COLUMNS, PERMUTS},
     };
+    use crate::linearization::{constraints_expr, linearization_columns};
+    use crate::proof::LookupEvaluations;
+    use ark_ff::UniformRand;
     use array_init::array_init;
     use mina_curves::pasta::fp::Fp;
+    use std::collections::BTreeSet;
 
     #[test]
     #[should_panic]
@@ -2202,6 +2811,7 @@ pub mod test {
         let witness_cols: [_; COLUMNS] = array_init(|_| DensePolynomial::zero());
         let permutation = DensePolynomial::zero();
         let domain_evals = constraint_system.evaluate(&witness_cols, &permutation);
+        let column_evaluations = constraint_system.column_evaluations();
 
         let env = Environment {
             constants: Constants {
@@ -2211,9 +2821,10 @@ pub mod test {
                 joint_combiner: None,
                 endo_coefficient: one,
                 mds: vec![vec![]],
+                zk_rows: constraint_system.zk_rows,
             },
             witness: &domain_evals.d8.this.w,
-            coefficient: &constraint_system.coefficients8,
+            coefficient: &column_evaluations.coefficients8,
             vanishes_on_last_4_rows: &constraint_system.precomputations().vanishes_on_last_4_rows,
             z: &domain_evals.d8.this.z,
             l0_1: l0_1(constraint_system.domain.d1),
@@ -2225,4 +2836,230 @@ pub mod test {
         // this should panic as we don't have a domain large enough
         expr.evaluations(&env);
     }
+
+    fn bin(op: Op2, x: E<Fp>, y: E<Fp>) -> E<Fp> {
+        Expr::BinOp(op, Box::new(x), Box::new(y))
+    }
+
+    #[test]
+    fn test_optimize() {
+        // x = w0 * w1 + w2 is computed twice in x * 1 + x * (2 * 3)
+        let x = bin(
+            Op2::Add,
+            bin(Op2::Mul, witness_curr(0), witness_curr(1)),
+            witness_curr(2),
+        );
+        let six = ConstantExpr::Mul(Box::new(2u64.into()), Box::new(3u64.into()));
+        let expr = bin(
+            Op2::Add,
+            bin(Op2::Mul, x.clone(), Expr::one()),
+            bin(Op2::Mul, x.clone(), Expr::Constant(six)),
+        );
+
+        let (optimized, stats) = expr.optimize();
+        let cached = Expr::Cache(CacheId(0), Box::new(x.clone()));
+        let expected = bin(
+            Op2::Add,
+            cached.clone(),
+            bin(Op2::Mul, cached, Expr::literal(Fp::from(6u64))),
+        );
+        assert_eq!(optimized, expected);
+        assert_eq!(
+            stats,
+            OptimizationStats {
+                tokens_before: 17,
+                tokens_after: 10
+            }
+        );
+
+        // trivial expressions
+        assert!(bin(Op2::Sub, x.clone(), x.clone()).simplify().is_zero());
+        assert!(bin(Op2::Mul, x.clone(), E::zero()).simplify().is_zero());
+        assert!(Expr::Pow(Box::new(x), 0).simplify().is_one());
+    }
+
+    #[test]
+    fn test_optimize_linearization() {
+        let gate_types: BTreeSet<_> = [
+            GateType::Poseidon,
+            GateType::CompleteAdd,
+            GateType::VarBaseMul,
+            GateType::EndoMul,
+            GateType::EndoMulScalar,
+            GateType::ChaCha0,
+            GateType::ChaCha1,
+            GateType::ChaCha2,
+            GateType::ChaChaFinal,
+        ]
+        .into_iter()
+        .collect();
+        let domain = D::<Fp>::new(1 << 4).unwrap();
+        let (expr, _) = constraints_expr(domain, ZK_ROWS, &gate_types, None);
+        let linearization = expr
+            .linearize(linearization_columns::<Fp>(Width::default(), None))
+            .unwrap();
+
+        let (optimized, stats) = linearization.optimize();
+        assert!(stats.tokens_after <= stats.tokens_before);
+
+        // the optimized linearization evaluates to the same values on random points
+        let rng = &mut rand::thread_rng();
+        let evals: [ProofEvaluations<Fp>; 2] = array_init(|_| ProofEvaluations {
+            w: (0..COLUMNS).map(|_| Fp::rand(rng)).collect(),
+            z: Fp::rand(rng),
+            s: (0..PERMUTS - 1).map(|_| Fp::rand(rng)).collect(),
+            lookup: None,
+            generic_selector: Fp::rand(rng),
+            poseidon_selector: Fp::rand(rng),
+        });
+        let constants = Constants {
+            alpha: Fp::rand(rng),
+            beta: Fp::rand(rng),
+            gamma: Fp::rand(rng),
+            joint_combiner: None,
+            endo_coefficient: Fp::rand(rng),
+            mds: oracle::pasta::fp_kimchi::params().mds,
+            zk_rows: ZK_ROWS,
+        };
+        let pt = Fp::rand(rng);
+
+        let mut terms = vec![(&linearization.constant_term, &optimized.constant_term)];
+        terms.extend(
+            linearization
+                .index_terms
+                .iter()
+                .zip(&optimized.index_terms)
+                .map(|((_, e), (_, opt))| (e, opt)),
+        );
+        for (e, opt) in terms {
+            let expected = e.evaluate_(domain, pt, &evals, &constants).unwrap();
+            assert_eq!(
+                opt.evaluate_(domain, pt, &evals, &constants).unwrap(),
+                expected
+            );
+            assert_eq!(
+                PolishToken::evaluate(&opt.to_polish(), domain, pt, &evals, &constants).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_text_str() {
+        let w = witness_curr::<Fp>;
+        let alpha = E::<Fp>::Constant(ConstantExpr::Alpha);
+
+        // only the necessary parentheses are printed
+        let e = (w(0) + w(1)) * w(2) - w(3) * witness_next(4).square();
+        assert_eq!(e.text_str(), vec!["(w0 + w1) * w2 - w3 * next(w4)^2"]);
+        let e = w(0) - (w(1) - alpha.pow(3) * constant(-Fp::from(2u32)));
+        assert_eq!(e.text_str(), vec!["w0 - (w1 - alpha^3 * (-2))"]);
+
+        // cached subexpressions are defined before they are used
+        let mut cache = Cache::default();
+        let x = cache.cache(w(0) + w(1));
+        let constraints = vec![x.clone() * x - index(GateType::Poseidon), w(2).double()];
+        assert_eq!(
+            constraints[0].text_str(),
+            vec!["x_0 = w0 + w1", "x_0 * x_0 - Poseidon"]
+        );
+        assert_eq!(
+            E::<Fp>::markdown_str(&constraints),
+            "1. `x_0 * x_0 - Poseidon = 0`\n    - where `x_0 = w0 + w1`\n2. `2 * w2 = 0`\n"
+        );
+    }
+
+    #[test]
+    fn test_rust_str() {
+        let mut cache = Cache::default();
+        let x = cache.cache(witness_curr::<Fp>(0) + E::Constant(ConstantExpr::Beta));
+        let e = x.clone() * x * witness_next(1) - constant(-Fp::from(3u32)).pow(2);
+
+        let code = e.rust_str("constraint");
+        assert!(code.contains("pub fn constraint<F: ark_ff::PrimeField>("));
+        assert!(code.contains("    let x_0 = (evals[0].w[0] + c.beta);\n"));
+        assert!(code
+            .contains("    Ok((((x_0 * x_0) * evals[1].w[1]) - (-F::from(3u64)).pow([2u64])))\n"));
+
+        // the columns that are not evaluated are reported as errors
+        let code = coeff::<Fp>(0).rust_str("constraint");
+        assert!(code.contains("Ok(Err::<F, _>("));
+    }
+
+    /// The code generated by [Expr::rust_str] for the expression of [test_rust_str_evaluation].
+    #[allow(clippy::all)]
+    mod generated {
+        use crate as kimchi;
+        include!("../tests/fixtures/rust_str.rs");
+    }
+
+    #[test]
+    fn test_rust_str_evaluation() {
+        // an expression with all the kinds of nodes, and nested cached subexpressions
+        let constant_expr = |c| E::<Fp>::Constant(c);
+        let cell = |col, row| E::<Fp>::cell(col, row);
+        let mut cache = Cache::default();
+        let x = cache.cache(witness_curr(0) + constant_expr(ConstantExpr::Beta));
+        let y = cache.cache(
+            x.clone() * cell(Column::LookupSorted(2), Next)
+                - constant_expr(ConstantExpr::JointCombiner).pow(3),
+        );
+        let e = y.clone() * x.double()
+            + y.square() * cell(Column::Z, Next)
+            + cell(Column::LookupAggreg, Curr) * cell(Column::LookupTable, Next)
+            - cell(Column::Index(GateType::Poseidon), Curr)
+                * cell(Column::Index(GateType::Generic), Next)
+            + constant_expr(ConstantExpr::Alpha) * constant_expr(ConstantExpr::Gamma)
+            + constant_expr(ConstantExpr::EndoCoefficient)
+                * constant_expr(ConstantExpr::Mds { row: 1, col: 2 })
+            + E::UnnormalizedLagrangeBasis(3) * E::VanishesOnLast4Rows
+            - constant_expr(ConstantExpr::Literal(Fp::from(2u32).pow([200])))
+                * constant_expr(ConstantExpr::Literal(-Fp::from(5u32)));
+
+        // the fixture is the code currently generated for the expression
+        assert_eq!(
+            e.rust_str("constraint"),
+            include_str!("../tests/fixtures/rust_str.rs"),
+            "the generated code changed, update kimchi/src/tests/fixtures/rust_str.rs"
+        );
+
+        // and it evaluates the expression like the interpreter does
+        let rng = &mut rand::thread_rng();
+        let domain = D::<Fp>::new(1 << 4).unwrap();
+        let mut evals: [ProofEvaluations<Fp>; 2] = array_init(|_| ProofEvaluations {
+            w: (0..COLUMNS).map(|_| Fp::rand(rng)).collect(),
+            z: Fp::rand(rng),
+            s: (0..PERMUTS - 1).map(|_| Fp::rand(rng)).collect(),
+            lookup: Some(LookupEvaluations {
+                sorted: (0..3).map(|_| Fp::rand(rng)).collect(),
+                aggreg: Fp::rand(rng),
+                table: Fp::rand(rng),
+            }),
+            generic_selector: Fp::rand(rng),
+            poseidon_selector: Fp::rand(rng),
+        });
+        let constants = Constants {
+            alpha: Fp::rand(rng),
+            beta: Fp::rand(rng),
+            gamma: Fp::rand(rng),
+            joint_combiner: Some(Fp::rand(rng)),
+            endo_coefficient: Fp::rand(rng),
+            mds: oracle::pasta::fp_kimchi::params().mds,
+            zk_rows: ZK_ROWS,
+        };
+        let pt = Fp::rand(rng);
+        assert_eq!(
+            generated::constraint(domain, pt, &evals, &constants),
+            e.evaluate_(domain, pt, &evals, &constants)
+        );
+
+        // the errors are the same
+        for evals in &mut evals {
+            evals.lookup = None;
+        }
+        assert_eq!(
+            generated::constraint(domain, pt, &evals, &constants),
+            e.evaluate_(domain, pt, &evals, &constants)
+        );
+    }
 }
@@ -0,0 +1,575 @@
+//! This module implements a compiled evaluator for expressions.
+//!
+//! An expression (or its [PolishToken] form) is compiled to a flat program
+//! which operates on registers, each register holding one value per row.
+//! The values that do not depend on the row (the constants of the expression)
+//! are computed once per evaluation, instead of once per row.
+//! The program is then run over the evaluation domain in chunks of rows,
+//! which are processed in parallel and small enough for their registers to stay in cache.
+
+use super::{
+    evaluation_domain, get_domain, unnormalized_lagrange_evals, Column, ConstantExpr, Constants,
+    Environment, Expr, PolishToken, Variable,
+};
+use crate::circuits::polynomials::permutation::MAX_ZK_ROWS;
+use ark_ff::{FftField, Field};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// The number of rows that a thread evaluates at once.
+const CHUNK_SIZE: usize = 256;
+
+/// A value of the program that does not depend on the row.
+#[derive(Clone, Debug)]
+enum Scalar<F> {
+    Alpha,
+    Beta,
+    Gamma,
+    JointCombiner,
+    EndoCoefficient,
+    Mds { row: usize, col: usize },
+    Literal(F),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Mul(usize, usize),
+    Pow(usize, u64),
+}
+
+/// An operand of an instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operand {
+    /// The index of a value that does not depend on the row.
+    Scalar(usize),
+    /// The index of a register.
+    Register(usize),
+}
+
+/// An operation, producing one value per row.
+#[derive(Clone, Debug)]
+enum Op {
+    Cell(Variable),
+    VanishesOnLast4Rows,
+    UnnormalizedLagrangeBasis(usize),
+    Add(Operand, Operand),
+    Sub(Operand, Operand),
+    Mul(Operand, Operand),
+    Pow(usize, u64),
+}
+
+impl Op {
+    /// Returns the registers read by the operation.
+    fn registers(&self) -> Vec<usize> {
+        let operands = match self {
+            Op::Add(x, y) | Op::Sub(x, y) | Op::Mul(x, y) => vec![*x, *y],
+            Op::Pow(x, _) => vec![Operand::Register(*x)],
+            Op::Cell(_) | Op::VanishesOnLast4Rows | Op::UnnormalizedLagrangeBasis(_) => vec![],
+        };
+        let mut registers: Vec<_> = operands
+            .into_iter()
+            .filter_map(|x| match x {
+                Operand::Register(r) => Some(r),
+                Operand::Scalar(_) => None,
+            })
+            .collect();
+        registers.dedup();
+        registers
+    }
+
+    /// Renames the registers read by the operation.
+    fn rename(&self, f: impl Fn(usize) -> usize) -> Op {
+        let rename = |x: Operand| match x {
+            Operand::Register(r) => Operand::Register(f(r)),
+            x => x,
+        };
+        match self {
+            Op::Add(x, y) => Op::Add(rename(*x), rename(*y)),
+            Op::Sub(x, y) => Op::Sub(rename(*x), rename(*y)),
+            Op::Mul(x, y) => Op::Mul(rename(*x), rename(*y)),
+            Op::Pow(x, n) => Op::Pow(f(*x), *n),
+            op => op.clone(),
+        }
+    }
+}
+
+/// An instruction of the program, which writes the result of an operation to a register.
+#[derive(Clone, Debug)]
+struct Instruction {
+    dst: usize,
+    op: Op,
+}
+
+/// Builds a program in which every operation writes to a fresh register.
+struct Builder<F> {
+    scalars: Vec<Scalar<F>>,
+    ops: Vec<Op>,
+}
+
+impl<F> Builder<F> {
+    fn scalar(&mut self, scalar: Scalar<F>) -> Operand {
+        self.scalars.push(scalar);
+        Operand::Scalar(self.scalars.len() - 1)
+    }
+
+    fn op(&mut self, op: Op) -> Operand {
+        self.ops.push(op);
+        Operand::Register(self.ops.len() - 1)
+    }
+
+    fn binop(
+        &mut self,
+        x: Operand,
+        y: Operand,
+        scalar: fn(usize, usize) -> Scalar<F>,
+        op: fn(Operand, Operand) -> Op,
+    ) -> Operand {
+        match (x, y) {
+            (Operand::Scalar(x), Operand::Scalar(y)) => self.scalar(scalar(x, y)),
+            (x, y) => self.op(op(x, y)),
+        }
+    }
+
+    fn pow(&mut self, x: Operand, n: u64) -> Operand {
+        match x {
+            Operand::Scalar(x) => self.scalar(Scalar::Pow(x, n)),
+            Operand::Register(x) => self.op(Op::Pow(x, n)),
+        }
+    }
+
+    /// Allocates the registers of the program,
+    /// reusing the register of a value once it is not read anymore.
+    fn finish(self, result: Operand) -> CompiledExpr<F> {
+        // the index of the last operation reading each value
+        let mut last_use: Vec<_> = (0..self.ops.len()).collect();
+        for (i, op) in self.ops.iter().enumerate() {
+            for r in op.registers() {
+                last_use[r] = i;
+            }
+        }
+        if let Operand::Register(r) = result {
+            last_use[r] = usize::MAX;
+        }
+
+        let mut registers = 0;
+        let mut free = vec![];
+        let mut allocation = vec![0; self.ops.len()];
+        let mut instructions = Vec::with_capacity(self.ops.len());
+        for (i, op) in self.ops.iter().enumerate() {
+            for r in op.registers() {
+                if last_use[r] == i {
+                    free.push(allocation[r]);
+                }
+            }
+
+            let dst = free.pop().unwrap_or_else(|| {
+                registers += 1;
+                registers - 1
+            });
+            allocation[i] = dst;
+            if last_use[i] == i {
+                free.push(dst);
+            }
+
+            instructions.push(Instruction {
+                dst,
+                op: op.rename(|r| allocation[r]),
+            });
+        }
+
+        let result = match result {
+            Operand::Register(r) => Operand::Register(allocation[r]),
+            x => x,
+        };
+
+        CompiledExpr {
+            scalars: self.scalars,
+            instructions,
+            registers,
+            result,
+        }
+    }
+}
+
+/// An expression compiled to a register-based program (see the [module documentation](self)).
+#[derive(Clone, Debug)]
+pub struct CompiledExpr<F> {
+    scalars: Vec<Scalar<F>>,
+    instructions: Vec<Instruction>,
+    registers: usize,
+    result: Operand,
+}
+
+impl<F: FftField> CompiledExpr<F> {
+    /// Compiles an RPN expression.
+    pub fn compile(tokens: &[PolishToken<F>]) -> Self {
+        let mut builder = Builder {
+            scalars: vec![],
+            ops: vec![],
+        };
+        let mut stack = vec![];
+        let mut cache = vec![];
+
+        for t in tokens {
+            use PolishToken::*;
+            let x = match t {
+                Alpha => builder.scalar(Scalar::Alpha),
+                Beta => builder.scalar(Scalar::Beta),
+                Gamma => builder.scalar(Scalar::Gamma),
+                JointCombiner => builder.scalar(Scalar::JointCombiner),
+                EndoCoefficient => builder.scalar(Scalar::EndoCoefficient),
+                Mds { row, col } => builder.scalar(Scalar::Mds {
+                    row: *row,
+                    col: *col,
+                }),
+                Literal(x) => builder.scalar(Scalar::Literal(*x)),
+                Cell(v) => builder.op(Op::Cell(*v)),
+                VanishesOnLast4Rows => builder.op(Op::VanishesOnLast4Rows),
+                UnnormalizedLagrangeBasis(i) => builder.op(Op::UnnormalizedLagrangeBasis(*i)),
+                Dup => *stack.last().expect("empty stack"),
+                Pow(n) => {
+                    let x = stack.pop().expect("empty stack");
+                    builder.pow(x, *n)
+                }
+                Add | Mul | Sub => {
+                    let y = stack.pop().expect("empty stack");
+                    let x = stack.pop().expect("empty stack");
+                    match t {
+                        Add => builder.binop(x, y, Scalar::Add, Op::Add),
+                        Mul => builder.binop(x, y, Scalar::Mul, Op::Mul),
+                        _ => builder.binop(x, y, Scalar::Sub, Op::Sub),
+                    }
+                }
+                Store => {
+                    cache.push(*stack.last().expect("empty stack"));
+                    continue;
+                }
+                Load(i) => cache[*i],
+            };
+            stack.push(x);
+        }
+
+        assert_eq!(stack.len(), 1);
+        builder.finish(stack[0])
+    }
+
+    /// Compiles an expression.
+    pub fn compile_expr(expr: &Expr<ConstantExpr<F>>) -> Self {
+        Self::compile(&expr.to_polish())
+    }
+
+    /// Returns the columns read by the program.
+    pub fn columns(&self) -> impl Iterator<Item = Column> + '_ {
+        self.instructions
+            .iter()
+            .filter_map(|instruction| match instruction.op {
+                Op::Cell(Variable { col, .. }) => Some(col),
+                _ => None,
+            })
+    }
+
+    /// Returns the number of registers used by the program.
+    pub fn registers(&self) -> usize {
+        self.registers
+    }
+
+    /// Returns an upper bound on the degree of the polynomial computed by the program,
+    /// for a domain of size `d1_size` (see [Expr::degree]).
+    pub fn degree(&self, d1_size: u64) -> u64 {
+        let mut degrees = vec![0; self.registers];
+        let degree = |degrees: &[u64], x: &Operand| match x {
+            Operand::Scalar(_) => 0,
+            Operand::Register(r) => degrees[*r],
+        };
+        for Instruction { dst, op } in &self.instructions {
+            degrees[*dst] = match op {
+                Op::Cell(_) | Op::UnnormalizedLagrangeBasis(_) => d1_size,
+                Op::VanishesOnLast4Rows => MAX_ZK_ROWS + 1,
+                Op::Add(x, y) | Op::Sub(x, y) => {
+                    std::cmp::max(degree(&degrees, x), degree(&degrees, y))
+                }
+                Op::Mul(x, y) => degree(&degrees, x) + degree(&degrees, y),
+                Op::Pow(x, n) => degrees[*x] * n,
+            };
+        }
+        degree(&degrees, &self.result)
+    }
+
+    /// Computes the values that do not depend on the row.
+    fn scalars(&self, c: &Constants<F>) -> Vec<F> {
+        let mut res: Vec<F> = Vec::with_capacity(self.scalars.len());
+        for scalar in &self.scalars {
+            let x = match scalar {
+                Scalar::Alpha => c.alpha,
+                Scalar::Beta => c.beta,
+                Scalar::Gamma => c.gamma,
+                Scalar::JointCombiner => c.joint_combiner.expect("joint lookup was not expected"),
+                Scalar::EndoCoefficient => c.endo_coefficient,
+                Scalar::Mds { row, col } => c.mds[*row][*col],
+                Scalar::Literal(x) => *x,
+                Scalar::Add(x, y) => res[*x] + res[*y],
+                Scalar::Sub(x, y) => res[*x] - res[*y],
+                Scalar::Mul(x, y) => res[*x] * res[*y],
+                Scalar::Pow(x, n) => res[*x].pow(&[*n]),
+            };
+            res.push(x);
+        }
+        res
+    }
+
+    /// Returns the domain over which the program is evaluated (see [Expr::evaluations]).
+    pub fn domain(&self, env: &Environment<F>) -> D<F> {
+        let d1_size = env.domain.d1.size;
+        get_domain(evaluation_domain(self.degree(d1_size), d1_size), env)
+    }
+
+    /// Computes the polynomial corresponding to the program, in evaluation form.
+    /// This produces the same evaluations as [Expr::evaluations].
+    pub fn evaluations(&self, env: &Environment<F>) -> Evaluations<F, D<F>> {
+        let domain = self.domain(env);
+        let mut res = Evaluations::from_vec_and_domain(vec![F::zero(); domain.size()], domain);
+        self.add_evaluations_to(env, &mut res);
+        res
+    }
+
+    /// Adds the evaluations of the polynomial corresponding to the program to `acc`,
+    /// without allocating a buffer to hold them.
+    /// This allows a single buffer to accumulate the evaluations of several programs.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `acc` is not over the [domain](CompiledExpr::domain) of the program.
+    pub fn add_evaluations_to(&self, env: &Environment<F>, acc: &mut Evaluations<F, D<F>>) {
+        let d1_size = env.domain.d1.size;
+        let d = evaluation_domain(self.degree(d1_size), d1_size);
+        assert_eq!(
+            acc.domain(),
+            get_domain(d, env),
+            "the evaluations are accumulated over a different domain"
+        );
+        let scalars = self.scalars(&env.constants);
+
+        let register = match self.result {
+            Operand::Scalar(x) => {
+                let x = scalars[x];
+                acc.evals.par_iter_mut().for_each(|acc| *acc += x);
+                return;
+            }
+            Operand::Register(r) => r,
+        };
+
+        // the evaluations read by each instruction, with the step and offset to read them at
+        let lagrange: HashMap<usize, Evaluations<F, D<F>>> = self
+            .instructions
+            .iter()
+            .filter_map(|instruction| match instruction.op {
+                Op::UnnormalizedLagrangeBasis(i) => Some(i),
+                _ => None,
+            })
+            .map(|i| (i, unnormalized_lagrange_evals(env.l0_1, i, d, env)))
+            .collect();
+        let inputs: Vec<Option<(&Evaluations<F, D<F>>, usize, usize)>> = self
+            .instructions
+            .iter()
+            .map(|instruction| match &instruction.op {
+                Op::Cell(Variable { col, row }) => env.get_column(col).map(|evals| {
+                    let d_sub = col.domain() as usize;
+                    let scale = d_sub / d as usize;
+                    assert!(scale != 0);
+                    (evals, scale, d_sub * row.shift())
+                }),
+                Op::VanishesOnLast4Rows => {
+                    let scale = 8 / d as usize;
+                    assert!(scale != 0);
+                    Some((env.vanishes_on_last_4_rows, scale, 0))
+                }
+                Op::UnnormalizedLagrangeBasis(i) => Some((&lagrange[i], 1, 0)),
+                _ => None,
+            })
+            .collect();
+
+        acc.evals
+            .par_chunks_mut(CHUNK_SIZE)
+            .enumerate()
+            .for_each(|(chunk, res)| {
+                let start = chunk * CHUNK_SIZE;
+                let len = res.len();
+                let mut registers = vec![F::zero(); self.registers * len];
+
+                for (Instruction { dst, op }, input) in self.instructions.iter().zip(&inputs) {
+                    let dst = dst * len;
+                    match op {
+                        Op::Cell(_)
+                        | Op::VanishesOnLast4Rows
+                        | Op::UnnormalizedLagrangeBasis(_) => match input {
+                            // columns that are not present evaluate to zero
+                            None => registers[dst..dst + len].fill(F::zero()),
+                            Some((evals, step, offset)) => {
+                                let evals = &evals.evals;
+                                for (j, x) in registers[dst..dst + len].iter_mut().enumerate() {
+                                    *x = evals[(step * (start + j) + offset) % evals.len()];
+                                }
+                            }
+                        },
+                        Op::Add(x, y) => {
+                            binop(&mut registers, len, &scalars, dst, *x, *y, |x, y| x + y)
+                        }
+                        Op::Sub(x, y) => {
+                            binop(&mut registers, len, &scalars, dst, *x, *y, |x, y| x - y)
+                        }
+                        Op::Mul(x, y) => {
+                            binop(&mut registers, len, &scalars, dst, *x, *y, |x, y| x * y)
+                        }
+                        Op::Pow(x, n) => {
+                            let x = x * len;
+                            for j in 0..len {
+                                registers[dst + j] = registers[x + j].pow(&[*n]);
+                            }
+                        }
+                    }
+                }
+
+                let register = register * len;
+                for (acc, x) in res.iter_mut().zip(&registers[register..register + len]) {
+                    *acc += x;
+                }
+            });
+    }
+}
+
+/// Applies a binary operation to a chunk of rows.
+/// The destination register can be one of the operands, as each row is read before being written.
+fn binop<F: Field>(
+    registers: &mut [F],
+    len: usize,
+    scalars: &[F],
+    dst: usize,
+    x: Operand,
+    y: Operand,
+    f: impl Fn(F, F) -> F,
+) {
+    match (x, y) {
+        (Operand::Register(x), Operand::Register(y)) => {
+            let (x, y) = (x * len, y * len);
+            for j in 0..len {
+                registers[dst + j] = f(registers[x + j], registers[y + j]);
+            }
+        }
+        (Operand::Register(x), Operand::Scalar(y)) => {
+            let (x, y) = (x * len, scalars[y]);
+            for j in 0..len {
+                registers[dst + j] = f(registers[x + j], y);
+            }
+        }
+        (Operand::Scalar(x), Operand::Register(y)) => {
+            let (x, y) = (scalars[x], y * len);
+            for j in 0..len {
+                registers[dst + j] = f(x, registers[y + j]);
+            }
+        }
+        (Operand::Scalar(_), Operand::Scalar(_)) => {
+            unreachable!("operations on scalars are computed once, outside of the rows")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alphas::Alphas,
+        bench::ConstraintsEvaluationCtx,
+        circuits::{
+            argument::{Argument, ArgumentType},
+            expr::{prologue::*, Op2},
+            gate::GateType,
+            polynomials::{
+                chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
+                complete_add::CompleteAdd,
+                endomul_scalar::EndomulScalar,
+                endosclmul::EndosclMul,
+                poseidon::Poseidon,
+                varbasemul::VarbaseMul,
+            },
+        },
+    };
+    use mina_curves::pasta::fp::Fp;
+
+    #[test]
+    fn test_compiled_evaluations() {
+        let ctx = ConstraintsEvaluationCtx::new(1 << 4);
+        let env = ctx.env();
+
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(
+            ArgumentType::Gate(GateType::VarBaseMul),
+            VarbaseMul::<Fp>::CONSTRAINTS,
+        );
+
+        // an expression using all the kinds of operations
+        let misc: E<Fp> = Expr::UnnormalizedLagrangeBasis(1) * witness_curr(0)
+            + Expr::VanishesOnLast4Rows * witness_next(1).square()
+            - constant(Fp::from(3u32)) * witness_curr(2).double()
+            + Expr::BinOp(
+                Op2::Mul,
+                Box::new(constant(Fp::from(2u32))),
+                Box::new(constant(Fp::from(5u32))),
+            );
+
+        let exprs = vec![
+            Poseidon::combined_constraints(&alphas),
+            CompleteAdd::combined_constraints(&alphas),
+            VarbaseMul::combined_constraints(&alphas),
+            EndosclMul::combined_constraints(&alphas),
+            EndomulScalar::combined_constraints(&alphas),
+            ChaCha0::combined_constraints(&alphas),
+            ChaCha1::combined_constraints(&alphas),
+            ChaCha2::combined_constraints(&alphas),
+            ChaChaFinal::combined_constraints(&alphas),
+            misc,
+            constant(Fp::from(7u32)),
+        ];
+
+        for expr in exprs {
+            let compiled = CompiledExpr::compile_expr(&expr);
+            assert_eq!(
+                compiled.evaluations(&env).evals,
+                expr.evaluations(&env).evals
+            );
+
+            // expressions with caches are supported too
+            let (optimized, _) = expr.optimize();
+            let compiled = CompiledExpr::compile_expr(&optimized);
+            assert_eq!(
+                compiled.evaluations(&env).evals,
+                optimized.evaluations(&env).evals
+            );
+        }
+    }
+
+    #[test]
+    fn test_accumulated_evaluations() {
+        let ctx = ConstraintsEvaluationCtx::new(1 << 4);
+        let env = ctx.env();
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(
+            ArgumentType::Gate(GateType::VarBaseMul),
+            VarbaseMul::<Fp>::CONSTRAINTS,
+        );
+
+        let poseidon = CompiledExpr::compile_expr(&Poseidon::combined_constraints(&alphas));
+        let varbasemul = CompiledExpr::compile_expr(&VarbaseMul::combined_constraints(&alphas));
+        let seven = CompiledExpr::compile_expr(&constant(Fp::from(7u32)));
+        assert_eq!(poseidon.domain(&env), varbasemul.domain(&env));
+
+        let mut acc = poseidon.evaluations(&env);
+        varbasemul.add_evaluations_to(&env, &mut acc);
+        seven.add_evaluations_to(&env, &mut acc);
+
+        let expected = &(&poseidon.evaluations(&env) + &varbasemul.evaluations(&env))
+            + &Evaluations::from_vec_and_domain(
+                vec![Fp::from(7u32); acc.evals.len()],
+                acc.domain(),
+            );
+        assert_eq!(acc.evals, expected.evals);
+    }
+}
@@ -136,7 +136,7 @@ impl<F: FftField> CircuitGate<F> {
     pub fn verify(
         &self,
         row: usize,
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>],
         cs: &ConstraintSystem<F>,
         public: &[F],
     ) -> Result<(), String> {
@@ -233,8 +233,9 @@ pub mod caml {
         }
     }
 
-    /// helper to convert array to tuple (OCaml doesn't have fixed-size arrays)
-    fn array_to_tuple<T1, T2>(a: [T1; PERMUTS]) -> (T2, T2, T2, T2, T2, T2, T2)
+    /// helper to convert array to tuple (OCaml doesn't have fixed-size arrays).
+    /// The OCaml bindings only support the default [Width], so only the wires of the first [PERMUTS] columns are kept.
+    fn array_to_tuple<T1, T2>(a: [T1; COLUMNS]) -> (T2, T2, T2, T2, T2, T2, T2)
     where
         T1: Clone,
         T2: From<T1>,
@@ -245,12 +246,14 @@ pub mod caml {
             .expect("bug in array_to_tuple")
     }
 
-    /// helper to convert tuple to array (OCaml doesn't have fixed-size arrays)
-    fn tuple_to_array<T1, T2>(a: (T1, T1, T1, T1, T1, T1, T1)) -> [T2; PERMUTS]
+    /// helper to convert tuple to array (OCaml doesn't have fixed-size arrays).
+    /// The permutation of the default [Width] does not read the wires of the columns after the first [PERMUTS],
+    /// which are set to the first row.
+    fn tuple_to_array<T1>(a: (T1, T1, T1, T1, T1, T1, T1)) -> GateWires
     where
-        T2: From<T1>,
+        Wire: From<T1>,
     {
-        [
+        let wires: [Wire; PERMUTS] = [
             a.0.into(),
             a.1.into(),
             a.2.into(),
@@ -258,7 +261,8 @@ pub mod caml {
             a.4.into(),
             a.5.into(),
             a.6.into(),
-        ]
+        ];
+        array_init::array_init(|col| wires.get(col).copied().unwrap_or(Wire { row: 0, col }))
     }
 }
 
@@ -309,7 +313,7 @@ mod tests {
             let encoded = rmp_serde::to_vec(&cg).unwrap();
             let decoded: CircuitGate<Fp> = rmp_serde::from_read_ref(&encoded).unwrap();
             prop_assert_eq!(cg.typ, decoded.typ);
-            for i in 0..PERMUTS {
+            for i in 0..COLUMNS {
                 prop_assert_eq!(cg.wires[i], decoded.wires[i]);
             }
             prop_assert_eq!(cg.coeffs, decoded.coeffs);
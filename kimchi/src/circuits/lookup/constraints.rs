@@ -7,7 +7,6 @@ use crate::{
         lookup::lookups::{
             JointLookup, JointLookupSpec, JointLookupValue, LocalPosition, LookupInfo, LookupsUsed,
         },
-        wires::COLUMNS,
     },
     error::ProverError,
 };
@@ -22,21 +21,20 @@ use CurrOrNext::{Curr, Next};
 /// Number of constraints produced by the argument.
 pub const CONSTRAINTS: u32 = 7;
 
-/// The number of random values to append to columns for zero-knowledge.
-pub const ZK_ROWS: usize = 3;
-
-/// Pad with zeroes and then add 3 random elements in the last two
+/// Pad with zeroes and then add `zk_rows` random elements in the last
 /// rows for zero knowledge.
 pub fn zk_patch<R: Rng + ?Sized, F: FftField>(
     mut e: Vec<F>,
     d: D<F>,
+    zk_rows: u64,
     rng: &mut R,
 ) -> Evaluations<F, D<F>> {
     let n = d.size as usize;
+    let zk_rows = zk_rows as usize;
     let k = e.len();
-    assert!(k <= n - ZK_ROWS);
-    e.extend((0..((n - ZK_ROWS) - k)).map(|_| F::zero()));
-    e.extend((0..ZK_ROWS).map(|_| F::rand(rng)));
+    assert!(k <= n - zk_rows);
+    e.extend((0..((n - zk_rows) - k)).map(|_| F::zero()));
+    e.extend((0..zk_rows).map(|_| F::rand(rng)));
     Evaluations::<F, D<F>>::from_vec_and_domain(e, d)
 }
 
@@ -80,8 +78,9 @@ pub fn sorted<F>(
     dummy_lookup_value: F,
     joint_lookup_table_d8: &Evaluations<F, D<F>>,
     d1: D<F>,
+    zk_rows: u64,
     gates: &[CircuitGate<F>],
-    witness: &[Vec<F>; COLUMNS],
+    witness: &[Vec<F>],
     joint_combiner: F,
     table_id_combiner: F,
 ) -> Result<Vec<Vec<F>>, ProverError>
@@ -94,7 +93,7 @@ where
     let n = d1.size as usize;
     let mut counts: HashMap<&F, usize> = HashMap::new();
 
-    let lookup_rows = n - ZK_ROWS - 1;
+    let lookup_rows = n - zk_rows as usize - 1;
     let lookup_info = LookupInfo::<F>::create();
     let by_row = lookup_info.by_row(gates);
     let max_lookups_per_row = lookup_info.max_per_row;
@@ -220,8 +219,9 @@ pub fn aggregation<R, F>(
     dummy_lookup_value: F,
     joint_lookup_table_d8: &Evaluations<F, D<F>>,
     d1: D<F>,
+    zk_rows: u64,
     gates: &[CircuitGate<F>],
-    witness: &[Vec<F>; COLUMNS],
+    witness: &[Vec<F>],
     joint_combiner: &F,
     table_id_combiner: &F,
     beta: F,
@@ -234,7 +234,7 @@ where
     F: FftField,
 {
     let n = d1.size as usize;
-    let lookup_rows = n - ZK_ROWS - 1;
+    let lookup_rows = n - zk_rows as usize - 1;
     let beta1 = F::one() + beta;
     let gammabeta1 = gamma * beta1;
     let mut lookup_aggreg = vec![F::one()];
@@ -307,11 +307,11 @@ where
             lookup_aggreg[i + 1] *= prev;
         });
 
-    let res = zk_patch(lookup_aggreg, d1, rng);
+    let res = zk_patch(lookup_aggreg, d1, zk_rows, rng);
 
     // check that the final evaluation is equal to 1
     if cfg!(debug_assertions) {
-        let final_val = res.evals[d1.size() - (ZK_ROWS + 1)];
+        let final_val = res.evals[d1.size() - (zk_rows as usize + 1)];
         if final_val != F::one() {
             panic!("aggregation incorrect: {}", final_val);
         }
@@ -342,20 +342,24 @@ pub struct LookupConfiguration<F: FftField> {
 }
 
 /// Specifies the lookup constraints as expressions.
-pub fn constraints<F: FftField>(configuration: &LookupConfiguration<F>, d1: D<F>) -> Vec<E<F>> {
-    // Something important to keep in mind is that the last 2 rows of
+pub fn constraints<F: FftField>(
+    configuration: &LookupConfiguration<F>,
+    d1: D<F>,
+    zk_rows: u64,
+) -> Vec<E<F>> {
+    // Something important to keep in mind is that the last `zk_rows` rows of
     // all columns will have random values in them to maintain zero-knowledge.
     //
     // Another important thing to note is that there are no lookups permitted
-    // in the 3rd to last row.
+    // in the row preceding them.
     //
     // This is because computing the lookup-product requires
     // num_lookup_rows + 1
     // rows, so we need to have
-    // num_lookup_rows + 1 = n - 2 (the last 2 being reserved for the zero-knowledge random
+    // num_lookup_rows + 1 = n - zk_rows (the last `zk_rows` being reserved for the zero-knowledge random
     // values) and thus
     //
-    // num_lookup_rows = n - 3
+    // num_lookup_rows = n - zk_rows - 1
     let lookup_info = LookupInfo::<F>::create();
 
     let column = |col: Column| E::cell(col, Curr);
@@ -517,10 +521,10 @@ pub fn constraints<F: FftField>(configuration: &LookupConfiguration<F>, d1: D<F>
         - E::cell(Column::LookupAggreg, Curr) * numerator;
 
     let num_rows = d1.size();
-    let num_lookup_rows = num_rows - ZK_ROWS - 1;
+    let num_lookup_rows = num_rows - zk_rows as usize - 1;
 
     let mut res = vec![
-        // the accumulator except for the last 4 rows
+        // the accumulator except for the last `zk_rows + 1` rows
         // (contains the zk-rows and the last value of the accumulator)
         E::VanishesOnLast4Rows * aggreg_equation,
         // the initial value of the accumulator
@@ -556,8 +560,9 @@ pub fn verify<F: FftField, I: Iterator<Item = F>, G: Fn() -> I>(
     lookup_table: G,
     lookup_table_entries: usize,
     d1: D<F>,
+    zk_rows: u64,
     gates: &[CircuitGate<F>],
-    witness: &[Vec<F>; COLUMNS],
+    witness: &[Vec<F>],
     joint_combiner: &F,
     table_id_combiner: &F,
     sorted: &[Evaluations<F, D<F>>],
@@ -566,7 +571,7 @@ pub fn verify<F: FftField, I: Iterator<Item = F>, G: Fn() -> I>(
         .iter()
         .for_each(|s| assert_eq!(d1.size, s.domain().size));
     let n = d1.size as usize;
-    let lookup_rows = n - ZK_ROWS - 1;
+    let lookup_rows = n - zk_rows as usize - 1;
 
     // Check that the (desnakified) sorted table is
     // 1. Sorted
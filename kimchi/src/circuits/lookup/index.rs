@@ -1,12 +1,9 @@
-use crate::circuits::{domains::EvaluationDomains, gate::CircuitGate};
-use crate::circuits::{
-    lookup::{
-        constraints::LookupConfiguration,
-        lookups::{JointLookup, LookupInfo},
-        tables::LookupTable,
-    },
-    polynomials::permutation::ZK_ROWS,
+use crate::circuits::lookup::{
+    constraints::LookupConfiguration,
+    lookups::{JointLookup, LookupInfo},
+    tables::LookupTable,
 };
+use crate::circuits::{domains::EvaluationDomains, gate::CircuitGate};
 use ark_ff::{FftField, SquareRootField};
 use ark_poly::{
     univariate::DensePolynomial as DP, EvaluationDomain, Evaluations as E,
@@ -65,6 +62,7 @@ impl<F: FftField + SquareRootField> LookupConstraintSystem<F> {
         gates: &[CircuitGate<F>],
         lookup_tables: Vec<LookupTable<F>>,
         domain: &EvaluationDomains<F>,
+        zk_rows: u64,
     ) -> Result<Option<Self>, LookupError> {
         let lookup_info = LookupInfo::<F>::create();
 
@@ -75,10 +73,10 @@ impl<F: FftField + SquareRootField> LookupConstraintSystem<F> {
                 let d1_size = domain.d1.size();
 
                 // The maximum number of entries that can be provided across all tables.
-                // Since we do not assert the lookup constraint on the final `ZK_ROWS` rows, and
+                // Since we do not assert the lookup constraint on the final `zk_rows` rows, and
                 // because the row before is used to assert that the lookup argument's final
                 // product is 1, we cannot use those rows to store any values.
-                let max_num_entries = d1_size - (ZK_ROWS as usize) - 1;
+                let max_num_entries = d1_size - (zk_rows as usize) - 1;
 
                 //~ 2. Get the lookup selectors and lookup tables (TODO: how?)
                 let (lookup_selectors, gate_lookup_tables) =
@@ -9,8 +9,8 @@ use ark_poly::{univariate::DensePolynomial, Evaluations, Radix2EvaluationDomain
 /// Evaluations of the wires and permutation
 #[derive(Clone)]
 pub struct WitnessEvals<F: FftField> {
-    /// wire evaluations
-    pub w: [Evaluations<F, D<F>>; COLUMNS],
+    /// wire evaluations, one per column of the circuit
+    pub w: Vec<Evaluations<F, D<F>>>,
     /// permutation evaluations
     pub z: Evaluations<F, D<F>>,
 }
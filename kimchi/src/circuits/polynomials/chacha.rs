@@ -472,13 +472,13 @@ mod tests {
         circuits::{
             expr::{Column, Constants, PolishToken},
             lookup::lookups::LookupInfo,
+            polynomials::permutation::ZK_ROWS,
             wires::*,
         },
         proof::{LookupEvaluations, ProofEvaluations},
     };
     use ark_ff::UniformRand;
     use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
-    use array_init::array_init;
     use mina_curves::pasta::fp::Fp as F;
     use rand::{rngs::StdRng, SeedableRng};
     use std::fmt::{Display, Formatter};
@@ -540,9 +540,9 @@ mod tests {
 
         let pt = F::rand(rng);
         let mut eval = || ProofEvaluations {
-            w: array_init(|_| F::rand(rng)),
+            w: (0..COLUMNS).map(|_| F::rand(rng)).collect(),
             z: F::rand(rng),
-            s: array_init(|_| F::rand(rng)),
+            s: (0..PERMUTS - 1).map(|_| F::rand(rng)).collect(),
             generic_selector: F::zero(),
             poseidon_selector: F::zero(),
             lookup: Some(LookupEvaluations {
@@ -562,6 +562,7 @@ mod tests {
             joint_combiner: None,
             endo_coefficient: F::zero(),
             mds: vec![],
+            zk_rows: ZK_ROWS,
         };
 
         assert_eq!(
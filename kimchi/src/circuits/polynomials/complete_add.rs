@@ -19,7 +19,6 @@ use crate::circuits::{
     argument::{Argument, ArgumentType},
     expr::{prologue::*, Cache},
     gate::{CircuitGate, GateType},
-    wires::COLUMNS,
 };
 use ark_ff::{FftField, Field, One};
 
@@ -221,11 +220,7 @@ where
 
 impl<F: FftField> CircuitGate<F> {
     /// Check the correctness of witness values for a complete-add gate.
-    pub fn verify_complete_add(
-        &self,
-        row: usize,
-        witness: &[Vec<F>; COLUMNS],
-    ) -> Result<(), String> {
+    pub fn verify_complete_add(&self, row: usize, witness: &[Vec<F>]) -> Result<(), String> {
         let x1 = witness[0][row];
         let y1 = witness[1][row];
         let x2 = witness[2][row];
@@ -17,7 +17,7 @@ impl<F: FftField> CircuitGate<F> {
     pub fn verify_endomul_scalar(
         &self,
         row: usize,
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>],
         _cs: &ConstraintSystem<F>,
     ) -> Result<(), String> {
         ensure_eq!(self.typ, GateType::EndoMulScalar, "incorrect gate type");
@@ -122,7 +122,7 @@ impl<F: FftField> CircuitGate<F> {
     pub fn verify_endomul(
         &self,
         row: usize,
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>],
         cs: &ConstraintSystem<F>,
     ) -> Result<(), String> {
         ensure_eq!(self.typ, GateType::EndoMul, "incorrect gate type");
@@ -138,6 +138,7 @@ impl<F: FftField> CircuitGate<F> {
             gamma: F::zero(),
             joint_combiner: None,
             mds: vec![],
+            zk_rows: cs.zk_rows,
             endo_coefficient: cs.endo,
         };
 
@@ -164,11 +164,15 @@ impl<F: FftField> CircuitGate<F> {
 //~ where the $c_i$ are the [coefficients]().
 
 impl<F: FftField + SquareRootField> ConstraintSystem<F> {
-    /// generic constraint quotient poly contribution computation
+    /// generic constraint quotient poly contribution computation,
+    /// given the evaluations of the first `2 * GENERIC_COEFFS` coefficient polynomials over $d_8$
+    /// and of the generic selector over $d_4$
     pub fn gnrc_quot(
         &self,
         mut alphas: impl Iterator<Item = F>,
-        witness_cols_d4: &[Evaluations<F, D<F>>; COLUMNS],
+        witness_cols_d4: &[Evaluations<F, D<F>>],
+        coefficients8: &[Evaluations<F, D<F>>],
+        generic4: &Evaluations<F, D<F>>,
     ) -> Evaluations<F, D<F>> {
         let generic_gate = |alpha_pow, coeff_offset, register_offset| {
             let mut res = Evaluations::from_vec_and_domain(
@@ -180,7 +184,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
             for (witness_d4, selector_d8) in witness_cols_d4
                 .iter()
                 .skip(register_offset)
-                .zip(self.coefficients8.iter().skip(coeff_offset))
+                .zip(coefficients8.iter().skip(coeff_offset))
                 .take(GENERIC_REGISTERS)
             {
                 res.evals
@@ -191,7 +195,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
 
             // multiplication
             let mut mul = &witness_cols_d4[register_offset] * &witness_cols_d4[register_offset + 1];
-            let mul_selector_d8 = &self.coefficients8[coeff_offset + 3];
+            let mul_selector_d8 = &coefficients8[coeff_offset + 3];
             mul.evals
                 .par_iter_mut()
                 .enumerate()
@@ -199,7 +203,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
             res += &mul;
 
             // constant
-            let constant_d8 = &self.coefficients8[coeff_offset + 4];
+            let constant_d8 = &coefficients8[coeff_offset + 4];
             res.evals
                 .par_iter_mut()
                 .enumerate()
@@ -226,7 +230,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         res += &generic_gate(alpha_pow2, GENERIC_COEFFS, GENERIC_REGISTERS);
 
         // generic selector
-        &res * &self.generic4
+        &res * generic4
     }
 
     /// produces
@@ -239,7 +243,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     /// ```
     pub fn gnrc_scalars(
         mut alphas: impl Iterator<Item = F>,
-        w_zeta: &[F; COLUMNS],
+        w_zeta: &[F],
         generic_zeta: F,
     ) -> Vec<F> {
         // setup
@@ -277,7 +281,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     pub fn gnrc_lnrz(
         &self,
         alphas: impl Iterator<Item = F>,
-        w_zeta: &[F; COLUMNS],
+        w_zeta: &[F],
         generic_zeta: F,
     ) -> Evaluations<F, D<F>> {
         let d1 = self.domain.d1;
@@ -289,13 +293,16 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         //
         let mut res = Evaluations::from_vec_and_domain(vec![F::zero(); n], d1);
 
-        let scale = self.coefficients8[0].evals.len() / n;
-
-        let coeffs = self.coefficients8.iter();
-        for (scalar, coeff) in scalars.into_iter().zip(coeffs) {
-            res.evals.par_iter_mut().enumerate().for_each(|(i, e)| {
-                *e += scalar * coeff[scale * i];
-            });
+        // the coefficients over d1 are the ones of the gates
+        for (col, scalar) in scalars.into_iter().enumerate() {
+            res.evals
+                .par_iter_mut()
+                .zip(&self.gates)
+                .for_each(|(e, gate)| {
+                    if let Some(coeff) = gate.coeffs.get(col) {
+                        *e += scalar * coeff;
+                    }
+                });
         }
 
         // l * qwm[0] + r * qwm[1] + o * qwm[2] + l * r * qmm + qc
@@ -315,7 +322,7 @@ pub mod testing {
         pub fn verify_generic(
             &self,
             row: usize,
-            witness: &[Vec<F>; COLUMNS],
+            witness: &[Vec<F>],
             public: &[F],
         ) -> Result<(), String> {
             // assignments
@@ -356,11 +363,13 @@ pub mod testing {
         /// Function to verify the generic polynomials with a witness.
         pub fn verify_generic(
             &self,
-            witness: &[DensePolynomial<F>; COLUMNS],
+            witness: &[DensePolynomial<F>],
             public: &DensePolynomial<F>,
         ) -> bool {
-            let coefficientsm: [_; COLUMNS] =
-                array_init(|i| self.coefficients8[i].clone().interpolate());
+            let coefficientsm: [_; COLUMNS] = array_init(|i| {
+                self.coefficient_evaluations(i, self.domain.d1)
+                    .interpolate()
+            });
 
             let generic_gate = |coeff_offset, register_offset| {
                 // addition (of left, right, output wires)
@@ -530,7 +539,13 @@ mod tests {
 
         // compute quotient by dividing with vanishing polynomial
         let alphas = vec![Fp::rand(rng), Fp::rand(rng)];
-        let t1 = cs.gnrc_quot(&mut alphas.clone().into_iter(), &witness_d4);
+        let column_evaluations = cs.compute_column_evaluations();
+        let t1 = cs.gnrc_quot(
+            &mut alphas.clone().into_iter(),
+            &witness_d4,
+            &column_evaluations.coefficients8,
+            &column_evaluations.generic4,
+        );
         let t_before_division = &t1.interpolate() + &public;
         let (t, rem) = t_before_division
             .divide_by_vanishing_poly(cs.domain.d1)
@@ -3,30 +3,19 @@
 //~
 //~ The permutation constraints are the following 4 constraints:
 //~
-//~ The two sides of the coin (with $\text{shift}_0 = 1$):
+//~ The two sides of the coin (with $\text{shift}_0 = 1$),
+//~ over the $p$ permuted columns of the circuit ($p = 7$ by default):
 //~
 //~ $$\begin{align}
 //~     & z(x) \cdot zkpm(x) \cdot \alpha^{PERM0} \cdot \\
-//~     & (w_0(x) + \beta \cdot \text{shift}_0 x + \gamma) \cdot \\
-//~     & (w_1(x) + \beta \cdot \text{shift}_1 x + \gamma) \cdot \\
-//~     & (w_2(x) + \beta \cdot \text{shift}_2 x + \gamma) \cdot \\
-//~     & (w_3(x) + \beta \cdot \text{shift}_3 x + \gamma) \cdot \\
-//~     & (w_4(x) + \beta \cdot \text{shift}_4 x + \gamma) \cdot \\
-//~     & (w_5(x) + \beta \cdot \text{shift}_5 x + \gamma) \cdot \\
-//~     & (w_6(x) + \beta \cdot \text{shift}_6 x + \gamma)
+//~     & \prod_{i=0}^{p-1} (w_i(x) + \beta \cdot \text{shift}_i x + \gamma)
 //~ \end{align}$$
 //~
 //~ and
 //~
 //~ $$\begin{align}
 //~ & -1 \cdot z(x \omega) \cdot zkpm(x) \cdot \alpha^{PERM0} \cdot \\
-//~ & (w_0(x) + \beta \cdot \sigma_0(x) + \gamma) \cdot \\
-//~ & (w_1(x) + \beta \cdot \sigma_1(x) + \gamma) \cdot \\
-//~ & (w_2(x) + \beta \cdot \sigma_2(x) + \gamma) \cdot \\
-//~ & (w_3(x) + \beta \cdot \sigma_3(x) + \gamma) \cdot \\
-//~ & (w_4(x) + \beta \cdot \sigma_4(x) + \gamma) \cdot \\
-//~ & (w_5(x) + \beta \cdot \sigma_5(x) + \gamma) \cdot \\
-//~ & (w_6(x) + \beta \cdot \sigma_6(x) + \gamma) \cdot
+//~ & \prod_{i=0}^{p-1} (w_i(x) + \beta \cdot \sigma_i(x) + \gamma)
 //~ \end{align}$$
 //~
 //~ the initialization of the accumulator:
@@ -45,7 +34,7 @@ use crate::{
     error::ProverError,
     proof::ProofEvaluations,
 };
-use ark_ff::{FftField, SquareRootField, Zero};
+use ark_ff::{FftField, One, SquareRootField, Zero};
 use ark_poly::{
     univariate::{DenseOrSparsePolynomial, DensePolynomial},
     EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
@@ -53,82 +42,129 @@ use ark_poly::{
 use ark_poly::{Polynomial, UVPolynomial};
 use o1_utils::{ExtendedDensePolynomial, ExtendedEvaluations};
 use rand::{CryptoRng, RngCore};
+use std::borrow::Cow;
 
 /// Number of constraints produced by the argument.
 pub const CONSTRAINTS: u32 = 3;
+
+/// The default number of rows at the end of the domain reserved for zero-knowledge
+/// (see [ConstraintSystem::zk_rows]).
+/// With 3 rows, the permutation polynomial gets the 2 random evaluations it needs to hide the witness,
+/// see <https://www.plonk.cafe/t/noob-questions-plonk-paper/73>.
 pub const ZK_ROWS: u64 = 3;
+
+/// The maximum number of rows reserved for zero-knowledge.
+/// The permutation constraint is multiplied by the zero-knowledge polynomial,
+/// of degree the number of zero-knowledge rows,
+/// and must still fit in the quotient polynomial.
+pub const MAX_ZK_ROWS: u64 = 7;
+
+/// Returns the `zk_rows + 1` last elements of the domain, starting from the last one `w^{n - 1}`
+fn last_rows<F: FftField>(domain: D<F>, zk_rows: u64) -> impl Iterator<Item = F> {
+    std::iter::successors(Some(domain.group_gen_inv), move |w| {
+        Some(*w * domain.group_gen_inv)
+    })
+    .take(zk_rows as usize + 1)
+}
+
 /// Evaluates the polynomial
-/// (x - w^{n - 4}) (x - w^{n - 3}) * (x - w^{n - 2}) * (x - w^{n - 1})
-pub fn eval_vanishes_on_last_4_rows<F: FftField>(domain: D<F>, x: F) -> F {
-    let w4 = domain.group_gen.pow(&[domain.size - (ZK_ROWS + 1)]);
-    let w3 = domain.group_gen * w4;
-    let w2 = domain.group_gen * w3;
-    let w1 = domain.group_gen * w2;
-    (x - w1) * (x - w2) * (x - w3) * (x - w4)
+/// (x - w^{n - zk_rows - 1}) * ... * (x - w^{n - 2}) * (x - w^{n - 1})
+pub fn eval_vanishes_on_last_4_rows<F: FftField>(domain: D<F>, zk_rows: u64, x: F) -> F {
+    last_rows(domain, zk_rows).fold(F::one(), |acc, w| acc * (x - w))
 }
 
 /// The polynomial
-/// (x - w^{n - 4}) (x - w^{n - 3}) * (x - w^{n - 2}) * (x - w^{n - 1})
-pub fn vanishes_on_last_4_rows<F: FftField>(domain: D<F>) -> DensePolynomial<F> {
+/// (x - w^{n - zk_rows - 1}) * ... * (x - w^{n - 2}) * (x - w^{n - 1}),
+/// which vanishes on the zero-knowledge rows and on the row preceding them
+/// (the last 4 rows with the default [ZK_ROWS])
+pub fn vanishes_on_last_4_rows<F: FftField>(domain: D<F>, zk_rows: u64) -> DensePolynomial<F> {
     let x = DensePolynomial::from_coefficients_slice(&[F::zero(), F::one()]);
-    let c = |a: F| DensePolynomial::from_coefficients_slice(&[a]);
-    let w4 = domain.group_gen.pow(&[domain.size - (ZK_ROWS + 1)]);
-    let w3 = domain.group_gen * w4;
-    let w2 = domain.group_gen * w3;
-    let w1 = domain.group_gen * w2;
-    &(&(&x - &c(w1)) * &(&x - &c(w2))) * &(&(&x - &c(w3)) * &(&x - &c(w4)))
+    last_rows(domain, zk_rows).fold(
+        DensePolynomial::from_coefficients_slice(&[F::one()]),
+        |acc, w| &acc * &(&x - &DensePolynomial::from_coefficients_slice(&[w])),
+    )
 }
 
-/// Returns the end of the circuit, which is used for introducing zero-knowledge in the permutation polynomial
-pub fn zk_w3<F: FftField>(domain: D<F>) -> F {
-    domain.group_gen.pow(&[domain.size - (ZK_ROWS)])
+/// Returns the end of the circuit, `w^{n - zk_rows}`,
+/// which is used for introducing zero-knowledge in the permutation polynomial
+pub fn zk_w3<F: FftField>(domain: D<F>, zk_rows: u64) -> F {
+    domain.group_gen.pow(&[domain.size - zk_rows])
 }
 
 /// Evaluates the polynomial
-/// (x - w^{n - 3}) * (x - w^{n - 2}) * (x - w^{n - 1})
-pub fn eval_zk_polynomial<F: FftField>(domain: D<F>, x: F) -> F {
-    let w3 = zk_w3(domain);
-    let w2 = domain.group_gen * w3;
-    let w1 = domain.group_gen * w2;
-    (x - w1) * (x - w2) * (x - w3)
+/// (x - w^{n - zk_rows}) * ... * (x - w^{n - 2}) * (x - w^{n - 1})
+pub fn eval_zk_polynomial<F: FftField>(domain: D<F>, zk_rows: u64, x: F) -> F {
+    last_rows(domain, zk_rows)
+        .take(zk_rows as usize)
+        .fold(F::one(), |acc, w| acc * (x - w))
 }
 
-/// Computes the zero-knowledge polynomial for blinding the permutation polynomial: `(x-w^{n-k})(x-w^{n-k-1})...(x-w^n)`.
-/// Currently, we use k = 3 for 2 blinding factors,
-/// see <https://www.plonk.cafe/t/noob-questions-plonk-paper/73>
-pub fn zk_polynomial<F: FftField>(domain: D<F>) -> DensePolynomial<F> {
-    let w3 = zk_w3(domain);
-    let w2 = domain.group_gen * w3;
-    let w1 = domain.group_gen * w2;
-
-    // (x-w3)(x-w2)(x-w1) =
-    // x^3 - x^2(w1+w2+w3) + x(w1w2+w1w3+w2w3) - w1w2w3
-    let w1w2 = w1 * w2;
-    DensePolynomial::from_coefficients_slice(&[
-        -w1w2 * w3,                   // 1
-        w1w2 + (w1 * w3) + (w3 * w2), // x
-        -w1 - w2 - w3,                // x^2
-        F::one(),                     // x^3
-    ])
+/// Computes the zero-knowledge polynomial for blinding the permutation polynomial:
+/// `(x-w^{n-zk_rows})...(x-w^{n-2})(x-w^{n-1})`.
+/// The permutation constraint does not apply to these `zk_rows` rows,
+/// the last `zk_rows - 1` of which are random.
+pub fn zk_polynomial<F: FftField>(domain: D<F>, zk_rows: u64) -> DensePolynomial<F> {
+    let x = DensePolynomial::from_coefficients_slice(&[F::zero(), F::one()]);
+    last_rows(domain, zk_rows).take(zk_rows as usize).fold(
+        DensePolynomial::from_coefficients_slice(&[F::one()]),
+        |acc, w| &acc * &(&x - &DensePolynomial::from_coefficients_slice(&[w])),
+    )
 }
 
 impl<F: FftField + SquareRootField> ConstraintSystem<F> {
-    /// permutation quotient poly contribution computation
-    #[allow(clippy::type_complexity)]
+    /// permutation quotient poly contribution computation,
+    /// evaluated over the domain of the permutation argument (see [ConstraintSystem::permutation_domain]),
+    /// over which `sigmal8` must be evaluated
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
     pub fn perm_quot(
         &self,
         lagrange: &WitnessOverDomains<F>,
+        witness: &[DensePolynomial<F>],
         beta: F,
         gamma: F,
         z: &DensePolynomial<F>,
         mut alphas: impl Iterator<Item = F>,
+        sigmal8: &[Evaluations<F, D<F>>],
     ) -> Result<(Evaluations<F, D<F>>, DensePolynomial<F>), ProverError> {
         let alpha0 = alphas.next().expect("missing power of alpha");
         let alpha1 = alphas.next().expect("missing power of alpha");
         let alpha2 = alphas.next().expect("missing power of alpha");
 
-        // constant gamma in evaluation form (in domain d8)
-        let gamma = &self.precomputations().constant_1_d8.scale(gamma);
+        // the witness, z(x), z(x * w), x and zkpl(x) in evaluation form,
+        // over d8 unless more than PERMUTS columns are permuted
+        let domain = self.permutation_domain();
+        let (w, z_this, z_next, x, zkpl) = if self.width.permuts <= PERMUTS {
+            (
+                Cow::Borrowed(&lagrange.d8.this.w[..]),
+                Cow::Borrowed(&lagrange.d8.this.z),
+                Cow::Borrowed(&lagrange.d8.next.z),
+                Cow::Borrowed(&self.precomputations().poly_x_d1),
+                Cow::Borrowed(&self.precomputations().zkpl),
+            )
+        } else {
+            let rate = (domain.size / self.domain.d1.size) as usize;
+            let w: Vec<_> = witness[..self.width.permuts]
+                .iter()
+                .map(|w| w.evaluate_over_domain_by_ref(domain))
+                .collect();
+            let z_this = z.evaluate_over_domain_by_ref(domain);
+            let z_next = z_this.shift(rate);
+            let x = Evaluations::from_vec_and_domain(domain.elements().collect(), domain);
+            let zkpl = self
+                .precomputations()
+                .zkpm
+                .evaluate_over_domain_by_ref(domain);
+            (
+                Cow::Owned(w),
+                Cow::Owned(z_this),
+                Cow::Owned(z_next),
+                Cow::Owned(x),
+                Cow::Owned(zkpl),
+            )
+        };
+
+        // constant gamma in evaluation form
+        let gamma = &Evaluations::from_vec_and_domain(vec![gamma; domain.size()], domain);
 
         //~ The quotient contribution of the permutation is split into two parts $perm$ and $bnd$.
         //~ They will be used by the prover.
@@ -137,52 +173,41 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         //~ \begin{align}
         //~ perm(x) =
         //~     & \; a^{PERM0} \cdot zkpl(x) \cdot [ \\
-        //~     & \;\;   z(x) \cdot \\
-        //~     & \;\;   (w_0(x) + \gamma + x \cdot \beta \cdot \text{shift}_0) \cdot \\
-        //~     & \;\;   (w_1(x) + \gamma + x \cdot \beta \cdot \text{shift}_1) \cdot \\
-        //~     & \;\;   (w_2(x) + \gamma + x \cdot \beta \cdot \text{shift}_2) \cdot \\
-        //~     & \;\;   (w_3(x) + \gamma + x \cdot \beta \cdot \text{shift}_3) \cdot \\
-        //~     & \;\;   (w_4(x) + \gamma + x \cdot \beta \cdot \text{shift}_4) \cdot \\
-        //~     & \;\;   (w_5(x) + \gamma + x \cdot \beta \cdot \text{shift}_5) \cdot \\
-        //~     & \;\;   (w_6(x) + \gamma + x \cdot \beta \cdot \text{shift}_6) \cdot \\
+        //~     & \;\;   z(x) \cdot \prod_{i=0}^{p-1} (w_i(x) + \gamma + x \cdot \beta \cdot \text{shift}_i) \\
         //~     & \;   - \\
-        //~     & \;\;   z(x \cdot w) \cdot \\
-        //~     & \;\;   (w_0(x) + \gamma + \sigma_0 \cdot \beta) \cdot \\
-        //~     & \;\;   (w_1(x) + \gamma + \sigma_1 \cdot \beta) \cdot \\
-        //~     & \;\;   (w_2(x) + \gamma + \sigma_2 \cdot \beta) \cdot \\
-        //~     & \;\;   (w_3(x) + \gamma + \sigma_3 \cdot \beta) \cdot \\
-        //~     & \;\;   (w_4(x) + \gamma + \sigma_4 \cdot \beta) \cdot \\
-        //~     & \;\;   (w_5(x) + \gamma + \sigma_5 \cdot \beta) \cdot \\
-        //~     & \;\;   (w_6(x) + \gamma + \sigma_6 \cdot \beta) \cdot \\
+        //~     & \;\;   z(x \cdot w) \cdot \prod_{i=0}^{p-1} (w_i(x) + \gamma + \sigma_i \cdot \beta) \\
         //~     &]
         //~ \end{align}
         //~ $$
         //~
+        //~ where $p$ is the number of permuted columns ($p = 7$ by default).
+        //~ The degree of $perm$ is about $(p + 1) n$:
+        //~ it is evaluated over $d_8$ if $p \leq 7$, and over a domain of size $16n$ otherwise.
+        //~
         let perm = {
             // shifts = z(x) *
             // (w[0](x) + gamma + x * beta * shift[0]) *
             // (w[1](x) + gamma + x * beta * shift[1]) * ...
-            // (w[6](x) + gamma + x * beta * shift[6])
-            // in evaluation form in d8
-            let mut shifts = lagrange.d8.this.z.clone();
-            for (witness, shift) in lagrange.d8.this.w.iter().zip(self.shift.iter()) {
-                let term =
-                    &(witness + gamma) + &self.precomputations().poly_x_d1.scale(beta * shift);
+            // (w[p-1](x) + gamma + x * beta * shift[p-1])
+            // in evaluation form
+            let mut shifts = z_this.into_owned();
+            for (witness, shift) in w.iter().zip(self.shift.iter()) {
+                let term = &(witness + gamma) + &x.scale(beta * shift);
                 shifts = &shifts * &term;
             }
 
             // sigmas = z(x * w) *
-            // (w8[0] + gamma + sigma[0] * beta) *
-            // (w8[1] + gamma + sigma[1] * beta) * ...
-            // (w8[6] + gamma + sigma[6] * beta)
-            // in evaluation form in d8
-            let mut sigmas = lagrange.d8.next.z.clone();
-            for (witness, sigma) in lagrange.d8.this.w.iter().zip(self.sigmal8.iter()) {
+            // (w[0] + gamma + sigma[0] * beta) *
+            // (w[1] + gamma + sigma[1] * beta) * ...
+            // (w[p-1] + gamma + sigma[p-1] * beta)
+            // in evaluation form
+            let mut sigmas = z_next.into_owned();
+            for (witness, sigma) in w.iter().zip(sigmal8.iter()) {
                 let term = witness + &(gamma + &sigma.scale(beta));
                 sigmas = &sigmas * &term;
             }
 
-            &(&shifts - &sigmas).scale(alpha0) * &self.precomputations().zkpl
+            &(&shifts - &sigmas).scale(alpha0) * &*zkpl
         };
 
         //~ and `bnd`:
@@ -208,9 +233,9 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
                 return Err(ProverError::Permutation("first division rest"));
             }
 
-            // accumulator end := (z(x) - 1) / (x - sid[n-3])
+            // accumulator end := (z(x) - 1) / (x - sid[n-k])
             let denominator = DensePolynomial::from_coefficients_slice(&[
-                -self.sid[self.domain.d1.size as usize - 3],
+                -zk_w3(self.domain.d1, self.zk_rows),
                 F::one(),
             ]);
             let (bnd2, res) = DenseOrSparsePolynomial::divide_with_q_and_r(
@@ -241,11 +266,11 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         //~
         //~ The linearization:
         //~
-        //~ $\text{scalar} \cdot \sigma_6(x)$
+        //~ $\text{scalar} \cdot \sigma_{p-1}(x)$
         //~
         let zkpm_zeta = self.precomputations().zkpm.evaluate(&zeta);
         let scalar = Self::perm_scalars(e, beta, gamma, alphas, zkpm_zeta);
-        self.sigmam[PERMUTS - 1].scale(scalar)
+        self.sigmam[self.width.permuts - 1].scale(scalar)
     }
 
     pub fn perm_scalars(
@@ -270,12 +295,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         //~ $$
         //~ \begin{align}
         //~ z(\zeta \omega) \beta \alpha^{PERM0} zkpl(\zeta) \cdot \\
-        //~ (\gamma + \beta \sigma_0(\zeta) + w_0(\zeta)) \cdot \\
-        //~ (\gamma + \beta \sigma_1(\zeta) + w_1(\zeta)) \cdot \\
-        //~ (\gamma + \beta \sigma_2(\zeta) + w_2(\zeta)) \cdot \\
-        //~ (\gamma + \beta \sigma_3(\zeta) + w_3(\zeta)) \cdot \\
-        //~ (\gamma + \beta \sigma_4(\zeta) + w_4(\zeta)) \cdot \\
-        //~ (\gamma + \beta \sigma_5(\zeta) + w_5(\zeta)) \cdot \\
+        //~ \prod_{i=0}^{p-2} (\gamma + \beta \sigma_i(\zeta) + w_i(\zeta)) \\
         //~ \end{align}
         //~$$
         //~
@@ -292,12 +312,13 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
     /// permutation aggregation polynomial computation
     pub fn perm_aggreg(
         &self,
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>],
         beta: &F,
         gamma: &F,
         rng: &mut (impl RngCore + CryptoRng),
     ) -> Result<DensePolynomial<F>, ProverError> {
         let n = self.domain.d1.size as usize;
+        let zk_rows = self.zk_rows as usize;
 
         // only works if first element is 1
         assert_eq!(self.domain.d1.elements().next(), Some(F::one()));
@@ -308,9 +329,12 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         //~ The first evaluation represents the initial value of the accumulator:
         //~ $$z(g^0) = 1$$
 
-        let mut z = vec![F::one(); n];
+        // without zero-knowledge rows, the accumulator wraps around the domain,
+        // and the extra evaluation $z(g^n) = z(g^0)$ is only used for the final check
+        let mut z = vec![F::one(); n + 1];
 
-        //~ For $i = 0, \cdot, n - 4$, where $n$ is the size of the domain,
+        //~ For $i = 0, \cdot, n - k - 1$, where $n$ is the size of the domain
+        //~ and $k$ the number of zero-knowledge rows,
         //~ evaluations are computed as:
         //~
         //~ $$z(g^{i+1}) = z_1 / z_2$$
@@ -319,13 +343,7 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         //~
         //~ $$
         //~ \begin{align}
-        //~ z_1 = &\ (w_0(g^i + sid(g^i) \cdot beta \cdot shift_0 + \gamma) \cdot \\
-        //~ &\ (w_1(g^i) + sid(g^i) \cdot beta \cdot shift_1 + \gamma) \cdot \\
-        //~ &\ (w_2(g^i) + sid(g^i) \cdot beta \cdot shift_2 + \gamma) \cdot \\
-        //~ &\ (w_3(g^i) + sid(g^i) \cdot beta \cdot shift_3 + \gamma) \cdot \\
-        //~ &\ (w_4(g^i) + sid(g^i) \cdot beta \cdot shift_4 + \gamma) \cdot \\
-        //~ &\ (w_5(g^i) + sid(g^i) \cdot beta \cdot shift_5 + \gamma) \cdot \\
-        //~ &\ (w_6(g^i) + sid(g^i) \cdot beta \cdot shift_6 + \gamma)
+        //~ z_1 = &\ \prod_{j=0}^{p-1} (w_j(g^i) + sid(g^i) \cdot beta \cdot shift_j + \gamma)
         //~ \end{align}
         //~ $$
         //~
@@ -333,18 +351,14 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
         //~
         //~ $$
         //~ \begin{align}
-        //~ z_2 = &\ (w_0(g^i) + \sigma_0 \cdot beta + \gamma) \cdot \\
-        //~ &\ (w_1(g^i) + \sigma_1 \cdot beta + \gamma) \cdot \\
-        //~ &\ (w_2(g^i) + \sigma_2 \cdot beta + \gamma) \cdot \\
-        //~ &\ (w_3(g^i) + \sigma_3 \cdot beta + \gamma) \cdot \\
-        //~ &\ (w_4(g^i) + \sigma_4 \cdot beta + \gamma) \cdot \\
-        //~ &\ (w_5(g^i) + \sigma_5 \cdot beta + \gamma) \cdot \\
-        //~ &\ (w_6(g^i) + \sigma_6 \cdot beta + \gamma)
+        //~ z_2 = &\ \prod_{j=0}^{p-1} (w_j(g^i) + \sigma_j \cdot beta + \gamma)
         //~ \end{align}
         //~ $$
         //~
+        //~ where $p$ is the number of permuted columns.
+        //~
         //~
-        for j in 0..n - 3 {
+        for j in 0..n - zk_rows {
             z[j + 1] = witness
                 .iter()
                 .zip(self.sigmal1.iter())
@@ -352,9 +366,9 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
                 .fold(F::one(), |x, y| x * y)
         }
 
-        ark_ff::fields::batch_inversion::<F>(&mut z[1..=n - 3]);
+        ark_ff::fields::batch_inversion::<F>(&mut z[1..=n - zk_rows]);
 
-        for j in 0..n - 3 {
+        for j in 0..n - zk_rows {
             let x = z[j];
             z[j + 1] *= witness
                 .iter()
@@ -363,16 +377,20 @@ impl<F: FftField + SquareRootField> ConstraintSystem<F> {
                 .fold(x, |z, y| z * y)
         }
 
-        //~ If computed correctly, we should have $z(g^{n-3}) = 1$.
+        //~ If computed correctly, we should have $z(g^{n-k}) = 1$.
         //~
-        if z[n - 3] != F::one() {
+        if z[n - zk_rows] != F::one() {
             return Err(ProverError::Permutation("final value"));
         };
 
-        //~ Finally, randomize the last `EVAL_POINTS` evaluations $z(g^{n-2})$ and $z(g^{n-1})$,
+        //~ Finally, randomize the last $k - 1$ evaluations $z(g^{n-k+1}), \cdots, z(g^{n-1})$,
         //~ in order to add zero-knowledge to the protocol.
-        z[n - 2] = F::rand(rng);
-        z[n - 1] = F::rand(rng);
+        //~ In the non-hiding mode, $k = 0$ and the check above is $z(g^n) = z(g^0) = 1$,
+        //~ so that no evaluation is randomized.
+        z.truncate(n);
+        for value in z.iter_mut().skip(n - zk_rows + 1) {
+            *value = F::rand(rng);
+        }
 
         let res = Evaluations::<F, D<F>>::from_vec_and_domain(z, self.domain.d1).interpolate();
         Ok(res)
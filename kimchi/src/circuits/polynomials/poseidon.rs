@@ -136,7 +136,7 @@ impl<F: FftField> CircuitGate<F> {
         &self,
         row: usize,
         // TODO(mimoo): we should just pass two rows instead of the whole witness
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>],
         cs: &ConstraintSystem<F>,
     ) -> Result<(), String> {
         ensure_eq!(
@@ -84,7 +84,7 @@ use crate::circuits::constraints::ConstraintSystem;
 use crate::circuits::expr::{self, Column};
 use crate::circuits::expr::{witness_curr, witness_next, Cache, ConstantExpr, Expr, E};
 use crate::circuits::gate::{CircuitGate, GateType};
-use crate::circuits::wires::{GateWires, Wire, COLUMNS};
+use crate::circuits::wires::{GateWires, Wire, COLUMNS, PERMUTS};
 use crate::proof::ProofEvaluations;
 use ark_ff::{FftField, Field, One};
 use array_init::array_init;
@@ -184,7 +184,7 @@ impl<F: FftField> CircuitGate<F> {
     pub fn verify_cairo_gate(
         &self,
         row: usize,
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>],
         cs: &ConstraintSystem<F>,
     ) -> Result<(), String> {
         // assignments
@@ -220,10 +220,10 @@ impl<F: FftField> CircuitGate<F> {
 
         // Setup proof evaluations
         let rng = &mut StdRng::from_seed([0u8; 32]);
-        let mut eval = |witness| ProofEvaluations {
-            w: witness,
+        let mut eval = |witness: [F; COLUMNS]| ProofEvaluations {
+            w: witness.into_iter().collect(),
             z: F::rand(rng),
-            s: array_init(|_| F::rand(rng)),
+            s: (0..PERMUTS - 1).map(|_| F::rand(rng)).collect(),
             generic_selector: F::zero(),
             poseidon_selector: F::zero(),
             lookup: None,
@@ -238,6 +238,7 @@ impl<F: FftField> CircuitGate<F> {
             joint_combiner: None,
             endo_coefficient: cs.endo,
             mds: vec![],
+            zk_rows: cs.zk_rows,
         };
 
         let pt = F::rand(rng);
@@ -420,7 +421,7 @@ pub mod testing {
     pub fn ensure_cairo_gate<F: FftField>(
         gate: &CircuitGate<F>,
         row: usize,
-        witness: &[Vec<F>; COLUMNS],
+        witness: &[Vec<F>],
         //_cs: &ConstraintSystem<F>,
     ) -> Result<(), String> {
         // assignments
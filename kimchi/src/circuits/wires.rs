@@ -1,14 +1,15 @@
 //! This module implements Plonk circuit gate wires primitive.
 
+use crate::circuits::polynomials::generic::GENERIC_COEFFS;
 use ark_ff::bytes::{FromBytes, ToBytes};
 use array_init::array_init;
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Result as IoResult, Write};
 
-/// Number of registers
+/// Number of registers by default, and number of wires of a gate
 pub const COLUMNS: usize = 15;
 
-/// Number of registers that can be wired (participating in the permutation)
+/// Number of registers that can be wired (participating in the permutation) by default
 pub const PERMUTS: usize = 7;
 
 /// index of all registers
@@ -28,7 +29,7 @@ pub struct Wire {
 
 impl Wire {
     /// Creates a new set of wires for a given row.
-    pub fn new(row: usize) -> [Self; PERMUTS] {
+    pub fn new(row: usize) -> GateWires {
         array_init(|col| Self { row, col })
     }
 }
@@ -36,7 +37,46 @@ impl Wire {
 /// GateWires document the wiring of a gate. More specifically, each value either
 /// represents the same cell (row and column) or a different cell in another row.
 /// (This is to help the permutation argument.)
-pub type GateWires = [Wire; PERMUTS];
+/// Only the wires of the columns permuted by the circuit are read (see [Width]),
+/// so at most [COLUMNS] columns can be permuted.
+pub type GateWires = [Wire; COLUMNS];
+
+/// The width of a circuit: how many registers its witness uses ([COLUMNS] by default),
+/// and how many of those, starting from the first one, participate in the permutation.
+/// Narrow circuits get smaller proofs, as each column adds a commitment and evaluations to the proof,
+/// while wide circuits can have more than [COLUMNS] columns, and wire more than [PERMUTS] of them,
+/// at the cost of computing the permutation argument over a domain twice as large.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Width {
+    /// number of witness columns
+    pub columns: usize,
+    /// number of permuted columns
+    pub permuts: usize,
+}
+
+impl Default for Width {
+    fn default() -> Self {
+        Width {
+            columns: COLUMNS,
+            permuts: PERMUTS,
+        }
+    }
+}
+
+impl Width {
+    /// The number of chunks, of the size of the domain, of the quotient polynomial:
+    /// [PERMUTS] for the constraints of the gates, evaluated over $d_8$,
+    /// or one per permuted column if the permutation argument needs more.
+    pub fn quotient_chunks(&self) -> usize {
+        std::cmp::max(self.permuts, PERMUTS)
+    }
+
+    /// The number of coefficient columns of the circuit:
+    /// one per witness column, but at least the coefficients of the double generic gate.
+    pub fn coefficients(&self) -> usize {
+        std::cmp::max(self.columns, 2 * GENERIC_COEFFS)
+    }
+}
 
 impl ToBytes for Wire {
     #[inline]
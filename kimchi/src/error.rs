@@ -1,5 +1,14 @@
 //! This module implements the [ProverError] type.
 
+use crate::{
+    circuits::{
+        argument::ArgumentType,
+        polynomials::generic::GENERIC_REGISTERS,
+        wires::{Width, COLUMNS},
+    },
+    prover::hooks::ProverRound,
+};
+use commitment_dlog::pcs::OpeningProofError;
 use thiserror::Error;
 
 /// Errors that can arise when creating a proof
@@ -9,7 +18,9 @@ pub enum ProverError {
     #[error("the circuit is too large")]
     NoRoomForZkInWitness,
 
-    #[error("the witness columns are not all the same size")]
+    #[error(
+        "the witness columns are not all the same size, or fewer than the columns of the circuit"
+    )]
     WitnessCsInconsistent,
 
     #[error("the proof could not be constructed: {0}")]
@@ -20,6 +31,12 @@ pub enum ProverError {
 
     #[error("the lookup failed to find a match in the table")]
     ValueNotInTable,
+
+    #[error("the proof creation was cancelled at the end of the {0:?} round")]
+    Cancelled(ProverRound),
+
+    #[error("the OCaml bindings only support proofs for circuits of the default width")]
+    UnsupportedWidth,
 }
 
 /// Errors that can arise when verifying a proof
@@ -31,6 +48,9 @@ pub enum VerifyError {
     #[error("the opening proof failed to verify")]
     OpenProof,
 
+    #[error("the accumulated opening proofs failed to verify")]
+    Accumulator,
+
     #[error("lookup used in circuit, but proof is missing lookup commitments")]
     LookupCommitmentMissing,
 
@@ -39,6 +59,43 @@ pub enum VerifyError {
 
     #[error("lookup used in circuit, but proof has inconsistent number of lookup evaluations and commitments")]
     ProofInconsistentLookup,
+
+    #[error("the commitment to {0} has {1} chunks, but {2} were expected")]
+    IncorrectCommitmentChunks(&'static str, usize, usize),
+
+    #[error("the commitment to {0} contains a point that is not on the curve")]
+    PointNotOnCurve(&'static str),
+
+    #[error("the commitment to {0} contains a point that is not in the prime-order subgroup")]
+    PointNotInSubgroup(&'static str),
+
+    #[error("the opening proof contains the point at infinity in {0}")]
+    PointAtInfinity(&'static str),
+
+    #[error("the opening proof has {0} rounds, but {1} were expected")]
+    IncorrectOpeningProofRounds(usize, usize),
+
+    #[error("the evaluations of {0} are of an unexpected length")]
+    IncorrectEvaluationsLength(&'static str),
+
+    #[error("the proof has {0} public inputs, but the circuit expects {1}")]
+    IncorrectPublicInputLength(usize, usize),
+
+    #[error("the SRS of a verifier index is not compatible with the SRS of the other verifier indexes of the batch")]
+    IncompatibleSrs,
+}
+
+impl From<OpeningProofError> for VerifyError {
+    fn from(e: OpeningProofError) -> Self {
+        match e {
+            OpeningProofError::IncorrectRounds(rounds, expected) => {
+                VerifyError::IncorrectOpeningProofRounds(rounds, expected)
+            }
+            OpeningProofError::PointNotOnCurve(name) => VerifyError::PointNotOnCurve(name),
+            OpeningProofError::PointNotInSubgroup(name) => VerifyError::PointNotInSubgroup(name),
+            OpeningProofError::PointAtInfinity(name) => VerifyError::PointAtInfinity(name),
+        }
+    }
 }
 
 /// Errors that can arise when preparing the setup
@@ -49,4 +106,31 @@ pub enum SetupError {
 
     #[error("the domain could not be constructed: {0}")]
     DomainCreation(&'static str),
+
+    #[error(
+        "the constraints of {0:?} have degree {1}, but the prover supports a degree of at most {2}"
+    )]
+    ConstraintDegree(ArgumentType, u64, u64),
+
+    #[error("the {0} of the index do not match the ones computed from the circuit")]
+    InconsistentIndex(&'static str),
+
+    #[error("the number of zero-knowledge rows must be between 1 and {1}, but it is {0}")]
+    ZkRows(u64, u64),
+
+    #[error("the shared precomputations were not created for the {0} of the circuit")]
+    Precomputations(&'static str),
+
+    #[error(
+        "a circuit has at least {} columns, and permutes between 1 and all of them (at most {}), but the width is {0:?}",
+        2 * GENERIC_REGISTERS,
+        COLUMNS
+    )]
+    Width(Width),
+
+    #[error("the constraints of {0:?} use {1} witness columns, but the circuit only has {2}")]
+    ConstraintColumns(ArgumentType, usize, usize),
+
+    #[error("the zero-knowledge mode requires a polynomial commitment scheme that hides the commitments")]
+    NonHidingCommitments,
 }
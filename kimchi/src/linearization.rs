@@ -1,5 +1,7 @@
 //! This module implements the linearization.
 
+pub mod json;
+
 use crate::alphas::Alphas;
 use crate::circuits::argument::{Argument, ArgumentType};
 use crate::circuits::lookup;
@@ -8,6 +10,7 @@ use crate::circuits::polynomials::chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFina
 use crate::circuits::polynomials::complete_add::CompleteAdd;
 use crate::circuits::polynomials::endomul_scalar::EndomulScalar;
 use crate::circuits::polynomials::endosclmul::EndosclMul;
+use crate::circuits::polynomials::generic;
 use crate::circuits::polynomials::permutation;
 use crate::circuits::polynomials::poseidon::Poseidon;
 use crate::circuits::polynomials::varbasemul::VarbaseMul;
@@ -16,14 +19,21 @@ use crate::circuits::{
     gate::GateType,
     wires::*,
 };
-use ark_ff::{FftField, SquareRootField};
+use crate::error::SetupError;
+use ark_ff::{FftField, SquareRootField, Zero};
 use ark_poly::Radix2EvaluationDomain as D;
+use std::collections::BTreeSet;
 
-pub fn constraints_expr<F: FftField + SquareRootField>(
+/// Returns the combined constraints of each argument used by the circuit,
+/// along with the powers of alpha they use.
+/// Only the constraints of the gates present in `gate_types` are included
+/// (the generic gate and the permutation are handled separately, outside of the expression framework).
+pub fn arguments_constraints<F: FftField + SquareRootField>(
     domain: D<F>,
-    chacha: bool,
+    zk_rows: u64,
+    gate_types: &BTreeSet<GateType>,
     lookup_constraint_system: Option<&LookupConfiguration<F>>,
-) -> (Expr<ConstantExpr<F>>, Alphas<F>) {
+) -> (Vec<(ArgumentType, Expr<ConstantExpr<F>>)>, Alphas<F>) {
     // register powers of alpha so that we don't reuse them across mutually inclusive constraints
     let mut powers_of_alpha = Alphas::<F>::default();
 
@@ -34,17 +44,59 @@ pub fn constraints_expr<F: FftField + SquareRootField>(
         highest_constraints,
     );
 
-    let mut expr = Poseidon::combined_constraints(&powers_of_alpha);
-    expr += VarbaseMul::combined_constraints(&powers_of_alpha);
-    expr += CompleteAdd::combined_constraints(&powers_of_alpha);
-    expr += EndosclMul::combined_constraints(&powers_of_alpha);
-    expr += EndomulScalar::combined_constraints(&powers_of_alpha);
-
-    if chacha {
-        expr += ChaCha0::combined_constraints(&powers_of_alpha);
-        expr += ChaCha1::combined_constraints(&powers_of_alpha);
-        expr += ChaCha2::combined_constraints(&powers_of_alpha);
-        expr += ChaChaFinal::combined_constraints(&powers_of_alpha);
+    // the generic gate is handled separately, and always uses its powers of alpha
+    powers_of_alpha.declare(ArgumentType::Gate(GateType::Generic), generic::CONSTRAINTS);
+
+    let mut arguments = vec![];
+    for gate_type in gate_types {
+        let (constraints, powers) = match gate_type {
+            GateType::Poseidon => (
+                Poseidon::combined_constraints(&powers_of_alpha),
+                Poseidon::<F>::CONSTRAINTS,
+            ),
+            GateType::VarBaseMul => (
+                VarbaseMul::combined_constraints(&powers_of_alpha),
+                VarbaseMul::<F>::CONSTRAINTS,
+            ),
+            GateType::CompleteAdd => (
+                CompleteAdd::combined_constraints(&powers_of_alpha),
+                CompleteAdd::<F>::CONSTRAINTS,
+            ),
+            GateType::EndoMul => (
+                EndosclMul::combined_constraints(&powers_of_alpha),
+                EndosclMul::<F>::CONSTRAINTS,
+            ),
+            GateType::EndoMulScalar => (
+                EndomulScalar::combined_constraints(&powers_of_alpha),
+                EndomulScalar::<F>::CONSTRAINTS,
+            ),
+            GateType::ChaCha0 => (
+                ChaCha0::combined_constraints(&powers_of_alpha),
+                ChaCha0::<F>::CONSTRAINTS,
+            ),
+            GateType::ChaCha1 => (
+                ChaCha1::combined_constraints(&powers_of_alpha),
+                ChaCha1::<F>::CONSTRAINTS,
+            ),
+            GateType::ChaCha2 => (
+                ChaCha2::combined_constraints(&powers_of_alpha),
+                ChaCha2::<F>::CONSTRAINTS,
+            ),
+            GateType::ChaChaFinal => (
+                ChaChaFinal::combined_constraints(&powers_of_alpha),
+                ChaChaFinal::<F>::CONSTRAINTS,
+            ),
+            // the generic gate is handled separately, and the other gates have no constraints here
+            GateType::Zero
+            | GateType::Generic
+            | GateType::Lookup
+            | GateType::CairoClaim
+            | GateType::CairoInstruction
+            | GateType::CairoFlags
+            | GateType::CairoTransition => continue,
+        };
+        powers_of_alpha.declare(ArgumentType::Gate(*gate_type), powers);
+        arguments.push((ArgumentType::Gate(*gate_type), constraints));
     }
 
     // permutation
@@ -56,21 +108,81 @@ pub fn constraints_expr<F: FftField + SquareRootField>(
         let alphas =
             powers_of_alpha.get_exponents(ArgumentType::Lookup, lookup::constraints::CONSTRAINTS);
 
-        let constraints = lookup::constraints::constraints(lcs, domain);
+        let constraints = lookup::constraints::constraints(lcs, domain, zk_rows);
         let combined = Expr::combine_constraints(alphas, constraints);
-        expr += combined;
+        arguments.push((ArgumentType::Lookup, combined));
+    }
+
+    (arguments, powers_of_alpha)
+}
+
+/// Creates the expression combining all the constraints of the circuit
+/// (see [arguments_constraints]).
+pub fn constraints_expr<F: FftField + SquareRootField>(
+    domain: D<F>,
+    zk_rows: u64,
+    gate_types: &BTreeSet<GateType>,
+    lookup_constraint_system: Option<&LookupConfiguration<F>>,
+) -> (Expr<ConstantExpr<F>>, Alphas<F>) {
+    let (arguments, powers_of_alpha) =
+        arguments_constraints(domain, zk_rows, gate_types, lookup_constraint_system);
+
+    let mut expr = Expr::zero();
+    for (_, constraints) in arguments {
+        expr += constraints;
     }
 
-    // return the expression
     (expr, powers_of_alpha)
 }
 
+/// Checks that the constraints of each argument have a degree that the prover can handle.
+/// That is, for a domain of size `d1_size`, the degree of the constraints must not exceed
+///
+/// - the size of the `d8` domain, over which the constraints are evaluated,
+/// - `max_quot_size + d1_size`, as the constraints are divided by the vanishing polynomial of the domain,
+/// - the size of the domain over which the columns they use are evaluated (see [Expr::supported_degree]).
+pub fn check_constraints_degree<F: FftField>(
+    d1_size: u64,
+    max_quot_size: usize,
+    arguments: &[(ArgumentType, Expr<ConstantExpr<F>>)],
+) -> Result<(), SetupError> {
+    for (argument, constraints) in arguments {
+        let degree = constraints.degree(d1_size);
+        let supported = std::cmp::min(8 * d1_size, max_quot_size as u64 + d1_size);
+        let supported = std::cmp::min(supported, constraints.supported_degree(d1_size));
+        if degree > supported {
+            return Err(SetupError::ConstraintDegree(*argument, degree, supported));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the constraints of each argument only read the witness columns of a circuit of the given width.
+pub fn check_constraints_width<F: FftField>(
+    width: Width,
+    arguments: &[(ArgumentType, Expr<ConstantExpr<F>>)],
+) -> Result<(), SetupError> {
+    for (argument, constraints) in arguments {
+        let columns = constraints.witness_columns();
+        if columns > width.columns {
+            return Err(SetupError::ConstraintColumns(
+                *argument,
+                columns,
+                width.columns,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the columns evaluated by the prover in a proof for a circuit of the given width.
 pub fn linearization_columns<F: FftField + SquareRootField>(
+    width: Width,
     lookup_constraint_system: Option<&LookupConfiguration<F>>,
 ) -> std::collections::HashSet<Column> {
     let mut h = std::collections::HashSet::new();
     use Column::*;
-    for i in 0..COLUMNS {
+    for i in 0..width.columns {
         h.insert(Witness(i));
     }
     match lookup_constraint_system.as_ref() {
@@ -89,19 +201,96 @@ pub fn linearization_columns<F: FftField + SquareRootField>(
     h
 }
 
+/// Computes the linearization of the constraints of a circuit of the given width using the gates in `gate_types`
+/// (see [constraints_expr]).
 pub fn expr_linearization<F: FftField + SquareRootField>(
     domain: D<F>,
-    chacha: bool,
+    zk_rows: u64,
+    width: Width,
+    gate_types: &BTreeSet<GateType>,
     lookup_constraint_system: Option<&LookupConfiguration<F>>,
 ) -> (Linearization<Vec<PolishToken<F>>>, Alphas<F>) {
-    let evaluated_cols = linearization_columns::<F>(lookup_constraint_system);
+    let evaluated_cols = linearization_columns::<F>(width, lookup_constraint_system);
 
-    let (expr, powers_of_alpha) = constraints_expr(domain, chacha, lookup_constraint_system);
+    let (expr, powers_of_alpha) =
+        constraints_expr(domain, zk_rows, gate_types, lookup_constraint_system);
 
-    let linearization = expr
-        .linearize(evaluated_cols)
-        .unwrap()
-        .map(|e| e.to_polish());
+    let (linearization, _stats) = expr.linearize(evaluated_cols).unwrap().optimize();
+    let linearization = linearization.map(|e| e.to_polish());
 
     (linearization, powers_of_alpha)
 }
+
+/// Checks that a linearization and its powers of alpha,
+/// typically deserialized along with an index,
+/// are the ones computed from the gates of the circuit (see [expr_linearization]).
+pub fn check_linearization<F: FftField + SquareRootField>(
+    domain: D<F>,
+    zk_rows: u64,
+    width: Width,
+    gate_types: &BTreeSet<GateType>,
+    lookup_constraint_system: Option<&LookupConfiguration<F>>,
+    linearization: &Linearization<Vec<PolishToken<F>>>,
+    powers_of_alpha: &Alphas<F>,
+) -> Result<(), SetupError> {
+    let (expected_linearization, expected_powers_of_alpha) =
+        expr_linearization(domain, zk_rows, width, gate_types, lookup_constraint_system);
+
+    if powers_of_alpha != &expected_powers_of_alpha {
+        return Err(SetupError::InconsistentIndex("powers of alpha"));
+    }
+    if linearization != &expected_linearization {
+        return Err(SetupError::InconsistentIndex("linearization"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::expr::prologue::*;
+    use crate::circuits::polynomials::permutation::ZK_ROWS;
+    use ark_poly::EvaluationDomain;
+    use mina_curves::pasta::fp::Fp;
+
+    #[test]
+    fn test_constraints_degree() {
+        let domain = D::<Fp>::new(1 << 4).unwrap();
+        let n = domain.size;
+        let max_quot_size = PERMUTS * n as usize;
+
+        // the constraints of all the gates are supported
+        let gate_types = [
+            GateType::Poseidon,
+            GateType::CompleteAdd,
+            GateType::VarBaseMul,
+            GateType::EndoMul,
+            GateType::EndoMulScalar,
+            GateType::ChaCha0,
+            GateType::ChaCha1,
+            GateType::ChaCha2,
+            GateType::ChaChaFinal,
+        ]
+        .into_iter()
+        .collect();
+        let (arguments, _) = arguments_constraints(domain, ZK_ROWS, &gate_types, None);
+        check_constraints_degree(n, max_quot_size, &arguments).unwrap();
+
+        // the selector of the complete addition gate is only evaluated over d4
+        let add: E<Fp> = index(GateType::CompleteAdd) * witness_curr(0).pow(4);
+        let arguments = [(ArgumentType::Gate(GateType::CompleteAdd), add)];
+        assert!(matches!(
+            check_constraints_degree(n, max_quot_size, &arguments),
+            Err(SetupError::ConstraintDegree(_, degree, supported)) if degree == 5 * n && supported == 4 * n
+        ));
+
+        // constraints cannot exceed the d8 domain
+        let poseidon: E<Fp> = index(GateType::Poseidon) * witness_curr(0).pow(8);
+        let arguments = [(ArgumentType::Gate(GateType::Poseidon), poseidon)];
+        assert!(matches!(
+            check_constraints_degree(n, max_quot_size, &arguments),
+            Err(SetupError::ConstraintDegree(_, degree, supported)) if degree == 9 * n && supported == 8 * n
+        ));
+    }
+}
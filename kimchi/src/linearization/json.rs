@@ -0,0 +1,269 @@
+//! This module exports the linearization of a circuit, along with its powers of alpha,
+//! to a JSON format that verifiers written in other languages can consume
+//! instead of reimplementing [super::expr_linearization].
+//!
+//! The exported document has the following shape:
+//!
+//! ```text
+//! {
+//!   "version": 2,
+//!   "zk_rows": <u64>,
+//!   "powers_of_alpha": [ { "argument": <argument>, "start": <u32>, "end": <u32> }, ... ],
+//!   "constant_term": [ <token>, ... ],
+//!   "index_terms": [ { "column": <column>, "coefficient": [ <token>, ... ] }, ... ]
+//! }
+//! ```
+//!
+//! - `zk_rows` is the number of rows at the end of the domain reserved for zero-knowledge.
+//! - `powers_of_alpha` lists the ranges `[start, end)` of powers of alpha used by each argument,
+//!   ordered by `start`. An `<argument>` is one of `"gates"` (shared by all the gates),
+//!   `"permutation"`, or `"lookup"`.
+//! - `constant_term` and the `coefficient`s of the `index_terms` are programs in
+//!   reverse Polish notation, which evaluate to a single field element.
+//!   The linearization is `constant_term + sum_i coefficient_i * column_i`,
+//!   where the `column_i` are the polynomials committed to in the verifier index.
+//!
+//! A `<column>` is an object with a `kind` field, which is one of
+//! `"witness"`, `"lookup_sorted"`, `"lookup_kind_index"`, `"coefficient"` (all with an `index` field),
+//! `"index"` (with a `gate` field containing the name of a gate type),
+//! `"z"`, `"lookup_aggreg"`, or `"lookup_table"`.
+//!
+//! A `<token>` is an object with an `op` field. The tokens operate on a stack of field elements,
+//! and on a list of stored field elements which starts empty:
+//!
+//! | `op`                            | other fields             | effect                                                          |
+//! |---------------------------------|--------------------------|-----------------------------------------------------------------|
+//! | `alpha`, `beta`, `gamma`        |                          | pushes the corresponding challenge                              |
+//! | `joint_combiner`                |                          | pushes the joint combiner of the lookup argument                |
+//! | `endo_coefficient`              |                          | pushes the endomorphism coefficient                             |
+//! | `mds`                           | `row`, `col`             | pushes an entry of the MDS matrix of the Poseidon permutation   |
+//! | `literal`                       | `value`                  | pushes a field element, encoded as a little-endian hex string   |
+//! | `cell`                          | `column`, `row`          | pushes the evaluation of a column at `"curr"` or `"next"`       |
+//! | `vanishes_on_last_4_rows`       |                          | pushes the evaluation of the polynomial vanishing on the last `zk_rows + 1` rows of the domain |
+//! | `unnormalized_lagrange_basis`   | `index`                  | pushes `(x^n - 1) / (x - w^index)` evaluated at the point       |
+//! | `dup`                           |                          | pushes the top of the stack                                     |
+//! | `pow`                           | `exponent`               | raises the top of the stack to the power `exponent`             |
+//! | `add`, `sub`, `mul`             |                          | pops `y` then `x`, and pushes `x + y`, `x - y`, or `x * y`      |
+//! | `store`                         |                          | appends the top of the stack (without popping it) to the stored elements |
+//! | `load`                          | `index`                  | pushes the stored element at position `index`                   |
+//!
+//! Once a program has been run, the stack contains a single element: the result.
+
+use crate::alphas::Alphas;
+use crate::circuits::{
+    argument::ArgumentType,
+    expr::{Column, Linearization, PolishToken, Variable},
+    gate::{CurrOrNext, GateType},
+};
+use ark_ff::PrimeField;
+use o1_utils::FieldHelpers;
+use serde::Serialize;
+
+/// The version of the JSON format, to be increased whenever it changes.
+pub const VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct AlphasRangeJson {
+    argument: &'static str,
+    start: u32,
+    end: u32,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ColumnJson {
+    Witness { index: usize },
+    Z,
+    LookupSorted { index: usize },
+    LookupAggreg,
+    LookupTable,
+    LookupKindIndex { index: usize },
+    Index { gate: GateType },
+    Coefficient { index: usize },
+}
+
+impl From<Column> for ColumnJson {
+    fn from(col: Column) -> Self {
+        match col {
+            Column::Witness(index) => ColumnJson::Witness { index },
+            Column::Z => ColumnJson::Z,
+            Column::LookupSorted(index) => ColumnJson::LookupSorted { index },
+            Column::LookupAggreg => ColumnJson::LookupAggreg,
+            Column::LookupTable => ColumnJson::LookupTable,
+            Column::LookupKindIndex(index) => ColumnJson::LookupKindIndex { index },
+            Column::Index(gate) => ColumnJson::Index { gate },
+            Column::Coefficient(index) => ColumnJson::Coefficient { index },
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum TokenJson {
+    Alpha,
+    Beta,
+    Gamma,
+    JointCombiner,
+    EndoCoefficient,
+    Mds {
+        row: usize,
+        col: usize,
+    },
+    Literal {
+        value: String,
+    },
+    Cell {
+        column: ColumnJson,
+        row: &'static str,
+    },
+    Dup,
+    Pow {
+        exponent: u64,
+    },
+    Add,
+    Mul,
+    Sub,
+    VanishesOnLast4Rows,
+    UnnormalizedLagrangeBasis {
+        index: usize,
+    },
+    Store,
+    Load {
+        index: usize,
+    },
+}
+
+impl<F: PrimeField> From<&PolishToken<F>> for TokenJson {
+    fn from(token: &PolishToken<F>) -> Self {
+        match token {
+            PolishToken::Alpha => TokenJson::Alpha,
+            PolishToken::Beta => TokenJson::Beta,
+            PolishToken::Gamma => TokenJson::Gamma,
+            PolishToken::JointCombiner => TokenJson::JointCombiner,
+            PolishToken::EndoCoefficient => TokenJson::EndoCoefficient,
+            PolishToken::Mds { row, col } => TokenJson::Mds {
+                row: *row,
+                col: *col,
+            },
+            PolishToken::Literal(x) => TokenJson::Literal { value: x.to_hex() },
+            PolishToken::Cell(Variable { col, row }) => TokenJson::Cell {
+                column: (*col).into(),
+                row: match row {
+                    CurrOrNext::Curr => "curr",
+                    CurrOrNext::Next => "next",
+                },
+            },
+            PolishToken::Dup => TokenJson::Dup,
+            PolishToken::Pow(exponent) => TokenJson::Pow {
+                exponent: *exponent,
+            },
+            PolishToken::Add => TokenJson::Add,
+            PolishToken::Mul => TokenJson::Mul,
+            PolishToken::Sub => TokenJson::Sub,
+            PolishToken::VanishesOnLast4Rows => TokenJson::VanishesOnLast4Rows,
+            PolishToken::UnnormalizedLagrangeBasis(index) => {
+                TokenJson::UnnormalizedLagrangeBasis { index: *index }
+            }
+            PolishToken::Store => TokenJson::Store,
+            PolishToken::Load(index) => TokenJson::Load { index: *index },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct IndexTermJson {
+    column: ColumnJson,
+    coefficient: Vec<TokenJson>,
+}
+
+#[derive(Serialize)]
+struct LinearizationJson {
+    version: u32,
+    zk_rows: u64,
+    powers_of_alpha: Vec<AlphasRangeJson>,
+    constant_term: Vec<TokenJson>,
+    index_terms: Vec<IndexTermJson>,
+}
+
+fn tokens<F: PrimeField>(tokens: &[PolishToken<F>]) -> Vec<TokenJson> {
+    tokens.iter().map(Into::into).collect()
+}
+
+/// Exports a linearization and its powers of alpha to the JSON format documented in this module,
+/// along with the number of zero-knowledge rows of the circuit, which its evaluation depends on.
+pub fn to_json<F: PrimeField>(
+    zk_rows: u64,
+    linearization: &Linearization<Vec<PolishToken<F>>>,
+    powers_of_alpha: &Alphas<F>,
+) -> String {
+    let powers_of_alpha = powers_of_alpha
+        .ranges()
+        .into_iter()
+        .map(|(ty, range)| AlphasRangeJson {
+            argument: match ty {
+                ArgumentType::Gate(_) => "gates",
+                ArgumentType::Permutation => "permutation",
+                ArgumentType::Lookup => "lookup",
+            },
+            start: range.start,
+            end: range.end,
+        })
+        .collect();
+
+    let json = LinearizationJson {
+        version: VERSION,
+        zk_rows,
+        powers_of_alpha,
+        constant_term: tokens(&linearization.constant_term),
+        index_terms: linearization
+            .index_terms
+            .iter()
+            .map(|(col, coefficient)| IndexTermJson {
+                column: (*col).into(),
+                coefficient: tokens(coefficient),
+            })
+            .collect(),
+    };
+
+    serde_json::to_string_pretty(&json).expect("the linearization can always be serialized")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::{polynomials::permutation::ZK_ROWS, wires::Width};
+    use crate::linearization::expr_linearization;
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+    use mina_curves::pasta::fp::Fp;
+
+    #[test]
+    fn test_linearization_json() {
+        let domain = D::<Fp>::new(1 << 4).unwrap();
+        let gate_types = [GateType::Generic, GateType::Poseidon]
+            .into_iter()
+            .collect();
+        let (linearization, powers_of_alpha) =
+            expr_linearization(domain, ZK_ROWS, Width::default(), &gate_types, None);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&to_json(ZK_ROWS, &linearization, &powers_of_alpha)).unwrap();
+        assert_eq!(json["version"], 2);
+        assert_eq!(json["zk_rows"], ZK_ROWS);
+        assert_eq!(json["powers_of_alpha"][0]["argument"], "gates");
+        assert_eq!(json["powers_of_alpha"][1]["argument"], "permutation");
+
+        // the round constants of poseidon are not evaluated
+        let index_terms = json["index_terms"].as_array().unwrap();
+        assert_eq!(index_terms.len(), linearization.index_terms.len());
+        assert!(index_terms
+            .iter()
+            .all(|term| term["column"]["kind"] == "coefficient"));
+
+        // but its selector is
+        let constant_term = json["constant_term"].as_array().unwrap();
+        assert_eq!(constant_term.len(), linearization.constant_term.len());
+        assert!(constant_term.iter().any(|token| token["op"] == "cell"
+            && token["column"]["kind"] == "index"
+            && token["column"]["gate"] == "Poseidon"));
+    }
+}
@@ -1,4 +1,5 @@
 use ark_ff::{Field, PrimeField};
+use o1_utils::chunked_evaluations::ChunkedEvaluations;
 use oracle::sponge::{DefaultFrSponge, ScalarChallenge};
 use oracle::{
     constants::PlonkSpongeConstantsKimchi as SC,
@@ -19,7 +20,7 @@ pub trait FrSponge<Fr: Field> {
 
     /// Absorbs the given evaluations into the sponge.
     // TODO: IMO this function should be inlined in prover/verifier
-    fn absorb_evaluations(&mut self, p: &[Fr], e: &ProofEvaluations<Vec<Fr>>);
+    fn absorb_evaluations(&mut self, p: &[Fr], e: &ProofEvaluations<ChunkedEvaluations<Fr>>);
 }
 
 impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
@@ -40,39 +41,17 @@ impl<Fr: PrimeField> FrSponge<Fr> for DefaultFrSponge<Fr, SC> {
         ScalarChallenge(self.squeeze(oracle::sponge::CHALLENGE_LENGTH_IN_LIMBS))
     }
 
-    fn absorb_evaluations(&mut self, p: &[Fr], e: &ProofEvaluations<Vec<Fr>>) {
+    fn absorb_evaluations(&mut self, p: &[Fr], e: &ProofEvaluations<ChunkedEvaluations<Fr>>) {
         self.last_squeezed = vec![];
         self.sponge.absorb(p);
 
-        let points = [
-            &e.z,
-            &e.generic_selector,
-            &e.poseidon_selector,
-            &e.w[0],
-            &e.w[1],
-            &e.w[2],
-            &e.w[3],
-            &e.w[4],
-            &e.w[5],
-            &e.w[6],
-            &e.w[7],
-            &e.w[8],
-            &e.w[9],
-            &e.w[10],
-            &e.w[11],
-            &e.w[12],
-            &e.w[13],
-            &e.w[14],
-            &e.s[0],
-            &e.s[1],
-            &e.s[2],
-            &e.s[3],
-            &e.s[4],
-            &e.s[5],
-        ];
+        let points = [&e.z, &e.generic_selector, &e.poseidon_selector]
+            .into_iter()
+            .chain(&e.w)
+            .chain(&e.s);
 
-        for p in &points {
-            self.sponge.absorb(p);
+        for p in points {
+            self.sponge.absorb(&p.chunks);
         }
     }
 }
@@ -2,92 +2,130 @@
 
 use crate::circuits::wires::{COLUMNS, PERMUTS};
 use ark_ec::AffineCurve;
-use ark_ff::{FftField, Zero};
-use ark_poly::univariate::DensePolynomial;
-use array_init::array_init;
-use commitment_dlog::{commitment::PolyComm, evaluation_proof::OpeningProof};
-use o1_utils::{types::fields::*, ExtendedDensePolynomial};
+use ark_ff::{Field, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use commitment_dlog::{
+    commitment::{CommitmentCurve, PolyComm},
+    srs::SRS,
+    PolynomialCommitment,
+};
+use o1_utils::{chunked_evaluations::ChunkedEvaluations, types::fields::*};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_with::serde_as;
 
 //~ spec:startcode
-#[derive(Clone)]
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "Field: CanonicalSerialize + CanonicalDeserialize")]
 pub struct LookupEvaluations<Field> {
     /// sorted lookup table polynomial
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
     pub sorted: Vec<Field>,
     /// lookup aggregation polynomial
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub aggreg: Field,
     // TODO: May be possible to optimize this away?
     /// lookup table polynomial
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub table: Field,
 }
 
-// TODO: this should really be vectors here, perhaps create another type for chunked evaluations?
-#[derive(Clone)]
+/// The evaluations of the polynomials at a point.
+/// In a proof, these are [ChunkedEvaluations] as polynomials might be split in chunks.
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "Field: CanonicalSerialize + CanonicalDeserialize")]
 pub struct ProofEvaluations<Field> {
-    /// witness polynomials
-    pub w: [Field; COLUMNS],
+    /// witness polynomials, one per witness column of the circuit
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub w: Vec<Field>,
     /// permutation polynomial
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub z: Field,
-    /// permutation polynomials
-    /// (PERMUTS-1 evaluations because the last permutation is only used in commitment form)
-    pub s: [Field; PERMUTS - 1],
+    /// permutation polynomials, one per permuted column of the circuit but the last one
+    /// (the last permutation is only used in commitment form)
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub s: Vec<Field>,
     /// lookup-related evaluations
     pub lookup: Option<LookupEvaluations<Field>>,
     /// evaluation of the generic selector polynomial
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub generic_selector: Field,
     /// evaluation of the poseidon selector polynomial
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub poseidon_selector: Field,
 }
 
 /// Commitments linked to the lookup feature
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LookupCommitments<G: AffineCurve> {
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub sorted: Vec<PolyComm<G>>,
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub aggreg: PolyComm<G>,
 }
 
 /// All the commitments that the prover creates as part of the proof.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ProverCommitments<G: AffineCurve> {
-    /// The commitments to the witness (execution trace)
-    pub w_comm: [PolyComm<G>; COLUMNS],
+    /// The commitments to the witness (execution trace), one per witness column of the circuit
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    pub w_comm: Vec<PolyComm<G>>,
     /// The commitment to the permutation polynomial
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub z_comm: PolyComm<G>,
     /// The commitment to the quotient polynomial
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub t_comm: PolyComm<G>,
     /// Commitments related to the lookup argument
+    #[serde(bound = "LookupCommitments<G>: Serialize + DeserializeOwned")]
     pub lookup: Option<LookupCommitments<G>>,
 }
 
-/// The proof that the prover creates from a [ProverIndex] and a `witness`.
-#[derive(Clone)]
-pub struct ProverProof<G: AffineCurve> {
+/// The proof that the prover creates from a [ProverIndex](crate::prover_index::ProverIndex) and a `witness`,
+/// whose polynomials are opened with the polynomial commitment scheme `PCS`.
+/// Its curve points are always serialized compressed (see [o1_utils::serialization::SerdeAs]):
+/// unlike SRS files (see [commitment_dlog::srs::PointEncoding]), the encoding cannot be selected.
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProverProof<G: CommitmentCurve, PCS: PolynomialCommitment<G> = SRS<G>> {
     /// All the polynomial commitments required in the proof
+    #[serde(bound = "ProverCommitments<G>: Serialize + DeserializeOwned")]
     pub commitments: ProverCommitments<G>,
 
     /// batched commitment opening proof
-    pub proof: OpeningProof<G>,
+    #[serde(bound = "PCS::OpeningProof: Serialize + DeserializeOwned")]
+    pub proof: PCS::OpeningProof,
 
     /// Two evaluations over a number of committed polynomials
     // TODO(mimoo): that really should be a type Evals { z: PE, zw: PE }
-    pub evals: [ProofEvaluations<Vec<ScalarField<G>>>; 2],
+    #[serde(
+        bound = "ProofEvaluations<ChunkedEvaluations<ScalarField<G>>>: Serialize + DeserializeOwned"
+    )]
+    pub evals: [ProofEvaluations<ChunkedEvaluations<ScalarField<G>>>; 2],
 
     /// Required evaluation for [Maller's optimization](https://o1-labs.github.io/mina-book/crypto/plonk/maller_15.html#the-evaluation-of-l)
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub ft_eval1: ScalarField<G>,
 
     /// The public input
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
     pub public: Vec<ScalarField<G>>,
 
     /// The challenges underlying the optional polynomials folded into the proof
+    #[serde_as(as = "Vec<(Vec<o1_utils::serialization::SerdeAs>, _)>")]
+    #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub prev_challenges: Vec<(Vec<ScalarField<G>>, PolyComm<G>)>,
 }
 //~ spec:endcode
 
 impl<F: Zero> ProofEvaluations<F> {
+    /// Returns evaluations for a circuit of the default width, with the given witness evaluations and zeros elsewhere.
     pub fn dummy_with_witness_evaluations(w: [F; COLUMNS]) -> ProofEvaluations<F> {
         ProofEvaluations {
-            w,
+            w: w.into_iter().collect(),
             z: F::zero(),
-            s: array_init(|_| F::zero()),
+            s: (0..PERMUTS - 1).map(|_| F::zero()).collect(),
             lookup: None,
             generic_selector: F::zero(),
             poseidon_selector: F::zero(),
@@ -95,23 +133,21 @@ impl<F: Zero> ProofEvaluations<F> {
     }
 }
 
-impl<F: FftField> ProofEvaluations<Vec<F>> {
+impl<F: Field> ProofEvaluations<ChunkedEvaluations<F>> {
+    /// Combines the chunked evaluations at a point $x$ into the evaluations of the polynomials,
+    /// given $x^n$ where $n$ is the size of the chunks (see [ChunkedEvaluations::combine]).
     pub fn combine(&self, pt: F) -> ProofEvaluations<F> {
         ProofEvaluations::<F> {
-            s: array_init(|i| DensePolynomial::eval_polynomial(&self.s[i], pt)),
-            w: array_init(|i| DensePolynomial::eval_polynomial(&self.w[i], pt)),
-            z: DensePolynomial::eval_polynomial(&self.z, pt),
+            s: self.s.iter().map(|s| s.combine(pt)).collect(),
+            w: self.w.iter().map(|w| w.combine(pt)).collect(),
+            z: self.z.combine(pt),
             lookup: self.lookup.as_ref().map(|l| LookupEvaluations {
-                table: DensePolynomial::eval_polynomial(&l.table, pt),
-                aggreg: DensePolynomial::eval_polynomial(&l.aggreg, pt),
-                sorted: l
-                    .sorted
-                    .iter()
-                    .map(|x| DensePolynomial::eval_polynomial(x, pt))
-                    .collect(),
+                table: l.table.combine(pt),
+                aggreg: l.aggreg.combine(pt),
+                sorted: l.sorted.iter().map(|x| x.combine(pt)).collect(),
             }),
-            generic_selector: DensePolynomial::eval_polynomial(&self.generic_selector, pt),
-            poseidon_selector: DensePolynomial::eval_polynomial(&self.poseidon_selector, pt),
+            generic_selector: self.generic_selector.combine(pt),
+            poseidon_selector: self.poseidon_selector.combine(pt),
         }
     }
 }
@@ -123,6 +159,7 @@ impl<F: FftField> ProofEvaluations<Vec<F>> {
 #[cfg(feature = "ocaml_types")]
 pub mod caml {
     use super::*;
+    use crate::error::ProverError;
 
     //
     // CamlLookupEvaluations<CamlF>
@@ -135,25 +172,25 @@ pub mod caml {
         pub table: Vec<CamlF>,
     }
 
-    impl<F, CamlF> From<LookupEvaluations<Vec<F>>> for CamlLookupEvaluations<CamlF>
+    impl<F, CamlF> From<LookupEvaluations<ChunkedEvaluations<F>>> for CamlLookupEvaluations<CamlF>
     where
         F: Clone,
         CamlF: From<F>,
     {
-        fn from(le: LookupEvaluations<Vec<F>>) -> Self {
+        fn from(le: LookupEvaluations<ChunkedEvaluations<F>>) -> Self {
             Self {
                 sorted: le
                     .sorted
                     .into_iter()
-                    .map(|x| x.into_iter().map(Into::into).collect())
+                    .map(|x| x.chunks.into_iter().map(Into::into).collect())
                     .collect(),
-                aggreg: le.aggreg.into_iter().map(Into::into).collect(),
-                table: le.table.into_iter().map(Into::into).collect(),
+                aggreg: le.aggreg.chunks.into_iter().map(Into::into).collect(),
+                table: le.table.chunks.into_iter().map(Into::into).collect(),
             }
         }
     }
 
-    impl<F, CamlF> From<CamlLookupEvaluations<CamlF>> for LookupEvaluations<Vec<F>>
+    impl<F, CamlF> From<CamlLookupEvaluations<CamlF>> for LookupEvaluations<ChunkedEvaluations<F>>
     where
         F: From<CamlF> + Clone,
     {
@@ -207,58 +244,75 @@ pub mod caml {
     }
 
     //
-    // ProofEvaluations<Vec<F>> <-> CamlProofEvaluations<CamlF>
+    // ProofEvaluations<ChunkedEvaluations<F>> <-> CamlProofEvaluations<CamlF>
     //
 
-    impl<F, CamlF> From<ProofEvaluations<Vec<F>>> for CamlProofEvaluations<CamlF>
+    impl<F, CamlF> TryFrom<ProofEvaluations<ChunkedEvaluations<F>>> for CamlProofEvaluations<CamlF>
     where
         F: Clone,
         CamlF: From<F>,
     {
-        fn from(pe: ProofEvaluations<Vec<F>>) -> Self {
+        type Error = ProverError;
+
+        /// Fails if the evaluations are not the ones of a circuit of the default width,
+        /// the only one supported by the OCaml bindings.
+        fn try_from(pe: ProofEvaluations<ChunkedEvaluations<F>>) -> Result<Self, ProverError> {
+            if pe.w.len() != COLUMNS || pe.s.len() != PERMUTS - 1 {
+                return Err(ProverError::UnsupportedWidth);
+            }
             let w = (
-                pe.w[0].iter().cloned().map(Into::into).collect(),
-                pe.w[1].iter().cloned().map(Into::into).collect(),
-                pe.w[2].iter().cloned().map(Into::into).collect(),
-                pe.w[3].iter().cloned().map(Into::into).collect(),
-                pe.w[4].iter().cloned().map(Into::into).collect(),
-                pe.w[5].iter().cloned().map(Into::into).collect(),
-                pe.w[6].iter().cloned().map(Into::into).collect(),
-                pe.w[7].iter().cloned().map(Into::into).collect(),
-                pe.w[8].iter().cloned().map(Into::into).collect(),
-                pe.w[9].iter().cloned().map(Into::into).collect(),
-                pe.w[10].iter().cloned().map(Into::into).collect(),
-                pe.w[11].iter().cloned().map(Into::into).collect(),
-                pe.w[12].iter().cloned().map(Into::into).collect(),
-                pe.w[13].iter().cloned().map(Into::into).collect(),
-                pe.w[14].iter().cloned().map(Into::into).collect(),
+                pe.w[0].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[1].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[2].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[3].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[4].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[5].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[6].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[7].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[8].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[9].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[10].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[11].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[12].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[13].chunks.iter().cloned().map(Into::into).collect(),
+                pe.w[14].chunks.iter().cloned().map(Into::into).collect(),
             );
             let s = (
-                pe.s[0].iter().cloned().map(Into::into).collect(),
-                pe.s[1].iter().cloned().map(Into::into).collect(),
-                pe.s[2].iter().cloned().map(Into::into).collect(),
-                pe.s[3].iter().cloned().map(Into::into).collect(),
-                pe.s[4].iter().cloned().map(Into::into).collect(),
-                pe.s[5].iter().cloned().map(Into::into).collect(),
+                pe.s[0].chunks.iter().cloned().map(Into::into).collect(),
+                pe.s[1].chunks.iter().cloned().map(Into::into).collect(),
+                pe.s[2].chunks.iter().cloned().map(Into::into).collect(),
+                pe.s[3].chunks.iter().cloned().map(Into::into).collect(),
+                pe.s[4].chunks.iter().cloned().map(Into::into).collect(),
+                pe.s[5].chunks.iter().cloned().map(Into::into).collect(),
             );
 
-            Self {
+            Ok(Self {
                 w,
-                z: pe.z.into_iter().map(Into::into).collect(),
+                z: pe.z.chunks.into_iter().map(Into::into).collect(),
                 s,
-                generic_selector: pe.generic_selector.into_iter().map(Into::into).collect(),
-                poseidon_selector: pe.poseidon_selector.into_iter().map(Into::into).collect(),
-            }
+                generic_selector: pe
+                    .generic_selector
+                    .chunks
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+                poseidon_selector: pe
+                    .poseidon_selector
+                    .chunks
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            })
         }
     }
 
-    impl<F, CamlF> From<CamlProofEvaluations<CamlF>> for ProofEvaluations<Vec<F>>
+    impl<F, CamlF> From<CamlProofEvaluations<CamlF>> for ProofEvaluations<ChunkedEvaluations<F>>
     where
         F: Clone,
         F: From<CamlF>,
     {
         fn from(cpe: CamlProofEvaluations<CamlF>) -> Self {
-            let w = [
+            let w = vec![
                 cpe.w.0.into_iter().map(Into::into).collect(),
                 cpe.w.1.into_iter().map(Into::into).collect(),
                 cpe.w.2.into_iter().map(Into::into).collect(),
@@ -275,7 +329,7 @@ pub mod caml {
                 cpe.w.13.into_iter().map(Into::into).collect(),
                 cpe.w.14.into_iter().map(Into::into).collect(),
             ];
-            let s = [
+            let s = vec![
                 cpe.s.0.into_iter().map(Into::into).collect(),
                 cpe.s.1.into_iter().map(Into::into).collect(),
                 cpe.s.2.into_iter().map(Into::into).collect(),
@@ -2,21 +2,12 @@
 
 use crate::{
     circuits::{
-        argument::{Argument, ArgumentType},
+        argument::ArgumentType,
+        constraints::ZkMode,
         expr::{l0_1, Constants, Environment, LookupEnvironment},
         gate::GateType,
         lookup::{self, lookups::LookupsUsed, tables::combine_table_entry},
-        polynomials::{
-            chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
-            complete_add::CompleteAdd,
-            endomul_scalar::EndomulScalar,
-            endosclmul::EndosclMul,
-            generic, permutation,
-            permutation::ZK_ROWS,
-            poseidon::Poseidon,
-            varbasemul::VarbaseMul,
-        },
-        wires::{COLUMNS, PERMUTS},
+        polynomials::{generic, permutation},
     },
     error::ProverError,
     plonk_sponge::FrSponge,
@@ -29,33 +20,28 @@ use ark_ff::{FftField, Field, One, PrimeField, UniformRand, Zero};
 use ark_poly::{
     univariate::DensePolynomial, Evaluations, Polynomial, Radix2EvaluationDomain as D, UVPolynomial,
 };
-use array_init::array_init;
-use commitment_dlog::commitment::{b_poly_coefficients, CommitmentCurve, PolyComm};
-use itertools::Itertools;
-use o1_utils::{types::fields::*, ExtendedDensePolynomial as _};
+use commitment_dlog::{
+    commitment::{b_poly_coefficients, CommitmentCurve, PolyComm},
+    PolynomialCommitment,
+};
+use o1_utils::{
+    chunked_evaluations::ChunkedEvaluations, types::fields::*, ExtendedDensePolynomial as _,
+};
 use oracle::{sponge::ScalarChallenge, FqSponge};
+use rayon::prelude::*;
 use std::collections::HashMap;
 
 /// The result of a proof creation or verification.
 type Result<T> = std::result::Result<T, ProverError>;
 
-/// Helper to quickly test if a witness satisfies a constraint
-macro_rules! check_constraint {
-    ($index:expr, $evaluation:expr) => {{
-        if cfg!(debug_assertions) {
-            let (_, res) = $evaluation
-                .interpolate_by_ref()
-                .divide_by_vanishing_poly($index.cs.domain.d1)
-                .unwrap();
-            if !res.is_zero() {
-                panic!(
-                    "couldn't divide by vanishing polynomial: {}",
-                    stringify!($evaluation)
-                );
-            }
-        }
-    }};
-}
+mod context;
+pub mod hooks;
+pub mod quotient;
+
+use context::ProverContext;
+use hooks::Progress;
+pub use hooks::{ProverHooks, ProverRound};
+pub use quotient::QuotientTimings;
 
 /// Contains variables needed for lookup in the prover algorithm.
 #[derive(Default)]
@@ -90,11 +76,11 @@ where
     aggreg8: Option<Evaluations<F, D<F>>>,
 
     /// The evaluations of the aggregation polynomial for the proof
-    eval_zeta: Option<LookupEvaluations<Vec<F>>>,
-    eval_zeta_omega: Option<LookupEvaluations<Vec<F>>>,
+    eval_zeta: Option<LookupEvaluations<ChunkedEvaluations<F>>>,
+    eval_zeta_omega: Option<LookupEvaluations<ChunkedEvaluations<F>>>,
 }
 
-impl<G: CommitmentCurve> ProverProof<G>
+impl<G: CommitmentCurve, PCS: PolynomialCommitment<G>> ProverProof<G, PCS>
 where
     G::BaseField: PrimeField,
 {
@@ -104,26 +90,166 @@ where
         EFrSponge: FrSponge<ScalarField<G>>,
     >(
         groupmap: &G::Map,
-        witness: [Vec<ScalarField<G>>; COLUMNS],
-        index: &ProverIndex<G>,
+        witness: Vec<Vec<ScalarField<G>>>,
+        index: &ProverIndex<G, PCS>,
     ) -> Result<Self> {
         Self::create_recursive::<EFqSponge, EFrSponge>(groupmap, witness, index, Vec::new())
     }
 
+    /// This function constructs prover's zk-proof like [ProverProof::create],
+    /// invoking the `hooks` at the end of each round of the protocol.
+    /// The creation of the proof is aborted with [ProverError::Cancelled] if the hooks request it.
+    pub fn create_with_hooks<
+        EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+        EFrSponge: FrSponge<ScalarField<G>>,
+        H: ProverHooks + ?Sized,
+    >(
+        groupmap: &G::Map,
+        witness: Vec<Vec<ScalarField<G>>>,
+        index: &ProverIndex<G, PCS>,
+        hooks: &mut H,
+    ) -> Result<Self> {
+        Self::create_recursive_with_hooks::<EFqSponge, EFrSponge, H>(
+            groupmap,
+            witness,
+            index,
+            Vec::new(),
+            hooks,
+        )
+    }
+
+    /// This function constructs a zk-proof for each of the given witnesses against the same ProverIndex,
+    /// like [ProverProof::create] does for each witness, creating the proofs in parallel.
+    /// The computations that only depend on the index (the quotient contributions compiled from the constraints,
+    /// the index polynomials read by the linearization, the domain constant evaluations)
+    /// are done once for all the proofs, which also reuse each other's buffers.
+    /// The proofs are returned in the order of the witnesses.
+    pub fn create_batch<
+        EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+        EFrSponge: FrSponge<ScalarField<G>>,
+    >(
+        group_map: &G::Map,
+        witnesses: Vec<Vec<Vec<ScalarField<G>>>>,
+        index: &ProverIndex<G, PCS>,
+    ) -> Result<Vec<Self>>
+    where
+        G::Map: Sync,
+    {
+        let context = ProverContext::new(index);
+        witnesses
+            .into_par_iter()
+            .map(|witness| {
+                Self::create_with_context::<EFqSponge, EFrSponge, _>(
+                    group_map,
+                    witness,
+                    index,
+                    Vec::new(),
+                    &mut (),
+                    &context,
+                )
+            })
+            .collect()
+    }
+
     /// This function constructs prover's recursive zk-proof from the witness & the ProverIndex against SRS instance
     pub fn create_recursive<
         EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
         EFrSponge: FrSponge<ScalarField<G>>,
     >(
         group_map: &G::Map,
-        mut witness: [Vec<ScalarField<G>>; COLUMNS],
-        index: &ProverIndex<G>,
+        witness: Vec<Vec<ScalarField<G>>>,
+        index: &ProverIndex<G, PCS>,
         prev_challenges: Vec<(Vec<ScalarField<G>>, PolyComm<G>)>,
     ) -> Result<Self> {
+        Self::create_recursive_with_hooks::<EFqSponge, EFrSponge, _>(
+            group_map,
+            witness,
+            index,
+            prev_challenges,
+            &mut (),
+        )
+    }
+
+    /// This function constructs prover's recursive zk-proof like [ProverProof::create_recursive],
+    /// and also returns the time spent computing the contribution of each argument to the quotient polynomial.
+    pub fn create_recursive_with_timings<
+        EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+        EFrSponge: FrSponge<ScalarField<G>>,
+    >(
+        group_map: &G::Map,
+        witness: Vec<Vec<ScalarField<G>>>,
+        index: &ProverIndex<G, PCS>,
+        prev_challenges: Vec<(Vec<ScalarField<G>>, PolyComm<G>)>,
+    ) -> Result<(Self, QuotientTimings)> {
+        #[derive(Default)]
+        struct Timings(QuotientTimings);
+
+        impl ProverHooks for Timings {
+            fn quotient_timings(&mut self, timings: &QuotientTimings) {
+                self.0 = timings.clone();
+            }
+        }
+
+        let mut timings = Timings::default();
+        let proof = Self::create_recursive_with_hooks::<EFqSponge, EFrSponge, _>(
+            group_map,
+            witness,
+            index,
+            prev_challenges,
+            &mut timings,
+        )?;
+        Ok((proof, timings.0))
+    }
+
+    /// This function constructs prover's recursive zk-proof like [ProverProof::create_recursive],
+    /// invoking the `hooks` at the end of each round of the protocol.
+    /// The creation of the proof is aborted with [ProverError::Cancelled] if the hooks request it.
+    pub fn create_recursive_with_hooks<
+        EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+        EFrSponge: FrSponge<ScalarField<G>>,
+        H: ProverHooks + ?Sized,
+    >(
+        group_map: &G::Map,
+        witness: Vec<Vec<ScalarField<G>>>,
+        index: &ProverIndex<G, PCS>,
+        prev_challenges: Vec<(Vec<ScalarField<G>>, PolyComm<G>)>,
+        hooks: &mut H,
+    ) -> Result<Self> {
+        Self::create_with_context::<EFqSponge, EFrSponge, H>(
+            group_map,
+            witness,
+            index,
+            prev_challenges,
+            hooks,
+            &ProverContext::new(index),
+        )
+    }
+
+    /// This function constructs prover's recursive zk-proof like [ProverProof::create_recursive_with_hooks],
+    /// reusing the precomputations and buffers of `context`, which must have been created for `index`.
+    fn create_with_context<
+        EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+        EFrSponge: FrSponge<ScalarField<G>>,
+        H: ProverHooks + ?Sized,
+    >(
+        group_map: &G::Map,
+        mut witness: Vec<Vec<ScalarField<G>>>,
+        index: &ProverIndex<G, PCS>,
+        prev_challenges: Vec<(Vec<ScalarField<G>>, PolyComm<G>)>,
+        hooks: &mut H,
+        context: &ProverContext<ScalarField<G>>,
+    ) -> Result<Self> {
+        let mut progress = Progress::new(hooks);
         let d1_size = index.cs.domain.d1.size as usize;
         // TODO: rng should be passed as arg
         let rng = &mut rand::rngs::OsRng;
 
+        // the witness has a column per column of the circuit, the ones beyond are ignored
+        let columns = index.cs.width.columns;
+        if witness.len() < columns {
+            return Err(ProverError::WitnessCsInconsistent);
+        }
+
         // double-check the witness
         if cfg!(test) {
             let public = witness[0][0..index.cs.public].to_vec();
@@ -136,19 +262,23 @@ where
         //~ 1. Ensure we have room in the witness for the zero-knowledge rows.
         //~    We currently expect the witness not to be of the same length as the domain,
         //~    but instead be of the length of the (smaller) circuit.
-        //~    If we cannot add `ZK_ROWS` rows to the columns of the witness before reaching
+        //~    If we cannot add `zk_rows` rows to the columns of the witness before reaching
         //~    the size of the domain, abort.
+        //~    In the non-hiding mode, no row is reserved, so the witness only needs to fit in the domain.
+        let hiding = index.cs.zk_mode == ZkMode::ZeroKnowledge;
         let length_witness = witness[0].len();
         let length_padding = d1_size
             .checked_sub(length_witness)
             .ok_or(ProverError::NoRoomForZkInWitness)?;
-        if length_padding < ZK_ROWS as usize {
+        let zk_rows = index.cs.zk_rows as usize;
+        if length_padding < zk_rows {
             return Err(ProverError::NoRoomForZkInWitness);
         }
 
         //~ 2. Pad the witness columns with Zero gates to make them the same length as the domain.
-        //~    Then, randomize the last `ZK_ROWS` of each columns.
-        for w in &mut witness {
+        //~    Then, randomize the last `zk_rows` of each column of the circuit (none in the non-hiding mode).
+        //~    The columns beyond the width of the circuit are ignored.
+        for (col, w) in witness.iter_mut().enumerate() {
             if w.len() != length_witness {
                 return Err(ProverError::WitnessCsInconsistent);
             }
@@ -157,8 +287,10 @@ where
             w.extend(std::iter::repeat(ScalarField::<G>::zero()).take(length_padding));
 
             // zk-rows
-            for row in w.iter_mut().rev().take(ZK_ROWS as usize) {
-                *row = <ScalarField<G> as UniformRand>::rand(rng);
+            if col < columns {
+                for row in w.iter_mut().rev().take(zk_rows) {
+                    *row = <ScalarField<G> as UniformRand>::rand(rng);
+                }
             }
         }
 
@@ -184,33 +316,50 @@ where
         //~    This is why we need to absorb the commitment to the public polynomial at this point.
         fq_sponge.absorb_g(&public_comm.unshifted);
 
-        //~ 7. Commit to the witness columns by creating `COLUMNS` hidding commitments.
+        //~ 7. Commit to the witness columns by creating one hidding commitment per column of the circuit
+        //~    (`COLUMNS` of them by default), the witness having at least as many columns as the circuit.
         //~    Note: since the witness is in evaluation form,
         //~    we can use the `commit_evaluation` optimization.
-        let w_comm: [(PolyComm<G>, PolyComm<ScalarField<G>>); COLUMNS] = array_init(|i| {
-            let e = Evaluations::<ScalarField<G>, D<ScalarField<G>>>::from_vec_and_domain(
-                witness[i].clone(),
-                index.cs.domain.d1,
-            );
-            index
-                .srs
-                .commit_evaluations(index.cs.domain.d1, &e, None, rng)
-        });
+        //~    In the non-hiding mode, this and all the following commitments are not blinded.
+        //~    Each commitment is padded with the point at infinity to one chunk per `max_poly_size` rows of the domain,
+        //~    as a zero column (or one of low degree) would otherwise be committed in fewer chunks.
+        let chunks = (d1_size + index.max_poly_size - 1) / index.max_poly_size;
+        let w_comm: Vec<(PolyComm<G>, PolyComm<ScalarField<G>>)> = witness[..columns]
+            .iter()
+            .map(|w| {
+                let e = Evaluations::<ScalarField<G>, D<ScalarField<G>>>::from_vec_and_domain(
+                    w.clone(),
+                    index.cs.domain.d1,
+                );
+                let mut comm =
+                    index
+                        .srs
+                        .commit_evaluations_non_hiding(index.cs.domain.d1, &e, None);
+                comm.unshifted.resize(chunks, G::zero());
+                index.mask(comm, rng)
+            })
+            .collect();
 
         //~ 8. Absorb the witness commitments with the Fq-Sponge.
         w_comm
             .iter()
             .for_each(|c| fq_sponge.absorb_g(&c.0.unshifted));
 
-        //~ 9. Compute the witness polynomials by interpolating each `COLUMNS` of the witness.
+        //~ 9. Compute the witness polynomials by interpolating each column of the witness
+        //~    (one per column of the circuit).
         //~    TODO: why not do this first, and then commit? Why commit from evaluation directly?
-        let witness_poly: [DensePolynomial<ScalarField<G>>; COLUMNS] = array_init(|i| {
-            Evaluations::<ScalarField<G>, D<ScalarField<G>>>::from_vec_and_domain(
-                witness[i].clone(),
-                index.cs.domain.d1,
-            )
-            .interpolate()
-        });
+        let witness_poly: Vec<DensePolynomial<ScalarField<G>>> = witness[..columns]
+            .iter()
+            .map(|w| {
+                Evaluations::<ScalarField<G>, D<ScalarField<G>>>::from_vec_and_domain(
+                    w.clone(),
+                    index.cs.domain.d1,
+                )
+                .interpolate()
+            })
+            .collect();
+
+        progress.round(ProverRound::WitnessCommitment)?;
 
         let mut lookup_context = LookupContext::default();
 
@@ -229,7 +378,7 @@ where
 
             //~     - Derive the scalar joint combiner $j$ from $j'$ using the endomorphism (TOOD: specify)
             let joint_combiner: ScalarField<G> =
-                ScalarChallenge(joint_combiner).to_field(&index.srs.endo_r);
+                ScalarChallenge(joint_combiner).to_field(index.srs.endo_r());
 
             //~     - If multiple lookup tables are involved,
             //~      set the `table_id_combiner` as the $j^i$ with $i$ the maximum width of any used table.
@@ -288,26 +437,32 @@ where
                 dummy_lookup_value,
                 &joint_lookup_table_d8,
                 index.cs.domain.d1,
+                index.cs.zk_rows,
                 &index.cs.gates,
                 &witness,
                 joint_combiner,
                 table_id_combiner,
             )?;
 
-            //~      - Randomize the last `EVALS` rows in each of the sorted polynomials
+            //~      - Randomize the last `zk_rows` rows in each of the sorted polynomials
             //~       in order to add zero-knowledge to the protocol.
             let sorted: Vec<_> = sorted
                 .into_iter()
-                .map(|chunk| lookup::constraints::zk_patch(chunk, index.cs.domain.d1, rng))
+                .map(|chunk| {
+                    lookup::constraints::zk_patch(chunk, index.cs.domain.d1, index.cs.zk_rows, rng)
+                })
                 .collect();
 
             //~      - Commit each of the sorted polynomials.
             let sorted_comms: Vec<_> = sorted
                 .iter()
                 .map(|v| {
-                    index
-                        .srs
-                        .commit_evaluations(index.cs.domain.d1, v, None, rng)
+                    index.mask(
+                        index
+                            .srs
+                            .commit_evaluations_non_hiding(index.cs.domain.d1, v, None),
+                        rng,
+                    )
                 })
                 .collect();
 
@@ -331,6 +486,8 @@ where
             lookup_context.sorted8 = Some(sorted8);
             lookup_context.joint_lookup_table_d8 = Some(joint_lookup_table_d8);
             lookup_context.joint_lookup_table = Some(joint_lookup_table);
+
+            progress.round(ProverRound::LookupSorted)?;
         }
 
         //~ 11. Sample $\beta$ with the Fq-Sponge.
@@ -348,6 +505,7 @@ where
                 lookup_context.dummy_lookup_value.unwrap(),
                 joint_lookup_table_d8,
                 index.cs.domain.d1,
+                index.cs.zk_rows,
                 &index.cs.gates,
                 &witness,
                 &lookup_context.joint_combiner.unwrap(),
@@ -359,9 +517,12 @@ where
             )?;
 
             //~     - Commit to the aggregation polynomial.
-            let aggreg_comm = index
-                .srs
-                .commit_evaluations(index.cs.domain.d1, &aggreg, None, rng);
+            let aggreg_comm = index.mask(
+                index
+                    .srs
+                    .commit_evaluations_non_hiding(index.cs.domain.d1, &aggreg, None),
+                rng,
+            );
 
             //~     - Absorb the commitment to the aggregation polynomial with the Fq-Sponge.
             fq_sponge.absorb_g(&aggreg_comm.0.unshifted);
@@ -375,22 +536,26 @@ where
             lookup_context.aggreg_comm = Some(aggreg_comm);
             lookup_context.aggreg_coeffs = Some(aggreg_coeffs);
             lookup_context.aggreg8 = Some(aggreg8);
+
+            progress.round(ProverRound::LookupAggregation)?;
         }
 
         //~ 14. Compute the permutation aggregation polynomial $z$.
         let z_poly = index.cs.perm_aggreg(&witness, &beta, &gamma, rng)?;
 
         //~ 15. Commit (hidding) to the permutation aggregation polynomial $z$.
-        let z_comm = index.srs.commit(&z_poly, None, rng);
+        let z_comm = index.mask(index.srs.commit_non_hiding(&z_poly, None), rng);
 
         //~ 16. Absorb the permutation aggregation polynomial $z$ with the Fq-Sponge.
         fq_sponge.absorb_g(&z_comm.0.unshifted);
 
+        progress.round(ProverRound::Permutation)?;
+
         //~ 17. Sample $\alpha'$ with the Fq-Sponge.
         let alpha_chal = ScalarChallenge(fq_sponge.challenge());
 
         //~ 18. Derive $\alpha$ from $\alpha'$ using the endomorphism (TODO: details)
-        let alpha: ScalarField<G> = alpha_chal.to_field(&index.srs.endo_r);
+        let alpha: ScalarField<G> = alpha_chal.to_field(index.srs.endo_r());
 
         //~ 19. TODO: instantiate alpha?
         let mut all_alphas = index.powers_of_alpha.clone();
@@ -418,22 +583,27 @@ where
         };
 
         let lagrange = index.cs.evaluate(&witness_poly, &z_poly);
+        // in the bounded memory mode, the quotient evaluates the index polynomials over the larger domains
+        // argument by argument, and the linearization only reads their values on the rows
+        let column_evaluations = &context.row_evaluations;
         let env = {
             let mut index_evals = HashMap::new();
             use GateType::*;
-            index_evals.insert(Poseidon, &index.cs.ps8);
-            index_evals.insert(CompleteAdd, &index.cs.complete_addl4);
-            index_evals.insert(VarBaseMul, &index.cs.mull8);
-            index_evals.insert(EndoMul, &index.cs.emull);
-            index_evals.insert(EndoMulScalar, &index.cs.endomul_scalar8);
+            index_evals.insert(Poseidon, &column_evaluations.ps8);
+            index_evals.insert(CompleteAdd, &column_evaluations.complete_addl4);
+            index_evals.insert(VarBaseMul, &column_evaluations.mull8);
+            index_evals.insert(EndoMul, &column_evaluations.emull);
+            index_evals.insert(EndoMulScalar, &column_evaluations.endomul_scalar8);
             [ChaCha0, ChaCha1, ChaCha2, ChaChaFinal]
                 .iter()
                 .enumerate()
                 .for_each(|(i, g)| {
-                    if let Some(c) = &index.cs.chacha8 {
+                    if let Some(c) = &column_evaluations.chacha8 {
                         index_evals.insert(*g, &c[i]);
                     }
                 });
+            // only the selectors of the gates used in the circuit are needed
+            index_evals.retain(|g, _| index.gate_types.contains(g));
             Environment {
                 constants: Constants {
                     alpha,
@@ -442,9 +612,10 @@ where
                     joint_combiner: lookup_context.joint_combiner,
                     endo_coefficient: index.cs.endo,
                     mds: index.cs.fr_sponge_params.mds.clone(),
+                    zk_rows: index.cs.zk_rows,
                 },
                 witness: &lagrange.d8.this.w,
-                coefficient: &index.cs.coefficients8,
+                coefficient: &column_evaluations.coefficients8,
                 vanishes_on_last_4_rows: &index.cs.precomputations().vanishes_on_last_4_rows,
                 z: &lagrange.d8.this.z,
                 l0_1: l0_1(index.cs.domain.d1),
@@ -454,150 +625,41 @@ where
             }
         };
 
-        let quotient_poly = {
-            // generic
-            let alphas =
-                all_alphas.get_alphas(ArgumentType::Gate(GateType::Generic), generic::CONSTRAINTS);
-            let mut t4 = index.cs.gnrc_quot(alphas, &lagrange.d4.this.w);
-
-            if cfg!(debug_assertions) {
-                let p4 = public_poly.evaluate_over_domain_by_ref(index.cs.domain.d4);
-                let gen_minus_pub = &t4 + &p4;
-
-                check_constraint!(index, gen_minus_pub);
-            }
-
-            // complete addition
-            {
-                let add_constraint = CompleteAdd::combined_constraints(&all_alphas);
-                let add4 = add_constraint.evaluations(&env);
-                t4 += &add4;
-
-                check_constraint!(index, add4);
-            }
-
-            // permutation
-            let (mut t8, bnd) = {
-                let alphas =
-                    all_alphas.get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS);
-                let (perm, bnd) = index
-                    .cs
-                    .perm_quot(&lagrange, beta, gamma, &z_poly, alphas)?;
-
-                check_constraint!(index, perm);
-
-                (perm, bnd)
-            };
-
-            // scalar multiplication
-            {
-                let mul8 = VarbaseMul::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &mul8;
-
-                check_constraint!(index, mul8);
-            }
-
-            // endoscaling
-            {
-                let emul8 = EndosclMul::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &emul8;
-
-                check_constraint!(index, emul8);
-            }
-
-            // endoscaling scalar computation
-            {
-                let emulscalar8 =
-                    EndomulScalar::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &emulscalar8;
-
-                check_constraint!(index, emulscalar8);
-            }
-
-            // poseidon
-            {
-                let pos8 = Poseidon::combined_constraints(&all_alphas).evaluations(&env);
-                t8 += &pos8;
-
-                check_constraint!(index, pos8);
-            }
-
-            // chacha
-            {
-                if index.cs.chacha8.as_ref().is_some() {
-                    let chacha0 = ChaCha0::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha0;
-
-                    let chacha1 = ChaCha1::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha1;
-
-                    let chacha2 = ChaCha2::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha2;
-
-                    let chacha_final =
-                        ChaChaFinal::combined_constraints(&all_alphas).evaluations(&env);
-                    t4 += &chacha_final;
-
-                    check_constraint!(index, chacha0);
-                    check_constraint!(index, chacha1);
-                    check_constraint!(index, chacha2);
-                    check_constraint!(index, chacha_final);
-                }
-            }
-
-            // lookup
-            if let Some(lcs) = index.cs.lookup_constraint_system.as_ref() {
-                let lookup_alphas =
-                    all_alphas.get_alphas(ArgumentType::Lookup, lookup::constraints::CONSTRAINTS);
-                let constraints =
-                    lookup::constraints::constraints(&lcs.configuration, index.cs.domain.d1);
-
-                for (constraint, alpha_pow) in constraints.into_iter().zip_eq(lookup_alphas) {
-                    let mut eval = constraint.evaluations(&env);
-                    eval.evals.iter_mut().for_each(|x| *x *= alpha_pow);
-
-                    if eval.domain().size == t4.domain().size {
-                        t4 += &eval;
-                    } else if eval.domain().size == t8.domain().size {
-                        t8 += &eval;
-                    } else {
-                        panic!("Bad evaluation")
-                    }
-                }
-            }
-
-            // public polynomial
-            let mut f = t4.interpolate() + t8.interpolate();
-            f += &public_poly;
-
-            // divide contributions with vanishing polynomial
-            let (mut quotient, res) = f
-                .divide_by_vanishing_poly(index.cs.domain.d1)
-                .ok_or(ProverError::Prover("division by vanishing polynomial"))?;
-            if !res.is_zero() {
-                return Err(ProverError::Prover(
-                    "rest of division by vanishing polynomial",
-                ));
-            }
-
-            quotient += &bnd; // already divided by Z_H
-            quotient
-        };
-
-        //~ 21. commit (hiding) to the quotient polynomial $t$
+        let (quotient_poly, quotient_timings) = quotient::quotient_poly(
+            index,
+            context,
+            &all_alphas,
+            &env,
+            index.column_evaluations().map(|c| c.as_ref()),
+            &lagrange,
+            &witness_poly,
+            &z_poly,
+            &public_poly,
+        )?;
+        progress.quotient_timings(&quotient_timings);
+
+        //~ 21. commit (hiding) to the quotient polynomial $t$.
+        //~     It is committed in `max(PERMUTS, p)` times as many chunks as a polynomial of degree less than the domain size,
+        //~     where `p` is the number of permuted columns
+        //~     (that is, `max(PERMUTS, p)` chunks unless the domain is larger than `max_poly_size`).
         //~     TODO: specify the dummies
         let t_comm = {
-            let (mut t_comm, mut omega_t) = index.srs.commit(&quotient_poly, None, rng);
+            let (mut t_comm, mut omega_t) =
+                index.mask(index.srs.commit_non_hiding(&quotient_poly, None), rng);
 
-            let expected_t_size = PERMUTS;
+            let expected_t_size = index.cs.width.quotient_chunks() * chunks;
             let dummies = expected_t_size - t_comm.unshifted.len();
             // Add `dummies` many hiding commitments to the 0 polynomial, since if the
             // number of commitments in `t_comm` is less than the max size, it means that
             // the higher degree coefficients of `t` are 0.
+            // In the non-hiding mode, these are the point at infinity.
             for _ in 0..dummies {
-                use ark_ec::ProjectiveCurve;
-                let w = <ScalarField<G> as UniformRand>::rand(rng);
-                t_comm.unshifted.push(index.srs.h.mul(w).into_affine());
+                let (comm, w) = if hiding {
+                    index.srs.commit_blinded_zero(rng)
+                } else {
+                    (G::zero(), ScalarField::<G>::zero())
+                };
+                t_comm.unshifted.push(comm);
                 omega_t.unshifted.push(w);
             }
             (t_comm, omega_t)
@@ -606,17 +668,19 @@ where
         //~ 22. Absorb the the commitment of the quotient polynomial with the Fq-Sponge.
         fq_sponge.absorb_g(&t_comm.0.unshifted);
 
+        progress.round(ProverRound::Quotient)?;
+
         //~ 23. Sample $\zeta'$ with the Fq-Sponge.
         let zeta_chal = ScalarChallenge(fq_sponge.challenge());
 
         //~ 24. Derive $\zeta$ from $\zeta'$ using the endomorphism (TODO: specify)
-        let zeta = zeta_chal.to_field(&index.srs.endo_r);
+        let zeta = zeta_chal.to_field(index.srs.endo_r());
 
         let omega = index.cs.domain.d1.group_gen;
         let zeta_omega = zeta * omega;
 
         //~ 25. If lookup is used, evaluate the following polynomials at $\zeta$ and $\zeta \omega$:
-        if let Some(lcs) = &index.cs.lookup_constraint_system {
+        if index.cs.lookup_constraint_system.is_some() {
             //     - the aggregation polynomial
             let aggreg = lookup_context
                 .aggreg_coeffs
@@ -632,44 +696,20 @@ where
                 .iter()
                 .map(|c| c.to_chunked_polynomial(index.max_poly_size));
 
-            //      - the table polynonials
-            let base_table = lcs
-                .lookup_table
-                .iter()
-                .map(|p| p.to_chunked_polynomial(index.max_poly_size));
-
-            let table_ids = lcs
-                .table_ids
+            //      - the combined table polynomial
+            let table = lookup_context
+                .joint_lookup_table
                 .as_ref()
-                .map(|tids| tids.to_chunked_polynomial(index.max_poly_size));
-
-            let lookup_evals = |e: ScalarField<G>| {
-                let table = base_table.clone().map(|p| p.evaluate_chunks(e)).rev().fold(
-                    vec![ScalarField::<G>::zero()],
-                    |acc, x| {
-                        acc.into_iter()
-                            .zip(x.iter())
-                            .map(|(acc, x)| acc * lookup_context.joint_combiner.unwrap() + x)
-                            .collect()
-                    },
-                );
-
-                let table = match &table_ids {
-                    None => table,
-                    Some(table_ids) => table
-                        .into_iter()
-                        .zip(table_ids.evaluate_chunks(e))
-                        .map(|(x, table_id)| {
-                            x + (lookup_context.table_id_combiner.unwrap() * table_id)
-                        })
-                        .collect(),
-                };
+                .unwrap()
+                .to_chunked_polynomial(index.max_poly_size);
 
-                LookupEvaluations {
-                    aggreg: aggreg.evaluate_chunks(e),
-                    sorted: sorted.clone().map(|s| s.evaluate_chunks(e)).collect(),
-                    table,
-                }
+            let lookup_evals = |e: ScalarField<G>| LookupEvaluations {
+                aggreg: aggreg.evaluate_chunks(e).into(),
+                sorted: sorted
+                    .clone()
+                    .map(|s| s.evaluate_chunks(e).into())
+                    .collect(),
+                table: table.evaluate_chunks(e).into(),
             };
 
             lookup_context.eval_zeta = Some(lookup_evals(zeta));
@@ -694,22 +734,35 @@ where
         //~     $$(f_0(x), f_1(x), f_2(x), \ldots)$$
         //~
         //~      TODO: do we want to specify more on that? It seems unecessary except for the t polynomial (or if for some reason someone sets that to a low value)
-        let chunked_evals = {
-            let chunked_evals_zeta = ProofEvaluations::<Vec<ScalarField<G>>> {
-                s: array_init(|i| {
-                    index.cs.sigmam[0..PERMUTS - 1][i]
-                        .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta)
-                }),
-                w: array_init(|i| {
-                    witness_poly[i]
+        // the evaluations of the witness columns are padded with zeros, like their commitments
+        let witness_evals = |x: ScalarField<G>| -> Vec<ChunkedEvaluations<ScalarField<G>>> {
+            witness_poly
+                .iter()
+                .map(|w| {
+                    let mut evals = w
                         .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta)
-                }),
+                        .evaluate_chunks(x);
+                    evals.resize(chunks, ScalarField::<G>::zero());
+                    evals.into()
+                })
+                .collect()
+        };
+        let chunked_evals = {
+            let chunked_evals_zeta = ProofEvaluations::<ChunkedEvaluations<ScalarField<G>>> {
+                s: index.cs.sigmam[0..index.cs.width.permuts - 1]
+                    .iter()
+                    .map(|s| {
+                        s.to_chunked_polynomial(index.max_poly_size)
+                            .evaluate_chunks(zeta)
+                            .into()
+                    })
+                    .collect(),
+                w: witness_evals(zeta),
 
                 z: z_poly
                     .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta),
+                    .evaluate_chunks(zeta)
+                    .into(),
 
                 lookup: lookup_context.eval_zeta.take(),
 
@@ -717,30 +770,32 @@ where
                     .cs
                     .genericm
                     .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta),
+                    .evaluate_chunks(zeta)
+                    .into(),
 
                 poseidon_selector: index
                     .cs
                     .psm
                     .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta),
+                    .evaluate_chunks(zeta)
+                    .into(),
             };
-            let chunked_evals_zeta_omega = ProofEvaluations::<Vec<ScalarField<G>>> {
-                s: array_init(|i| {
-                    index.cs.sigmam[0..PERMUTS - 1][i]
-                        .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta_omega)
-                }),
+            let chunked_evals_zeta_omega = ProofEvaluations::<ChunkedEvaluations<ScalarField<G>>> {
+                s: index.cs.sigmam[0..index.cs.width.permuts - 1]
+                    .iter()
+                    .map(|s| {
+                        s.to_chunked_polynomial(index.max_poly_size)
+                            .evaluate_chunks(zeta_omega)
+                            .into()
+                    })
+                    .collect(),
 
-                w: array_init(|i| {
-                    witness_poly[i]
-                        .to_chunked_polynomial(index.max_poly_size)
-                        .evaluate_chunks(zeta_omega)
-                }),
+                w: witness_evals(zeta_omega),
 
                 z: z_poly
                     .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta_omega),
+                    .evaluate_chunks(zeta_omega)
+                    .into(),
 
                 lookup: lookup_context.eval_zeta_omega.take(),
 
@@ -748,20 +803,22 @@ where
                     .cs
                     .genericm
                     .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta_omega),
+                    .evaluate_chunks(zeta_omega)
+                    .into(),
 
                 poseidon_selector: index
                     .cs
                     .psm
                     .to_chunked_polynomial(index.max_poly_size)
-                    .evaluate_chunks(zeta_omega),
+                    .evaluate_chunks(zeta_omega)
+                    .into(),
             };
 
             [chunked_evals_zeta, chunked_evals_zeta_omega]
         };
 
         let zeta_to_srs_len = zeta.pow(&[index.max_poly_size as u64]);
-        let zeta_omega_to_srs_len = zeta.pow(&[index.max_poly_size as u64]);
+        let zeta_omega_to_srs_len = zeta_omega.pow(&[index.max_poly_size as u64]);
         let zeta_to_domain_size = zeta.pow(&[d1_size as u64]);
 
         //~ 27. Evaluate the same polynomials without chunking them
@@ -771,22 +828,7 @@ where
             &chunked_evals
                 .iter()
                 .zip(power_of_eval_points_for_chunks.iter())
-                .map(|(es, &e1)| ProofEvaluations::<ScalarField<G>> {
-                    s: array_init(|i| DensePolynomial::eval_polynomial(&es.s[i], e1)),
-                    w: array_init(|i| DensePolynomial::eval_polynomial(&es.w[i], e1)),
-                    z: DensePolynomial::eval_polynomial(&es.z, e1),
-                    lookup: es.lookup.as_ref().map(|l| LookupEvaluations {
-                        table: DensePolynomial::eval_polynomial(&l.table, e1),
-                        aggreg: DensePolynomial::eval_polynomial(&l.aggreg, e1),
-                        sorted: l
-                            .sorted
-                            .iter()
-                            .map(|p| DensePolynomial::eval_polynomial(p, e1))
-                            .collect(),
-                    }),
-                    generic_selector: DensePolynomial::eval_polynomial(&es.generic_selector, e1),
-                    poseidon_selector: DensePolynomial::eval_polynomial(&es.poseidon_selector, e1),
-                })
+                .map(|(es, &e1)| es.combine(e1))
                 .collect::<Vec<_>>()
         };
 
@@ -831,6 +873,13 @@ where
             &f_chunked - &t_chunked.scale(zeta_to_domain_size - ScalarField::<G>::one())
         };
 
+        // in tests, check that all the powers of alpha were used as registered,
+        // including the ones used to compile the constraints of the context
+        if cfg!(test) {
+            all_alphas.extend_usage(&context.powers_of_alpha);
+            all_alphas.assert_usage();
+        }
+
         //~ 29. construct the blinding part of the ft polynomial commitment
         //~     see https://o1-labs.github.io/mina-book/crypto/plonk/maller_15.html#evaluation-proof-and-blinding-factors
         let blinding_ft = {
@@ -856,13 +905,12 @@ where
         //~ 32. Squeeze the Fq-sponge and absorb the result with the Fr-Sponge.
         fr_sponge.absorb(&fq_sponge.digest());
 
-        //~ 33. Evaluate the negated public polynomial (if present) at $\zeta$ and $\zeta\omega$.
-        let public_evals = if public_poly.is_zero() {
-            [Vec::new(), Vec::new()]
-        } else {
+        //~ 33. Chunk evaluate the negated public polynomial (if present) at $\zeta$ and $\zeta\omega$.
+        let public_evals = {
+            let public_chunked = public_poly.to_chunked_polynomial(index.max_poly_size);
             [
-                vec![public_poly.evaluate(&zeta)],
-                vec![public_poly.evaluate(&zeta_omega)],
+                public_chunked.evaluate_chunks(zeta),
+                public_chunked.evaluate_chunks(zeta_omega),
             ]
         };
 
@@ -871,8 +919,8 @@ where
         //~     - z
         //~     - generic selector
         //~     - poseidon selector
-        //~     - the 15 register/witness
-        //~     - 6 sigmas evaluations (the last one is not evaluated)
+        //~     - the registers/witness, one per column of the circuit (15 by default)
+        //~     - the sigmas evaluations, one per permuted column but the last one, which is not evaluated (6 by default)
         for i in 0..2 {
             fr_sponge.absorb_evaluations(&public_evals[i], &chunked_evals[i])
         }
@@ -880,17 +928,19 @@ where
         //~ 35. Absorb the unique evaluation of ft: $ft(\zeta\omega)$.
         fr_sponge.absorb(&ft_eval1);
 
+        progress.round(ProverRound::Evaluations)?;
+
         //~ 36. Sample $v'$ with the Fr-Sponge
         let v_chal = fr_sponge.challenge();
 
         //~ 37. Derive $v$ from $v'$ using the endomorphism (TODO: specify)
-        let v = v_chal.to_field(&index.srs.endo_r);
+        let v = v_chal.to_field(index.srs.endo_r());
 
         //~ 38. Sample $u'$ with the Fr-Sponge
         let u_chal = fr_sponge.challenge();
 
         //~ 39. Derive $u$ from $u'$ using the endomorphism (TODO: specify)
-        let u = u_chal.to_field(&index.srs.endo_r);
+        let u = u_chal.to_field(index.srs.endo_r());
 
         //~ 40. Create a list of all polynomials that will require evaluations
         //~     (and evaluation proofs) in the protocol.
@@ -900,6 +950,11 @@ where
             shifted: None,
         };
 
+        // the zero blinders of a polynomial committed without hiding, one per chunk
+        let non_hiding_chunks = |poly: &DensePolynomial<ScalarField<G>>| {
+            non_hiding((poly.coeffs.len() + index.max_poly_size - 1) / index.max_poly_size)
+        };
+
         let polys = prev_challenges
             .iter()
             .map(|(chals, comm)| {
@@ -921,13 +976,21 @@ where
         //~     - the permutation aggregation polynomial z polynomial
         //~     - the generic selector
         //~     - the poseidon selector
-        //~     - the 15 registers/witness columns
-        //~     - the 6 sigmas
-        polynomials.extend(vec![(&public_poly, None, non_hiding(1))]);
+        //~     - the registers/witness columns of the circuit (15 by default)
+        //~     - the sigmas of the permuted columns but the last one (6 by default)
+        polynomials.extend(vec![(&public_poly, None, non_hiding_chunks(&public_poly))]);
         polynomials.extend(vec![(&ft, None, blinding_ft)]);
         polynomials.extend(vec![(&z_poly, None, z_comm.1)]);
-        polynomials.extend(vec![(&index.cs.genericm, None, non_hiding(1))]);
-        polynomials.extend(vec![(&index.cs.psm, None, non_hiding(1))]);
+        polynomials.extend(vec![(
+            &index.cs.genericm,
+            None,
+            non_hiding_chunks(&index.cs.genericm),
+        )]);
+        polynomials.extend(vec![(
+            &index.cs.psm,
+            None,
+            non_hiding_chunks(&index.cs.psm),
+        )]);
         polynomials.extend(
             witness_poly
                 .iter()
@@ -936,9 +999,9 @@ where
                 .collect::<Vec<_>>(),
         );
         polynomials.extend(
-            index.cs.sigmam[0..PERMUTS - 1]
+            index.cs.sigmam[0..index.cs.width.permuts - 1]
                 .iter()
-                .map(|w| (w, None, non_hiding(1)))
+                .map(|w| (w, None, non_hiding_chunks(w)))
                 .collect::<Vec<_>>(),
         );
 
@@ -958,7 +1021,11 @@ where
 
             // add the combined table polynomial
             let joint_lookup_table = lookup_context.joint_lookup_table.as_ref().unwrap();
-            polynomials.push((joint_lookup_table, None, non_hiding(1)));
+            polynomials.push((
+                joint_lookup_table,
+                None,
+                non_hiding_chunks(joint_lookup_table),
+            ));
         }
 
         //~ 42. Create an aggregated evaluation proof for all of these polynomials at $\zeta$ and $\zeta\omega$ using $u$ and $v$.
@@ -972,6 +1039,8 @@ where
             rng,
         );
 
+        progress.round(ProverRound::Opening)?;
+
         let lookup = lookup_context
             .aggreg_comm
             .zip(lookup_context.sorted_comms)
@@ -982,7 +1051,7 @@ where
 
         Ok(Self {
             commitments: ProverCommitments {
-                w_comm: array_init(|i| w_comm[i].0.clone()),
+                w_comm: w_comm.into_iter().map(|c| c.0).collect(),
                 z_comm: z_comm.0,
                 t_comm: t_comm.0,
                 lookup,
@@ -999,9 +1068,10 @@ where
 #[cfg(feature = "ocaml_types")]
 pub mod caml {
     use super::*;
-    use crate::proof::caml::CamlProofEvaluations;
+    use crate::{circuits::wires::COLUMNS, proof::caml::CamlProofEvaluations};
     use ark_ec::AffineCurve;
     use commitment_dlog::commitment::caml::{CamlOpeningProof, CamlPolyComm};
+    use std::convert::TryInto;
 
     #[derive(ocaml::IntoValue, ocaml::FromValue, ocaml_gen::Struct)]
     pub struct CamlProverProof<CamlG, CamlF> {
@@ -1057,15 +1127,23 @@ pub mod caml {
     // CamlProverCommitments<CamlG> <-> ProverCommitments<G>
     //
 
-    impl<G, CamlG> From<ProverCommitments<G>> for CamlProverCommitments<CamlG>
+    impl<G, CamlG> TryFrom<ProverCommitments<G>> for CamlProverCommitments<CamlG>
     where
         G: AffineCurve,
         CamlPolyComm<CamlG>: From<PolyComm<G>>,
     {
-        fn from(prover_comm: ProverCommitments<G>) -> Self {
+        type Error = ProverError;
+
+        /// Fails if the commitments are not the ones of a circuit of the default width,
+        /// the only one supported by the OCaml bindings.
+        fn try_from(prover_comm: ProverCommitments<G>) -> Result<Self, ProverError> {
+            let w_comm: [PolyComm<G>; COLUMNS] = prover_comm
+                .w_comm
+                .try_into()
+                .map_err(|_| ProverError::UnsupportedWidth)?;
             let [w_comm0, w_comm1, w_comm2, w_comm3, w_comm4, w_comm5, w_comm6, w_comm7, w_comm8, w_comm9, w_comm10, w_comm11, w_comm12, w_comm13, w_comm14] =
-                prover_comm.w_comm;
-            Self {
+                w_comm;
+            Ok(Self {
                 w_comm: (
                     w_comm0.into(),
                     w_comm1.into(),
@@ -1085,7 +1163,7 @@ pub mod caml {
                 ),
                 z_comm: prover_comm.z_comm.into(),
                 t_comm: prover_comm.t_comm.into(),
-            }
+            })
         }
     }
 
@@ -1113,7 +1191,7 @@ pub mod caml {
                 w_comm14,
             ) = caml_prover_comm.w_comm;
             ProverCommitments {
-                w_comm: [
+                w_comm: vec![
                     w_comm0.into(),
                     w_comm1.into(),
                     w_comm2.into(),
@@ -1141,17 +1219,22 @@ pub mod caml {
     // ProverProof<G> <-> CamlProverProof<CamlG, CamlF>
     //
 
-    impl<G, CamlG, CamlF> From<ProverProof<G>> for CamlProverProof<CamlG, CamlF>
+    impl<G, CamlG, CamlF> TryFrom<ProverProof<G>> for CamlProverProof<CamlG, CamlF>
     where
-        G: AffineCurve,
+        G: CommitmentCurve,
         CamlG: From<G>,
         CamlF: From<G::ScalarField>,
     {
-        fn from(pp: ProverProof<G>) -> Self {
-            Self {
-                commitments: pp.commitments.into(),
+        type Error = ProverError;
+
+        /// Fails if the proof is not the one of a circuit of the default width,
+        /// the only one supported by the OCaml bindings.
+        fn try_from(pp: ProverProof<G>) -> Result<Self, ProverError> {
+            let [evals0, evals1] = pp.evals;
+            Ok(Self {
+                commitments: pp.commitments.try_into()?,
                 proof: pp.proof.into(),
-                evals: (pp.evals[0].clone().into(), pp.evals[1].clone().into()),
+                evals: (evals0.try_into()?, evals1.try_into()?),
                 ft_eval1: pp.ft_eval1.into(),
                 public: pp.public.into_iter().map(Into::into).collect(),
                 prev_challenges: pp
@@ -1162,13 +1245,13 @@ pub mod caml {
                         (v, c.into())
                     })
                     .collect(),
-            }
+            })
         }
     }
 
     impl<G, CamlG, CamlF> From<CamlProverProof<CamlG, CamlF>> for ProverProof<G>
     where
-        G: AffineCurve + From<CamlG>,
+        G: CommitmentCurve + From<CamlG>,
         G::ScalarField: From<CamlF>,
     {
         fn from(caml_pp: CamlProverProof<CamlG, CamlF>) -> ProverProof<G> {
@@ -0,0 +1,52 @@
+//! This module implements the [ProverContext], what the prover computes from the index alone,
+//! and which can therefore be shared by all the proofs created with the index
+//! (see [ProverProof::create_batch](crate::proof::ProverProof::create_batch)).
+
+use super::quotient::{self, BufferPool, Contribution};
+use crate::{
+    alphas::Alphas, circuits::argument::ArgumentType, circuits::constraints::ColumnEvaluations,
+    prover_index::ProverIndex,
+};
+use ark_ff::{FftField, PrimeField, SquareRootField};
+use commitment_dlog::{commitment::CommitmentCurve, PolynomialCommitment};
+use std::sync::Arc;
+
+/// The precomputations and buffers of the prover that do not depend on the witness.
+///
+/// The domain constant evaluations, the Lagrange bases of the SRS and,
+/// unless the index is in the [MemoryMode::Bounded](crate::prover_index::MemoryMode::Bounded) mode,
+/// the evaluations of the index polynomials are kept in the index:
+/// the context only makes sure they are computed before the proofs start.
+/// Note that the twiddle factors of the FFTs are computed by arkworks for each FFT, and cannot be shared.
+pub(crate) struct ProverContext<F: FftField> {
+    /// The evaluations of the index polynomials,
+    /// only on the rows in the bounded memory mode (see [ProverIndex::row_evaluations]).
+    pub(crate) row_evaluations: Arc<ColumnEvaluations<F>>,
+    /// The contributions of the arguments to the quotient polynomial, compiled once.
+    pub(crate) contributions: Vec<(ArgumentType, Contribution<F>)>,
+    /// The powers of alpha requested to compile the contributions,
+    /// which are added to the ones requested by each proof when checking their usage.
+    pub(crate) powers_of_alpha: Alphas<F>,
+    /// The buffers in which the contributions to the quotient polynomial are accumulated.
+    pub(crate) buffers: BufferPool<F>,
+}
+
+impl<F: FftField + SquareRootField> ProverContext<F> {
+    /// Creates the context of the proofs created with `index`.
+    pub(crate) fn new<G, PCS>(index: &ProverIndex<G, PCS>) -> Self
+    where
+        G: CommitmentCurve<ScalarField = F>,
+        G::BaseField: PrimeField,
+        PCS: PolynomialCommitment<G>,
+    {
+        index.cs.precomputations();
+        let powers_of_alpha = index.powers_of_alpha.without_usage();
+        let contributions = quotient::contributions(index, &powers_of_alpha);
+        ProverContext {
+            row_evaluations: index.row_evaluations(),
+            contributions,
+            powers_of_alpha,
+            buffers: BufferPool::default(),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! This module implements hooks to follow the progress of the prover, and to cancel it.
+
+use super::QuotientTimings;
+use crate::error::ProverError;
+use std::{
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
+
+/// A round of the protocol, at the end of which the prover invokes its [ProverHooks].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProverRound {
+    /// The commitments to the witness columns.
+    WitnessCommitment,
+    /// The commitments to the sorted lookup polynomials (only if lookup is used).
+    LookupSorted,
+    /// The commitment to the lookup aggregation polynomial (only if lookup is used).
+    LookupAggregation,
+    /// The commitment to the permutation aggregation polynomial $z$.
+    Permutation,
+    /// The commitment to the quotient polynomial $t$.
+    Quotient,
+    /// The evaluations of the polynomials at $\zeta$ and $\zeta\omega$.
+    Evaluations,
+    /// The opening proof.
+    Opening,
+}
+
+/// Hooks invoked by the prover while it creates a proof
+/// (see [ProverProof::create_with_hooks](crate::proof::ProverProof::create_with_hooks)).
+/// All the methods do nothing by default.
+pub trait ProverHooks {
+    /// Called at the end of each round of the protocol,
+    /// with the time spent since the end of the previous round (or since the prover started).
+    /// Returning [ControlFlow::Break] aborts the creation of the proof with [ProverError::Cancelled].
+    fn round(&mut self, _round: ProverRound, _elapsed: Duration) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called once the quotient polynomial has been computed,
+    /// with the time spent computing the contribution of each argument.
+    fn quotient_timings(&mut self, _timings: &QuotientTimings) {}
+}
+
+/// No hooks.
+impl ProverHooks for () {}
+
+/// Times the rounds of the protocol, and reports them to the hooks.
+pub(crate) struct Progress<'a, H: ?Sized> {
+    hooks: &'a mut H,
+    start: Instant,
+}
+
+impl<'a, H: ProverHooks + ?Sized> Progress<'a, H> {
+    pub(crate) fn new(hooks: &'a mut H) -> Self {
+        Progress {
+            hooks,
+            start: Instant::now(),
+        }
+    }
+
+    /// Reports the end of a round, and returns an error if the hooks cancel the proof.
+    pub(crate) fn round(&mut self, round: ProverRound) -> Result<(), ProverError> {
+        match self.hooks.round(round, self.start.elapsed()) {
+            ControlFlow::Continue(()) => {
+                self.start = Instant::now();
+                Ok(())
+            }
+            ControlFlow::Break(()) => Err(ProverError::Cancelled(round)),
+        }
+    }
+
+    pub(crate) fn quotient_timings(&mut self, timings: &QuotientTimings) {
+        self.hooks.quotient_timings(timings);
+    }
+}
@@ -0,0 +1,489 @@
+//! This module computes the quotient polynomial $t$ of the prover.
+//!
+//! The contribution of each argument to the quotient polynomial is evaluated over $d_4$ or $d_8$
+//! (or over a domain of size $16n$ for the permutation argument of circuits permuting more than 7 columns),
+//! and the contributions are evaluated in parallel.
+//! Each thread adds the contributions it evaluates to its own buffers (one per domain),
+//! so that the memory used by the computation is bounded by a few buffers per thread,
+//! instead of a buffer per argument.
+//! The buffers of the threads are then summed, interpolated, and divided by the vanishing polynomial.
+//!
+//! The contributions are compiled once per [ProverContext], with symbolic powers of alpha,
+//! and the buffers are taken from the [BufferPool] of the context,
+//! so that the proofs created with the same context share them.
+//!
+//! In the bounded memory mode, the evaluations of the index polynomials over the larger domains
+//! are not kept for the whole proof: each contribution evaluates the ones it reads, and drops them once added.
+
+use crate::{
+    alphas::Alphas,
+    circuits::{
+        argument::{Argument, ArgumentType},
+        constraints::{ColumnEvaluations, ConstraintSystem},
+        domains::EvaluationDomains,
+        expr::{compiled::CompiledExpr, Column, ConstantExpr, Environment, Expr, E},
+        gate::GateType,
+        lookup,
+        polynomial::WitnessOverDomains,
+        polynomials::{
+            chacha::{ChaCha0, ChaCha1, ChaCha2, ChaChaFinal},
+            complete_add::CompleteAdd,
+            endomul_scalar::EndomulScalar,
+            endosclmul::EndosclMul,
+            generic, permutation,
+            poseidon::Poseidon,
+            varbasemul::VarbaseMul,
+        },
+    },
+    error::ProverError,
+    prover::context::ProverContext,
+    prover_index::ProverIndex,
+};
+use ark_ff::{FftField, SquareRootField, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
+};
+use commitment_dlog::commitment::CommitmentCurve;
+use itertools::Itertools;
+use o1_utils::{types::fields::*, ExtendedDensePolynomial as _};
+use rayon::prelude::*;
+use std::{
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The time spent computing the quotient polynomial, broken down per argument.
+#[derive(Clone, Debug, Default)]
+pub struct QuotientTimings {
+    /// The time spent evaluating the contribution of each argument,
+    /// in the order in which the contributions are added to the quotient polynomial.
+    /// Gates are reported individually, as [ArgumentType::Gate].
+    /// As the contributions are evaluated concurrently,
+    /// these durations can add up to more than [QuotientTimings::total].
+    pub arguments: Vec<(ArgumentType, Duration)>,
+    /// The time spent interpolating the contributions and dividing them by the vanishing polynomial.
+    pub interpolation: Duration,
+    /// The time spent computing the quotient polynomial.
+    pub total: Duration,
+}
+
+impl QuotientTimings {
+    /// Returns the time spent evaluating the contribution of an argument,
+    /// or `None` if the argument is not used by the circuit.
+    pub fn get(&self, argument: ArgumentType) -> Option<Duration> {
+        self.arguments
+            .iter()
+            .find(|(arg, _)| *arg == argument)
+            .map(|(_, duration)| *duration)
+    }
+}
+
+impl fmt::Display for QuotientTimings {
+    /// Lists the arguments from the slowest to the fastest.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut arguments = self.arguments.clone();
+        arguments.sort_by(|(_, x), (_, y)| y.cmp(x));
+        for (argument, duration) in arguments {
+            writeln!(f, "{:?}: {:?}", argument, duration)?;
+        }
+        writeln!(f, "interpolation: {:?}", self.interpolation)?;
+        write!(f, "total: {:?}", self.total)
+    }
+}
+
+/// The contribution of an argument to the quotient polynomial.
+pub(crate) enum Contribution<F: FftField> {
+    /// The generic gate.
+    Generic,
+    /// The permutation argument.
+    Permutation,
+    /// Constraints already combined with their (symbolic) powers of alpha.
+    Constraints(CompiledExpr<F>),
+}
+
+/// The evaluations over the larger domains of the index polynomials read by a contribution,
+/// computed for it in the [MemoryMode::Bounded](crate::prover_index::MemoryMode::Bounded) mode,
+/// and dropped once the contribution is added.
+struct IndexColumns<F: FftField> {
+    /// The coefficient polynomials, if any of them is read.
+    coefficients: Option<Vec<Evaluations<F, D<F>>>>,
+    /// The selector polynomials that are read.
+    selectors: Vec<(GateType, Evaluations<F, D<F>>)>,
+}
+
+impl<F: FftField + SquareRootField> IndexColumns<F> {
+    /// Evaluates the index polynomials read by `program`.
+    fn compute(cs: &ConstraintSystem<F>, program: &CompiledExpr<F>, env: &Environment<F>) -> Self {
+        let mut coefficients = false;
+        let mut selectors: Vec<(GateType, Evaluations<F, D<F>>)> = vec![];
+        for col in program.columns() {
+            match col {
+                Column::Coefficient(_) => coefficients = true,
+                Column::Index(gate_type) if selectors.iter().all(|(g, _)| *g != gate_type) => {
+                    let evals = cs.selector_evaluations(gate_type, env.column_domain(&col));
+                    selectors.push((gate_type, evals));
+                }
+                _ => (),
+            }
+        }
+
+        let coefficients = coefficients.then(|| {
+            (0..cs.width.coefficients())
+                .map(|i| cs.coefficient_evaluations(i, env.column_domain(&Column::Coefficient(i))))
+                .collect()
+        });
+        IndexColumns {
+            coefficients,
+            selectors,
+        }
+    }
+
+    /// Returns `env`, with the index polynomials replaced by the ones computed for the contribution.
+    fn environment<'a>(&'a self, env: &Environment<'a, F>) -> Environment<'a, F> {
+        Environment {
+            witness: env.witness,
+            coefficient: self.coefficients.as_deref().unwrap_or(env.coefficient),
+            vanishes_on_last_4_rows: env.vanishes_on_last_4_rows,
+            z: env.z,
+            index: self
+                .selectors
+                .iter()
+                .map(|(g, evals)| (*g, evals))
+                .collect(),
+            l0_1: env.l0_1,
+            constants: env.constants.clone(),
+            domain: env.domain,
+            lookup: env.lookup.clone(),
+        }
+    }
+}
+
+/// Buffers of evaluations, reused by the proofs created with the same [ProverContext]
+/// instead of being allocated by each of them.
+pub(crate) struct BufferPool<F>(Mutex<Vec<Vec<F>>>);
+
+impl<F> Default for BufferPool<F> {
+    fn default() -> Self {
+        BufferPool(Mutex::new(vec![]))
+    }
+}
+
+impl<F: FftField> BufferPool<F> {
+    /// Returns a buffer of zeros over `domain`, reusing one of the same size if there is one.
+    fn take(&self, domain: D<F>) -> Evaluations<F, D<F>> {
+        let reused = {
+            let mut buffers = self.0.lock().unwrap();
+            buffers
+                .iter()
+                .position(|b| b.len() == domain.size())
+                .map(|i| buffers.swap_remove(i))
+        };
+        let evals = match reused {
+            Some(mut evals) => {
+                evals.par_iter_mut().for_each(|x| *x = F::zero());
+                evals
+            }
+            None => vec![F::zero(); domain.size()],
+        };
+        Evaluations::from_vec_and_domain(evals, domain)
+    }
+
+    /// Returns a buffer to the pool.
+    fn give(&self, evals: Evaluations<F, D<F>>) {
+        self.0.lock().unwrap().push(evals.evals);
+    }
+}
+
+/// The contributions accumulated by a thread.
+struct Accumulator<'a, F: FftField> {
+    /// The pool from which the buffers are taken, and to which they are returned.
+    pool: &'a BufferPool<F>,
+    /// The sum of the evaluations over each domain.
+    buffers: Vec<Evaluations<F, D<F>>>,
+    /// The part of the permutation argument that is already divided by the vanishing polynomial.
+    bnd: Option<DensePolynomial<F>>,
+    timings: Vec<(ArgumentType, Duration)>,
+}
+
+impl<'a, F: FftField> Accumulator<'a, F> {
+    fn new(pool: &'a BufferPool<F>) -> Self {
+        Accumulator {
+            pool,
+            buffers: vec![],
+            bnd: None,
+            timings: vec![],
+        }
+    }
+
+    /// Returns the buffer accumulating the evaluations over `domain`,
+    /// taking it from the pool the first time it is needed.
+    fn buffer(&mut self, domain: D<F>) -> &mut Evaluations<F, D<F>> {
+        match self.buffers.iter().position(|b| b.domain() == domain) {
+            Some(i) => &mut self.buffers[i],
+            None => {
+                self.buffers.push(self.pool.take(domain));
+                self.buffers.last_mut().unwrap()
+            }
+        }
+    }
+
+    fn add(&mut self, evals: &Evaluations<F, D<F>>) {
+        *self.buffer(evals.domain()) += evals;
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for evals in other.buffers {
+            match self
+                .buffers
+                .iter_mut()
+                .find(|b| b.domain() == evals.domain())
+            {
+                Some(buffer) => {
+                    *buffer += &evals;
+                    self.pool.give(evals);
+                }
+                None => self.buffers.push(evals),
+            }
+        }
+        self.bnd = self.bnd.or(other.bnd);
+        self.timings.extend(other.timings);
+        self
+    }
+}
+
+/// Panics if the contribution of an argument is not divisible by the vanishing polynomial.
+/// This is only checked in debug builds.
+fn check_contribution<F: FftField>(
+    argument: ArgumentType,
+    evals: &Evaluations<F, D<F>>,
+    domains: &EvaluationDomains<F>,
+) {
+    if cfg!(debug_assertions) {
+        let (_, res) = evals
+            .interpolate_by_ref()
+            .divide_by_vanishing_poly(domains.d1)
+            .unwrap();
+        if !res.is_zero() {
+            panic!("couldn't divide by vanishing polynomial: {:?}", argument);
+        }
+    }
+}
+
+/// Returns the contributions of the arguments used by the circuit,
+/// in the order in which they are added to the quotient polynomial.
+/// The constraints are combined with the powers of alpha of `powers_of_alpha` (which does not need to be instantiated)
+/// as symbolic constants, so that they can be compiled once and evaluated for any $\alpha$.
+pub(crate) fn contributions<G: CommitmentCurve, PCS>(
+    index: &ProverIndex<G, PCS>,
+    powers_of_alpha: &Alphas<ScalarField<G>>,
+) -> Vec<(ArgumentType, Contribution<ScalarField<G>>)> {
+    let mut contributions = vec![
+        (ArgumentType::Gate(GateType::Generic), Contribution::Generic),
+        (ArgumentType::Permutation, Contribution::Permutation),
+    ];
+
+    // the other gates
+    type Constraints<F> = fn(&Alphas<F>) -> E<F>;
+    let gates: [(GateType, Constraints<ScalarField<G>>); 9] = [
+        (GateType::CompleteAdd, CompleteAdd::combined_constraints),
+        (GateType::VarBaseMul, VarbaseMul::combined_constraints),
+        (GateType::EndoMul, EndosclMul::combined_constraints),
+        (GateType::EndoMulScalar, EndomulScalar::combined_constraints),
+        (GateType::Poseidon, Poseidon::combined_constraints),
+        (GateType::ChaCha0, ChaCha0::combined_constraints),
+        (GateType::ChaCha1, ChaCha1::combined_constraints),
+        (GateType::ChaCha2, ChaCha2::combined_constraints),
+        (GateType::ChaChaFinal, ChaChaFinal::combined_constraints),
+    ];
+    for (gate_type, combined_constraints) in gates {
+        if index.gate_types.contains(&gate_type) {
+            let constraints = combined_constraints(powers_of_alpha);
+            contributions.push((
+                ArgumentType::Gate(gate_type),
+                Contribution::Constraints(CompiledExpr::compile_expr(&constraints)),
+            ));
+        }
+    }
+
+    // lookup
+    if let Some(lcs) = index.cs.lookup_constraint_system.as_ref() {
+        let lookup_alphas =
+            powers_of_alpha.get_exponents(ArgumentType::Lookup, lookup::constraints::CONSTRAINTS);
+        let constraints = lookup::constraints::constraints(
+            &lcs.configuration,
+            index.cs.domain.d1,
+            index.cs.zk_rows,
+        );
+
+        for (constraint, alpha_pow) in constraints.into_iter().zip_eq(lookup_alphas) {
+            let constraint = constraint * Expr::Constant(ConstantExpr::Alpha.pow(alpha_pow as u64));
+            contributions.push((
+                ArgumentType::Lookup,
+                Contribution::Constraints(CompiledExpr::compile_expr(&constraint)),
+            ));
+        }
+    }
+
+    contributions
+}
+
+/// Computes the quotient polynomial $t$, such that $f = Z_H \cdot t$,
+/// where $f$ is the sum of the contributions of all the arguments and of the public polynomial.
+/// Also returns the time spent on each argument.
+///
+/// The evaluations of the index polynomials over the larger domains are read from `column_evaluations`
+/// (and from `env`, which must then hold them too), or, if it is `None`,
+/// computed for each contribution that needs them, and dropped once the contribution is added
+/// (see [MemoryMode::Bounded](crate::prover_index::MemoryMode::Bounded)).
+/// The contributions and the buffers are the ones of `context`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn quotient_poly<G: CommitmentCurve, PCS>(
+    index: &ProverIndex<G, PCS>,
+    context: &ProverContext<ScalarField<G>>,
+    all_alphas: &Alphas<ScalarField<G>>,
+    env: &Environment<ScalarField<G>>,
+    column_evaluations: Option<&ColumnEvaluations<ScalarField<G>>>,
+    lagrange: &WitnessOverDomains<ScalarField<G>>,
+    witness_poly: &[DensePolynomial<ScalarField<G>>],
+    z_poly: &DensePolynomial<ScalarField<G>>,
+    public_poly: &DensePolynomial<ScalarField<G>>,
+) -> Result<(DensePolynomial<ScalarField<G>>, QuotientTimings), ProverError> {
+    let start = Instant::now();
+    let domains = &index.cs.domain;
+
+    let contributions = &context.contributions;
+    let arguments: Vec<_> = contributions
+        .iter()
+        .map(|(argument, _)| *argument)
+        .dedup()
+        .collect();
+
+    // each thread evaluates a share of the contributions in its own buffers
+    let threads = rayon::current_num_threads();
+    let share = (contributions.len() + threads - 1) / threads;
+    let new_accumulator = || Accumulator::new(&context.buffers);
+    let acc = contributions
+        .par_iter()
+        .with_min_len(share)
+        .try_fold(new_accumulator, |mut acc, (argument, contribution)| {
+            let argument = *argument;
+            let start = Instant::now();
+            match contribution {
+                Contribution::Generic => {
+                    let alphas = all_alphas
+                        .get_alphas(ArgumentType::Gate(GateType::Generic), generic::CONSTRAINTS);
+                    let (coefficients8, generic4);
+                    let (coefficients8, generic4) = match column_evaluations {
+                        Some(c) => (&c.coefficients8[..], &c.generic4),
+                        None => {
+                            coefficients8 = (0..2 * generic::GENERIC_COEFFS)
+                                .map(|i| index.cs.coefficient_evaluations(i, domains.d8))
+                                .collect::<Vec<_>>();
+                            generic4 = index.cs.selector_evaluations(GateType::Generic, domains.d4);
+                            (&coefficients8[..], &generic4)
+                        }
+                    };
+                    let evals =
+                        index
+                            .cs
+                            .gnrc_quot(alphas, &lagrange.d4.this.w, coefficients8, generic4);
+
+                    if cfg!(debug_assertions) {
+                        let p4 = public_poly.evaluate_over_domain_by_ref(domains.d4);
+                        check_contribution(argument, &(&evals + &p4), domains);
+                    }
+
+                    acc.add(&evals);
+                }
+                Contribution::Permutation => {
+                    let alphas =
+                        all_alphas.get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS);
+                    let sigmal8;
+                    let sigmal8 = match column_evaluations {
+                        Some(c) => &c.sigmal8,
+                        None => {
+                            sigmal8 = index.cs.sigma_evaluations(index.cs.permutation_domain());
+                            &sigmal8
+                        }
+                    };
+                    let (perm, bnd) = index.cs.perm_quot(
+                        lagrange,
+                        witness_poly,
+                        env.constants.beta,
+                        env.constants.gamma,
+                        z_poly,
+                        alphas,
+                        sigmal8,
+                    )?;
+                    check_contribution(argument, &perm, domains);
+
+                    acc.add(&perm);
+                    acc.bnd = Some(bnd);
+                }
+                Contribution::Constraints(program) => {
+                    let columns;
+                    let local_env;
+                    let env = match column_evaluations {
+                        Some(_) => env,
+                        None => {
+                            columns = IndexColumns::compute(&index.cs, program, env);
+                            local_env = columns.environment(env);
+                            &local_env
+                        }
+                    };
+                    program.add_evaluations_to(env, acc.buffer(program.domain(env)));
+
+                    if cfg!(debug_assertions) {
+                        check_contribution(argument, &program.evaluations(env), domains);
+                    }
+                }
+            }
+            acc.timings.push((argument, start.elapsed()));
+            Ok::<_, ProverError>(acc)
+        })
+        .try_reduce(new_accumulator, |x, y| Ok(x.merge(y)))?;
+
+    let timings = arguments
+        .into_iter()
+        .map(|argument| {
+            let duration = acc
+                .timings
+                .iter()
+                .filter(|(arg, _)| *arg == argument)
+                .map(|(_, duration)| *duration)
+                .sum();
+            (argument, duration)
+        })
+        .collect();
+
+    // public polynomial
+    let interpolation = Instant::now();
+    let mut f = public_poly.clone();
+    for evals in acc.buffers {
+        f += &evals.interpolate_by_ref();
+        context.buffers.give(evals);
+    }
+
+    // divide contributions with vanishing polynomial
+    let (mut quotient, res) = f
+        .divide_by_vanishing_poly(domains.d1)
+        .ok_or(ProverError::Prover("division by vanishing polynomial"))?;
+    if !res.is_zero() {
+        return Err(ProverError::Prover(
+            "rest of division by vanishing polynomial",
+        ));
+    }
+
+    // already divided by Z_H
+    quotient += &acc
+        .bnd
+        .expect("the permutation argument is always part of the quotient");
+
+    let timings = QuotientTimings {
+        arguments: timings,
+        interpolation: interpolation.elapsed(),
+        total: start.elapsed(),
+    };
+    Ok((quotient, timings))
+}
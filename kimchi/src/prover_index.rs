@@ -2,39 +2,74 @@
 
 use crate::alphas::Alphas;
 use crate::circuits::{
-    constraints::ConstraintSystem,
+    constraints::{ColumnEvaluations, ConstraintSystem, ZkMode},
     expr::{Linearization, PolishToken},
+    gate::GateType,
     wires::*,
 };
-use crate::linearization::expr_linearization;
-use ark_ff::PrimeField;
-use commitment_dlog::{commitment::CommitmentCurve, srs::SRS};
+use crate::error::SetupError;
+use crate::linearization::{
+    arguments_constraints, check_constraints_degree, check_constraints_width, check_linearization,
+    expr_linearization,
+};
+use ark_ff::{PrimeField, Zero};
+use commitment_dlog::{
+    commitment::{CommitmentCurve, PolyComm},
+    srs::SRS,
+    PolynomialCommitment,
+};
 use o1_utils::types::fields::*;
 use oracle::poseidon::ArithmeticSpongeParams;
+use rand_core::{CryptoRng, RngCore};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 
-/// The index used by the prover
+/// How the prover holds the evaluations of the index polynomials over the larger domains
+/// (see [ColumnEvaluations]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemoryMode {
+    /// The evaluations are computed once, and kept in the constraint system for all the proofs.
+    Full,
+    /// The evaluations are recomputed from the gates for each proof, argument by argument,
+    /// and each argument frees the ones it reads once its contribution to the quotient polynomial is computed.
+    /// Only the values of the index polynomials on the rows are kept for the whole proof.
+    /// This trades a few FFTs per proof for an index whose size does not depend on the larger domains.
+    Bounded,
+}
+
+impl Default for MemoryMode {
+    fn default() -> Self {
+        MemoryMode::Full
+    }
+}
+
+/// The index used by the prover, which commits to the polynomials with the polynomial commitment scheme `PCS`
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug)]
 //~spec:startcode
-pub struct ProverIndex<G: CommitmentCurve> {
+pub struct ProverIndex<G: CommitmentCurve, PCS = SRS<G>> {
     /// constraints system polynomials
     #[serde(bound = "ConstraintSystem<ScalarField<G>>: Serialize + DeserializeOwned")]
     pub cs: ConstraintSystem<ScalarField<G>>,
 
+    /// The set of gate types used in the circuit
+    pub gate_types: BTreeSet<GateType>,
+
     /// The symbolic linearization of our circuit, which can compile to concrete types once certain values are learned in the protocol.
-    #[serde(skip)]
+    #[serde(
+        bound = "Linearization<Vec<PolishToken<ScalarField<G>>>>: Serialize + DeserializeOwned"
+    )]
     pub linearization: Linearization<Vec<PolishToken<ScalarField<G>>>>,
 
     /// The mapping between powers of alpha and constraints
-    #[serde(skip)]
+    #[serde(bound = "Alphas<ScalarField<G>>: Serialize + DeserializeOwned")]
     pub powers_of_alpha: Alphas<ScalarField<G>>,
 
     /// polynomial commitment keys
     #[serde(skip)]
-    pub srs: Arc<SRS<G>>,
+    pub srs: Arc<PCS>,
 
     /// maximal size of polynomial section
     pub max_poly_size: usize,
@@ -45,10 +80,14 @@ pub struct ProverIndex<G: CommitmentCurve> {
     /// random oracle argument parameters
     #[serde(skip)]
     pub fq_sponge_params: ArithmeticSpongeParams<BaseField<G>>,
+
+    /// how the evaluations of the index polynomials are kept in memory
+    #[serde(default)]
+    pub memory_mode: MemoryMode,
 }
 //~spec:endcode
 
-impl<'a, G: CommitmentCurve> ProverIndex<G>
+impl<'a, G: CommitmentCurve, PCS: PolynomialCommitment<G>> ProverIndex<G, PCS>
 where
     G::BaseField: PrimeField,
 {
@@ -57,68 +96,183 @@ where
         mut cs: ConstraintSystem<ScalarField<G>>,
         fq_sponge_params: ArithmeticSpongeParams<BaseField<G>>,
         endo_q: ScalarField<G>,
-        srs: Arc<SRS<G>>,
-    ) -> Self {
-        let max_poly_size = srs.g.len();
-        if cs.public > 0 {
-            assert!(
-                max_poly_size >= cs.domain.d1.size as usize,
-                "polynomial segment size has to be not smaller that that of the circuit!"
-            );
+        srs: Arc<PCS>,
+    ) -> Result<Self, SetupError> {
+        // the proofs can only be zero-knowledge if the commitments are blinded
+        if cs.zk_mode == ZkMode::ZeroKnowledge && !srs.is_hiding() {
+            return Err(SetupError::NonHidingCommitments);
         }
+
+        // polynomials that do not fit in the SRS are committed to and evaluated in chunks
+        let max_poly_size = srs.max_poly_size();
         cs.endo = endo_q;
 
-        // pre-compute the linearization
+        // set `max_quot_size` to the degree of the quotient polynomial,
+        // which is obtained by looking at the highest monomial in the product
+        // $$\prod_{i=0}^{p-1} (w_i(x) + \beta k_i x + \gamma)$$
+        // over the $p$ permuted columns, where the $w_i(x)$ are of degree the size of the domain
+        // (the constraints of the gates fit in `PERMUTS` chunks).
+        let max_quot_size = cs.width.quotient_chunks() * cs.domain.d1.size as usize;
+
+        // check that the constraints of the circuit fit in the quotient polynomial
+        let gate_types = cs.gate_types();
+        let lookup_configuration = cs
+            .lookup_constraint_system
+            .as_ref()
+            .map(|lcs| &lcs.configuration);
+        let (arguments, _) =
+            arguments_constraints(cs.domain.d1, cs.zk_rows, &gate_types, lookup_configuration);
+        check_constraints_degree(cs.domain.d1.size, max_quot_size, &arguments)?;
+        check_constraints_width(cs.width, &arguments)?;
+
+        // pre-compute the linearization, specialized to the gates used in the circuit
         let (linearization, powers_of_alpha) = expr_linearization(
             cs.domain.d1,
-            cs.chacha8.is_some(),
-            cs.lookup_constraint_system
-                .as_ref()
-                .map(|lcs| &lcs.configuration),
+            cs.zk_rows,
+            cs.width,
+            &gate_types,
+            lookup_configuration,
         );
 
-        // set `max_quot_size` to the degree of the quotient polynomial,
-        // which is obtained by looking at the highest monomial in the sum
-        // $$\sum_{i=0}^{PERMUTS} (w_i(x) + \beta k_i x + \gamma)$$
-        // where the $w_i(x)$ are of degree the size of the domain.
-        let max_quot_size = PERMUTS * cs.domain.d1.size as usize;
-
-        ProverIndex {
+        Ok(ProverIndex {
             cs,
+            gate_types,
             linearization,
             powers_of_alpha,
             srs,
             max_poly_size,
             max_quot_size,
             fq_sponge_params,
+            memory_mode: MemoryMode::default(),
+        })
+    }
+
+    /// Sets how the evaluations of the index polynomials are kept in memory.
+    /// Switching to [MemoryMode::Bounded] frees the evaluations kept so far.
+    pub fn set_memory_mode(&mut self, memory_mode: MemoryMode) {
+        self.memory_mode = memory_mode;
+        if memory_mode == MemoryMode::Bounded {
+            self.cs.clear_column_evaluations();
+        }
+    }
+
+    /// Blinds a commitment with random factors, unless the constraint system is in [ZkMode::NonHiding],
+    /// in which case the commitment is left as is, with zero blinding factors.
+    pub fn mask(
+        &self,
+        comm: PolyComm<G>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PolyComm<G>, PolyComm<ScalarField<G>>) {
+        match self.cs.zk_mode {
+            ZkMode::ZeroKnowledge => self.srs.mask(comm, rng),
+            ZkMode::NonHiding => {
+                let blinders = comm.map(|_| ScalarField::<G>::zero());
+                (comm, blinders)
+            }
         }
     }
+
+    /// Returns the evaluations of the index polynomials over the larger domains kept in the constraint system,
+    /// or `None` in the [MemoryMode::Bounded] mode,
+    /// in which the prover evaluates them argument by argument instead.
+    pub fn column_evaluations(&self) -> Option<&Arc<ColumnEvaluations<ScalarField<G>>>> {
+        match self.memory_mode {
+            MemoryMode::Full => Some(self.cs.column_evaluations()),
+            MemoryMode::Bounded => None,
+        }
+    }
+
+    /// Returns evaluations of the index polynomials from which their values on the rows can be read:
+    /// the ones over the larger domains if they are kept,
+    /// and otherwise only the values on the rows (see [ConstraintSystem::row_evaluations]).
+    pub(crate) fn row_evaluations(&self) -> Arc<ColumnEvaluations<ScalarField<G>>> {
+        self.column_evaluations()
+            .cloned()
+            .unwrap_or_else(|| Arc::new(self.cs.row_evaluations()))
+    }
+
+    /// Checks that the linearization and the powers of alpha of the index,
+    /// which might have been deserialized, match the ones computed from the constraint system.
+    pub fn check_linearization(&self) -> Result<(), SetupError> {
+        let lookup_configuration = self
+            .cs
+            .lookup_constraint_system
+            .as_ref()
+            .map(|lcs| &lcs.configuration);
+        check_linearization(
+            self.cs.domain.d1,
+            self.cs.zk_rows,
+            self.cs.width,
+            &self.gate_types,
+            lookup_configuration,
+            &self.linearization,
+            &self.powers_of_alpha,
+        )
+    }
 }
 
 pub mod testing {
     use super::*;
-    use crate::circuits::{gate::CircuitGate, lookup::tables::LookupTable};
+    use crate::circuits::{
+        constraints::DomainConfiguration, gate::CircuitGate, lookup::tables::LookupTable,
+    };
     use commitment_dlog::srs::endos;
     use mina_curves::pasta::{pallas::Affine as Other, vesta::Affine, Fp};
 
-    pub fn new_index_for_test_with_lookups(
+    pub fn new_index_for_test_with_configuration(
+        gates: Vec<CircuitGate<Fp>>,
+        public: usize,
+        lookup_tables: Vec<LookupTable<Fp>>,
+        configuration: DomainConfiguration,
+    ) -> ProverIndex<Affine> {
+        new_index_for_test_with_srs_size(gates, public, lookup_tables, configuration, None)
+    }
+
+    /// Creates an index with an SRS of the given size (by default, the size of the domain).
+    /// If the SRS is smaller than the domain, the polynomials of the circuit are chunked.
+    pub fn new_index_for_test_with_srs_size(
         gates: Vec<CircuitGate<Fp>>,
         public: usize,
         lookup_tables: Vec<LookupTable<Fp>>,
+        configuration: DomainConfiguration,
+        srs_size: Option<usize>,
     ) -> ProverIndex<Affine> {
         let fp_sponge_params = oracle::pasta::fp_kimchi::params();
 
         // not sure if theres a smarter way instead of the double unwrap, but should be fine in the test
-        let cs =
-            ConstraintSystem::<Fp>::create(gates, lookup_tables, fp_sponge_params, public).unwrap();
-        let mut srs = SRS::<Affine>::create(cs.domain.d1.size as usize);
-        srs.add_lagrange_basis(cs.domain.d1);
+        let cs = ConstraintSystem::<Fp>::create_with_configuration(
+            gates,
+            lookup_tables,
+            fp_sponge_params,
+            public,
+            None,
+            configuration,
+        )
+        .unwrap();
+        let d1_size = cs.domain.d1.size as usize;
+        let mut srs = SRS::<Affine>::create(srs_size.unwrap_or(d1_size));
+        if srs.g.len() >= d1_size {
+            srs.add_lagrange_basis(cs.domain.d1);
+        }
         let srs = Arc::new(srs);
 
         let fq_sponge_params = oracle::pasta::fq_kimchi::params();
         let (endo_q, _endo_r) = endos::<Other>();
-        ProverIndex::<Affine>::create(cs, fq_sponge_params, endo_q, srs)
+        ProverIndex::<Affine>::create(cs, fq_sponge_params, endo_q, srs).unwrap()
     }
+    pub fn new_index_for_test_with_lookups(
+        gates: Vec<CircuitGate<Fp>>,
+        public: usize,
+        lookup_tables: Vec<LookupTable<Fp>>,
+    ) -> ProverIndex<Affine> {
+        new_index_for_test_with_configuration(
+            gates,
+            public,
+            lookup_tables,
+            DomainConfiguration::default(),
+        )
+    }
+
     pub fn new_index_for_test(gates: Vec<CircuitGate<Fp>>, public: usize) -> ProverIndex<Affine> {
         new_index_for_test_with_lookups(gates, public, vec![])
     }
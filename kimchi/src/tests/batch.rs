@@ -0,0 +1,162 @@
+use crate::circuits::gate::CircuitGate;
+use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
+use crate::circuits::wires::{Wire, COLUMNS};
+use crate::proof::ProverProof;
+use crate::prover_index::{testing::new_index_for_test, MemoryMode, ProverIndex};
+use crate::verifier::{batch_verify, decide, par_batch_verify, verify, verify_succinct};
+use ark_ff::Zero;
+use array_init::array_init;
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+/// Creates a generic circuit padded with `padding` zero gates, and a witness for it.
+fn circuit(padding: usize) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let mut gates = create_circuit(0, 0);
+    let start = gates.len();
+    gates.extend((start..start + padding).map(|row| CircuitGate::zero(Wire::new(row))));
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+
+    (gates, witness)
+}
+
+/// Creates a generic circuit padded with `padding` zero gates, and a valid proof for it.
+fn index_and_proof(padding: usize) -> (ProverIndex<Affine>, ProverProof<Affine>) {
+    let (gates, witness) = circuit(padding);
+
+    let index = new_index_for_test(gates, 0);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
+
+    (index, proof)
+}
+
+#[test]
+fn test_batch_verify_different_srs_lengths() {
+    let (small_index, small_proof) = index_and_proof(0);
+    let (large_index, large_proof) = index_and_proof(200);
+    assert!(small_index.srs.g.len() < large_index.srs.g.len());
+
+    let small_verifier_index = small_index.verifier_index();
+    let large_verifier_index = large_index.verifier_index();
+
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let batch = vec![
+        (&small_verifier_index, &small_proof),
+        (&large_verifier_index, &large_proof),
+        (&small_verifier_index, &small_proof),
+    ];
+    batch_verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &batch).unwrap();
+    par_batch_verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &batch).unwrap();
+
+    // a proof that does not verify is still detected
+    let mut bad_proof = small_proof;
+    bad_proof.proof.z1 += Fp::from(1u32);
+    let batch = vec![
+        (&small_verifier_index, &bad_proof),
+        (&large_verifier_index, &large_proof),
+    ];
+    assert!(batch_verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &batch).is_err());
+    assert!(par_batch_verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &batch).is_err());
+}
+
+#[test]
+fn test_deferred_accumulation() {
+    let (small_index, small_proof) = index_and_proof(0);
+    let (large_index, large_proof) = index_and_proof(200);
+    let small_verifier_index = small_index.verifier_index();
+    let large_verifier_index = large_index.verifier_index();
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // succinct checks
+    let small_acc = verify_succinct::<Affine, BaseSponge, ScalarSponge>(
+        &group_map,
+        &small_verifier_index,
+        &small_proof,
+    )
+    .unwrap();
+    let large_acc = verify_succinct::<Affine, BaseSponge, ScalarSponge>(
+        &group_map,
+        &large_verifier_index,
+        &large_proof,
+    )
+    .unwrap();
+
+    // a proof that does not verify is detected by the succinct check
+    let mut bad_proof = small_proof;
+    bad_proof.proof.z1 += Fp::from(1u32);
+    assert!(verify_succinct::<Affine, BaseSponge, ScalarSponge>(
+        &group_map,
+        &small_verifier_index,
+        &bad_proof,
+    )
+    .is_err());
+
+    // decide the accumulators at once
+    let accumulators = vec![small_acc.clone(), large_acc.clone()];
+    decide(&large_index.srs, &accumulators).unwrap();
+
+    // a wrong sg is only detected when deciding
+    let mut bad_acc = small_acc;
+    bad_acc.sg = large_acc.sg;
+    assert!(decide(&large_index.srs, &[bad_acc, large_acc]).is_err());
+
+    // the accumulators can be deferred to a recursive proof instead,
+    // unless they do not fit in the SRS of the recursive proof
+    assert!(accumulators[1]
+        .to_prev_challenges(&small_index.srs)
+        .is_none());
+    let (gates, witness) = circuit(200);
+    let index = new_index_for_test(gates, 0);
+    let prev_challenges = accumulators
+        .iter()
+        .map(|acc| acc.to_prev_challenges(&index.srs).unwrap())
+        .collect();
+    let proof = ProverProof::create_recursive::<BaseSponge, ScalarSponge>(
+        &group_map,
+        witness.into(),
+        &index,
+        prev_challenges,
+    )
+    .unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &index.verifier_index(), &proof)
+        .unwrap();
+}
+
+#[test]
+fn test_create_batch() {
+    let (gates, witness) = circuit(0);
+    let mut index = new_index_for_test(gates, 0);
+    let verifier_index = index.verifier_index();
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // the proofs share the evaluations kept in the index, or the values on the rows computed for the batch
+    for memory_mode in [MemoryMode::Full, MemoryMode::Bounded] {
+        index.set_memory_mode(memory_mode);
+        let witnesses = vec![witness.to_vec(); 4];
+        let proofs =
+            ProverProof::create_batch::<BaseSponge, ScalarSponge>(&group_map, witnesses, &index)
+                .unwrap();
+        assert_eq!(proofs.len(), 4);
+
+        let batch: Vec<_> = proofs
+            .iter()
+            .map(|proof| (&verifier_index, proof))
+            .collect();
+        batch_verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &batch).unwrap();
+    }
+}
@@ -88,8 +88,8 @@ fn chacha_prover() {
     let group_map = <Affine as CommitmentCurve>::Map::setup();
 
     let start = Instant::now();
-    let proof =
-        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &index).unwrap();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
     println!("{}{:?}", "Prover time: ".yellow(), start.elapsed());
 
     let start = Instant::now();
@@ -97,7 +97,7 @@ fn chacha_prover() {
     println!("{}{:?}", "Verifier index time: ".yellow(), start.elapsed());
 
     let start = Instant::now();
-    match verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof) {
+    match verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof) {
         Err(error) => panic!("Failure verifying the prover's proofs in batch: {}", error),
         Ok(_) => {
             println!("{}{:?}", "Verifier time: ".yellow(), start.elapsed());
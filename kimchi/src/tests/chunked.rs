@@ -0,0 +1,167 @@
+use crate::circuits::constraints::DomainConfiguration;
+use crate::circuits::gate::CircuitGate;
+use crate::circuits::lookup::tables::LookupTable;
+use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
+use crate::circuits::polynomials::{chacha, poseidon, poseidon::ROUNDS_PER_ROW};
+use crate::circuits::wires::{Wire, COLUMNS, PERMUTS};
+use crate::proof::ProverProof;
+use crate::prover_index::testing::new_index_for_test_with_srs_size;
+use crate::tests::lookup::lookup_circuit;
+use crate::verifier::verify;
+use crate::verifier_index::VerifierIndex;
+use ark_ff::{One, Zero};
+use array_init::array_init;
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::{PlonkSpongeConstantsKimchi, SpongeConstants},
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use std::iter;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+/// Creates an index with a domain of size `domain_size`, `chunks` times the size of the SRS,
+/// and checks that the proof of the witness verifies and is made of chunks.
+fn prove_chunked(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: &[Vec<Fp>; COLUMNS],
+    public: &[Fp],
+    lookup_tables: Vec<LookupTable<Fp>>,
+    domain_size: usize,
+    chunks: usize,
+) -> (ProverProof<Affine>, VerifierIndex<Affine>) {
+    let configuration = DomainConfiguration {
+        min_domain_size: Some(domain_size),
+        ..DomainConfiguration::default()
+    };
+    let index = new_index_for_test_with_srs_size(
+        gates,
+        public.len(),
+        lookup_tables,
+        configuration,
+        Some(domain_size / chunks),
+    );
+    assert_eq!(index.cs.domain.d1.size as usize, domain_size);
+    assert_eq!(index.max_poly_size, domain_size / chunks);
+    let verifier_index = index.verifier_index();
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.to_vec(), &index)
+            .unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+
+    // the polynomials are committed to and evaluated in chunks
+    assert!(proof
+        .commitments
+        .w_comm
+        .iter()
+        .all(|c| c.unshifted.len() == chunks));
+    assert_eq!(proof.commitments.t_comm.unshifted.len(), PERMUTS * chunks);
+    assert!(proof
+        .evals
+        .iter()
+        .all(|e| e.z.len() == chunks && e.w.iter().all(|w| w.len() == chunks)));
+
+    // and so are the ones of the lookup argument
+    if let Some(lookup) = &proof.commitments.lookup {
+        assert!(lookup
+            .sorted
+            .iter()
+            .chain(iter::once(&lookup.aggreg))
+            .all(|c| c.unshifted.len() == chunks));
+        assert!(proof.evals.iter().all(|e| {
+            let lookup = e.lookup.as_ref().unwrap();
+            lookup
+                .sorted
+                .iter()
+                .chain(iter::once(&lookup.aggreg))
+                .all(|evals| evals.len() == chunks)
+        }));
+    }
+
+    (proof, verifier_index)
+}
+
+#[test]
+fn test_chunked_generic_gate_pub() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // the domain is several times the size of the SRS
+    for chunks in [2, 4] {
+        let (proof, verifier_index) =
+            prove_chunked(gates.clone(), &witness, &public, vec![], 1 << 8, chunks);
+
+        // the proof is bound to the public input
+        let mut other = proof.clone();
+        other.public[0] += Fp::one();
+        assert!(
+            verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &other)
+                .is_err()
+        );
+    }
+}
+
+#[test]
+fn test_chunked_poseidon() {
+    let rows_per_hash = PlonkSpongeConstantsKimchi::PERM_ROUNDS_FULL / ROUNDS_PER_ROW;
+    let round_constants = oracle::pasta::fp_kimchi::params().round_constants;
+    let (gates, _) = CircuitGate::<Fp>::create_poseidon_gadget(
+        0,
+        [Wire::new(0), Wire::new(rows_per_hash)],
+        &round_constants,
+    );
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); rows_per_hash + 1]);
+    let input = [Fp::from(1u32), Fp::from(2u32), Fp::from(3u32)];
+    poseidon::generate_witness(0, oracle::pasta::fp_kimchi::params(), &mut witness, input);
+
+    // the round constants are read from the coefficient columns
+    prove_chunked(gates, &witness, &[], vec![], 1 << 8, 4);
+}
+
+#[test]
+fn test_chunked_lookup_gates() {
+    // the tables are combined with their table ids
+    let (gates, witness, lookup_tables) = lookup_circuit(true, 500, vec![100, 50, 2]);
+    prove_chunked(gates, &witness, &[], lookup_tables, 1 << 9, 2);
+}
+
+#[test]
+fn test_chunked_chacha() {
+    let s0: Vec<u32> = vec![
+        0x61707865, 0x3320646e, 0x79622d32, 0x6b206574, 0x03020100, 0x07060504, 0x0b0a0908,
+        0x0f0e0d0c, 0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c, 0x00000001, 0x09000000,
+        0x4a000000, 0x00000000,
+    ];
+    let gates: Vec<_> = chacha::testing::chacha20_gates()
+        .into_iter()
+        .enumerate()
+        .map(|(i, typ)| CircuitGate {
+            typ,
+            coeffs: vec![],
+            wires: Wire::new(i),
+        })
+        .collect();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![]);
+    for row in chacha::testing::chacha20_rows::<Fp>(s0) {
+        for (col, x) in row.into_iter().enumerate() {
+            witness[col].push(x);
+        }
+    }
+
+    // the gates look their nybbles up in the XOR table
+    prove_chunked(gates, &witness, &[], vec![], 1 << 10, 2);
+}
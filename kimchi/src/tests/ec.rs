@@ -2,6 +2,7 @@ use crate::circuits::{
     gate::{CircuitGate, GateType},
     wires::*,
 };
+use crate::prover_index::MemoryMode;
 use ark_ec::{AffineCurve, ProjectiveCurve};
 use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
 use array_init::array_init;
@@ -140,5 +141,8 @@ fn ec_test() {
         witness[14].push(F::zero());
     }
 
-    TestFramework::run_test(gates, witness, &[]);
+    TestFramework::run_test(gates.clone(), witness.clone(), &[]);
+
+    // the selector of the gate is evaluated over d4 only
+    TestFramework::run_test_with_memory_mode(gates, witness, &[], MemoryMode::Bounded);
 }
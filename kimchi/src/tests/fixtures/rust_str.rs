@@ -0,0 +1,14 @@
+#[allow(unused_variables, unused_imports, unused_parens)]
+pub fn constraint<F: ark_ff::PrimeField>(
+    d: ark_poly::Radix2EvaluationDomain<F>,
+    pt: F,
+    evals: &[kimchi::proof::ProofEvaluations<F>],
+    c: &kimchi::circuits::expr::Constants<F>,
+) -> Result<F, &'static str> {
+    use ark_ff::Field;
+    use ark_poly::EvaluationDomain;
+
+    let x_0 = (evals[0].w[0] + c.beta);
+    let x_1 = { let x_0 = (evals[0].w[0] + c.beta); ((x_0 * evals[1].lookup.as_ref().ok_or("Lookup should not have been used")?.sorted[2]) - c.joint_combiner.ok_or("no joint lookup was expected")?.pow([3u64])) };
+    Ok(((((((((x_1 * x_0.double()) + (x_1.square() * evals[1].z)) + (evals[0].lookup.as_ref().ok_or("Lookup should not have been used")?.aggreg * evals[1].lookup.as_ref().ok_or("Lookup should not have been used")?.table)) - (evals[0].poseidon_selector * evals[1].generic_selector)) + (c.alpha * c.gamma)) + (c.endo_coefficient * c.mds[1][2])) + ((d.evaluate_vanishing_polynomial(pt) / (pt - d.group_gen.pow([3u64]))) * kimchi::circuits::polynomials::permutation::eval_vanishes_on_last_4_rows(d, c.zk_rows, pt))) - (F::from_le_bytes_mod_order(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0]) * (-F::from(5u64)))))
+}
@@ -3,7 +3,10 @@
 use crate::circuits::lookup::tables::LookupTable;
 use crate::circuits::{gate::CircuitGate, wires::COLUMNS};
 use crate::proof::ProverProof;
-use crate::prover_index::testing::{new_index_for_test, new_index_for_test_with_lookups};
+use crate::prover_index::{
+    testing::{new_index_for_test, new_index_for_test_with_lookups},
+    MemoryMode,
+};
 use crate::verifier::verify;
 use ark_ff::UniformRand;
 use ark_poly::univariate::DensePolynomial;
@@ -37,10 +40,21 @@ impl TestFramework {
         gates: Vec<CircuitGate<Fp>>,
         witness: [Vec<Fp>; COLUMNS],
         public: &[Fp],
+    ) {
+        Self::run_test_with_memory_mode(gates, witness, public, MemoryMode::Full)
+    }
+
+    /// Create and verify a proof, with the prover index in the given memory mode
+    pub(crate) fn run_test_with_memory_mode(
+        gates: Vec<CircuitGate<Fp>>,
+        witness: [Vec<Fp>; COLUMNS],
+        public: &[Fp],
+        memory_mode: MemoryMode,
     ) {
         // create the index
         let start = Instant::now();
-        let index = new_index_for_test(gates, public.len());
+        let mut index = new_index_for_test(gates, public.len());
+        index.set_memory_mode(memory_mode);
         let verifier_index = index.verifier_index();
         println!("- time to create index: {:?}s", start.elapsed().as_secs());
 
@@ -51,12 +65,18 @@ impl TestFramework {
         let start = Instant::now();
         let group_map = <Affine as CommitmentCurve>::Map::setup();
         let proof =
-            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &index).unwrap();
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+                .unwrap();
         println!("- time to create proof: {:?}s", start.elapsed().as_secs());
 
+        // in the bounded memory mode, the evaluations over the larger domains are not kept
+        if memory_mode == MemoryMode::Bounded {
+            assert!(!index.cs.has_column_evaluations());
+        }
+
         // verify the proof
         let start = Instant::now();
-        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+        verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
         println!("- time to verify: {}ms", start.elapsed().as_millis());
     }
 
@@ -93,7 +113,7 @@ impl TestFramework {
         let group_map = <Affine as CommitmentCurve>::Map::setup();
         let proof = ProverProof::create_recursive::<BaseSponge, ScalarSponge>(
             &group_map,
-            witness,
+            witness.into(),
             &index,
             vec![prev_challenges],
         )
@@ -102,7 +122,7 @@ impl TestFramework {
 
         // verify the proof
         let start = Instant::now();
-        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+        verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
         println!("- time to verify: {}ms", start.elapsed().as_millis());
     }
 
@@ -126,12 +146,13 @@ impl TestFramework {
         let start = Instant::now();
         let group_map = <Affine as CommitmentCurve>::Map::setup();
         let proof =
-            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &index).unwrap();
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+                .unwrap();
         println!("- time to create proof: {:?}s", start.elapsed().as_secs());
 
         // verify the proof
         let start = Instant::now();
-        verify::<Affine, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof).unwrap();
+        verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
         println!("- time to verify: {}ms", start.elapsed().as_millis());
     }
 }
@@ -1,9 +1,36 @@
 use super::framework::TestFramework;
+use crate::circuits::argument::ArgumentType;
+use crate::circuits::constraints::{ConstraintSystem, DomainConfiguration, ZkMode};
+use crate::circuits::expr::Column;
+use crate::circuits::gate::GateType;
 use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
-use crate::circuits::wires::COLUMNS;
+use crate::circuits::wires::{Wire, COLUMNS};
+use crate::error::{ProverError, SetupError};
+use crate::proof::ProverProof;
+use crate::prover::{ProverHooks, ProverRound};
+use crate::prover_index::{
+    testing::{new_index_for_test, new_index_for_test_with_configuration},
+    MemoryMode,
+};
+use crate::verifier::verify;
 use ark_ff::Zero;
+use ark_poly::{Evaluations, Radix2EvaluationDomain as D};
 use array_init::array_init;
-use mina_curves::pasta::fp::Fp;
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use std::{ops::ControlFlow, time::Duration};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
 
 #[test]
 fn test_generic_gate() {
@@ -29,3 +56,353 @@ fn test_generic_gate_pub() {
     // create and verify proof based on the witness
     TestFramework::run_test(gates, witness, &public);
 }
+
+#[test]
+fn test_generic_gate_specialized_linearization() {
+    let gates = create_circuit(0, 0);
+    let index = new_index_for_test(gates, 0);
+    assert!(index.gate_types.iter().all(|typ| *typ == GateType::Generic));
+
+    // no selector of an unused gate appears in the linearization
+    assert!(index
+        .linearization
+        .index_terms
+        .iter()
+        .all(|(col, _)| !matches!(col, Column::Index(_))));
+
+    // the verifier uses the same gate set
+    assert_eq!(index.verifier_index().gate_types, index.gate_types);
+}
+
+#[test]
+fn test_quotient_timings() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+
+    let index = new_index_for_test(gates, 0);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let (proof, timings) = ProverProof::create_recursive_with_timings::<BaseSponge, ScalarSponge>(
+        &group_map,
+        witness.into(),
+        &index,
+        vec![],
+    )
+    .unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &index.verifier_index(), &proof)
+        .unwrap();
+
+    // only the arguments used by the circuit are timed
+    let arguments: Vec<_> = timings.arguments.iter().map(|(arg, _)| *arg).collect();
+    assert_eq!(
+        arguments,
+        vec![
+            ArgumentType::Gate(GateType::Generic),
+            ArgumentType::Permutation
+        ]
+    );
+    assert!(timings
+        .get(ArgumentType::Gate(GateType::Poseidon))
+        .is_none());
+    assert!(timings.interpolation <= timings.total);
+    assert!(timings
+        .to_string()
+        .ends_with(&format!("total: {:?}", timings.total)));
+}
+
+/// Records the rounds of the prover, and cancels the proof at a given round.
+struct Rounds {
+    rounds: Vec<ProverRound>,
+    cancel_at: Option<ProverRound>,
+}
+
+impl ProverHooks for Rounds {
+    fn round(&mut self, round: ProverRound, _elapsed: Duration) -> ControlFlow<()> {
+        self.rounds.push(round);
+        if self.cancel_at == Some(round) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+#[test]
+fn test_prover_hooks() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+
+    let index = new_index_for_test(gates, 0);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // all the rounds are reported, in order
+    let mut hooks = Rounds {
+        rounds: vec![],
+        cancel_at: None,
+    };
+    let proof = ProverProof::create_with_hooks::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness.to_vec(),
+        &index,
+        &mut hooks,
+    )
+    .unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &index.verifier_index(), &proof)
+        .unwrap();
+    assert_eq!(
+        hooks.rounds,
+        vec![
+            ProverRound::WitnessCommitment,
+            ProverRound::Permutation,
+            ProverRound::Quotient,
+            ProverRound::Evaluations,
+            ProverRound::Opening
+        ]
+    );
+
+    // the hooks can cancel the proof
+    let mut hooks = Rounds {
+        rounds: vec![],
+        cancel_at: Some(ProverRound::Permutation),
+    };
+    let res = ProverProof::create_with_hooks::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness.into(),
+        &index,
+        &mut hooks,
+    );
+    assert!(matches!(
+        res,
+        Err(ProverError::Cancelled(ProverRound::Permutation))
+    ));
+    assert_eq!(
+        hooks.rounds,
+        vec![ProverRound::WitnessCommitment, ProverRound::Permutation]
+    );
+}
+
+#[test]
+fn test_bounded_memory_mode() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+
+    let mut index = new_index_for_test(gates, 0);
+    let verifier_index = index.verifier_index();
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // the values kept on the rows are the ones of the evaluations over the larger domains
+    {
+        let full = index.cs.column_evaluations();
+        let rows = index.cs.row_evaluations();
+        let d1_size = index.cs.domain.d1.size as usize;
+        let on_rows = |evals: &Evaluations<Fp, D<Fp>>| {
+            let step = evals.evals.len() / d1_size;
+            evals
+                .evals
+                .iter()
+                .step_by(step)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        for (full, rows) in full.coefficients8.iter().zip(&rows.coefficients8) {
+            assert_eq!(on_rows(full), rows.evals);
+        }
+        for (full, rows) in full.sigmal8.iter().zip(&rows.sigmal8) {
+            assert_eq!(on_rows(full), rows.evals);
+        }
+        assert_eq!(on_rows(&full.generic4), rows.generic4.evals);
+    }
+
+    // switching to the bounded memory mode frees the evaluations kept in the constraint system
+    index.set_memory_mode(MemoryMode::Bounded);
+    assert!(!index.cs.has_column_evaluations());
+    assert!(index.column_evaluations().is_none());
+
+    // and they are not computed again for the whole constraint system by the prover
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
+    assert!(!index.cs.has_column_evaluations());
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+
+    // nor by the verifier index
+    let other_verifier_index = index.verifier_index();
+    assert!(!index.cs.has_column_evaluations());
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &other_verifier_index, &proof)
+        .unwrap();
+}
+
+#[test]
+fn test_non_hiding_mode() {
+    // 12 public inputs and 20 generic gates fill a domain of 32 rows,
+    // which would have to be doubled to reserve the zero-knowledge rows
+    let public = vec![Fp::from(1u8); 12];
+    let mut gates = create_circuit(0, public.len());
+    assert_eq!(gates.len(), 32);
+
+    // wire the first constant to the one on the last row of the domain
+    let last = gates.len() - 1;
+    let first_const = last - 9;
+    gates[first_const].wires[0] = Wire { row: last, col: 0 };
+    gates[last].wires[0] = Wire {
+        row: first_const,
+        col: 0,
+    };
+
+    let zk_index = new_index_for_test(gates.clone(), public.len());
+    assert_eq!(zk_index.cs.domain.d1.size, 64);
+
+    let configuration = DomainConfiguration {
+        zk_mode: ZkMode::NonHiding,
+        ..DomainConfiguration::default()
+    };
+    let index = new_index_for_test_with_configuration(gates, public.len(), vec![], configuration);
+    assert_eq!(index.cs.domain.d1.size, 32);
+    assert_eq!(index.cs.zk_rows, 0);
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.zk_mode, ZkMode::NonHiding);
+    assert_eq!(verifier_index.zk_rows, 0);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); 32]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.to_vec(), &index)
+            .unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+
+    // neither the witness nor the permutation are randomized, and the commitments are not blinded
+    let other = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
+    assert!(proof
+        .commitments
+        .w_comm
+        .iter()
+        .zip(other.commitments.w_comm.iter())
+        .all(|(c1, c2)| c1.unshifted == c2.unshifted));
+    assert_eq!(
+        proof.commitments.z_comm.unshifted,
+        other.commitments.z_comm.unshifted
+    );
+}
+
+#[test]
+fn test_non_hiding_mode_almost_full_domain() {
+    // 11 public inputs and 20 generic gates only leave the last row of a domain of 32 rows free,
+    // where the zero-knowledge mode needs `zk_rows` of them
+    let public = vec![Fp::from(1u8); 11];
+    let gates = create_circuit(0, public.len());
+    assert_eq!(gates.len(), 31);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // the zero-knowledge prover rejects a witness that fills all but the last row of the domain
+    let zk_index = new_index_for_test(gates.clone(), public.len());
+    let zk_size = zk_index.cs.domain.d1.size as usize;
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); zk_size - 1]);
+    fill_in_witness(0, &mut witness, &public);
+    assert!(matches!(
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &zk_index),
+        Err(ProverError::NoRoomForZkInWitness)
+    ));
+
+    // while the non-hiding prover accepts it
+    let configuration = DomainConfiguration {
+        zk_mode: ZkMode::NonHiding,
+        ..DomainConfiguration::default()
+    };
+    let index = new_index_for_test_with_configuration(gates, public.len(), vec![], configuration);
+    assert_eq!(index.cs.domain.d1.size, 32);
+    let verifier_index = index.verifier_index();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); 31]);
+    fill_in_witness(0, &mut witness, &public);
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+}
+
+#[test]
+fn test_domain_configuration() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    for zk_rows in [1, 5] {
+        let configuration = DomainConfiguration {
+            zk_rows,
+            min_domain_size: Some(1 << 6),
+            ..DomainConfiguration::default()
+        };
+        let index = new_index_for_test_with_configuration(gates.clone(), 0, vec![], configuration);
+        assert_eq!(index.cs.domain.d1.size, 1 << 6);
+        let verifier_index = index.verifier_index();
+        assert_eq!(verifier_index.zk_rows, zk_rows);
+
+        let proof =
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.to_vec(), &index)
+                .unwrap();
+        verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+    }
+
+    // the permutation constraint must fit in the quotient
+    for zk_rows in [0, 8] {
+        let configuration = DomainConfiguration {
+            zk_rows,
+            min_domain_size: None,
+            ..DomainConfiguration::default()
+        };
+        let cs = ConstraintSystem::create_with_configuration(
+            gates.clone(),
+            vec![],
+            oracle::pasta::fp_kimchi::params(),
+            0,
+            None,
+            configuration,
+        );
+        assert!(matches!(cs, Err(SetupError::ZkRows(rows, 7)) if rows == zk_rows));
+    }
+
+    // the shared precomputations must match the domain and the zero-knowledge rows
+    let index = new_index_for_test_with_configuration(
+        gates.clone(),
+        0,
+        vec![],
+        DomainConfiguration {
+            zk_rows: 5,
+            min_domain_size: Some(1 << 6),
+            ..DomainConfiguration::default()
+        },
+    );
+    let precomputations = index.cs.precomputations();
+    for (configuration, mismatch) in [
+        (
+            DomainConfiguration {
+                zk_rows: 3,
+                min_domain_size: Some(1 << 6),
+                ..DomainConfiguration::default()
+            },
+            "number of zero-knowledge rows",
+        ),
+        (
+            DomainConfiguration {
+                zk_rows: 5,
+                min_domain_size: Some(1 << 7),
+                ..DomainConfiguration::default()
+            },
+            "domain",
+        ),
+    ] {
+        let cs = ConstraintSystem::create_with_configuration(
+            gates.clone(),
+            vec![],
+            oracle::pasta::fp_kimchi::params(),
+            0,
+            Some(precomputations.clone()),
+            configuration,
+        );
+        assert!(matches!(cs, Err(SetupError::Precomputations(m)) if m == mismatch));
+    }
+}
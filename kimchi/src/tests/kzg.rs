@@ -0,0 +1,113 @@
+use crate::circuits::constraints::{ConstraintSystem, DomainConfiguration, ZkMode};
+use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
+use crate::circuits::wires::COLUMNS;
+use crate::error::SetupError;
+use crate::proof::ProverProof;
+use crate::prover_index::ProverIndex;
+use crate::verifier::verify;
+use ark_bn254::{Bn254, Fr, G1Affine};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use array_init::array_init;
+use commitment_dlog::{commitment::CommitmentCurve, kzg::KzgSrs};
+use groupmap::GroupMap;
+use oracle::{
+    constants::{PlonkSpongeConstantsKimchi, SpongeConstants},
+    poseidon::ArithmeticSpongeParams,
+    sponge::{endo_coefficient, DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::sync::Arc;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<ark_bn254::g1::Parameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fr, SpongeParams>;
+
+/// There are no Poseidon parameters for the fields of BN254:
+/// these ones have the right shape, with a Cauchy matrix as MDS matrix and random round constants,
+/// but they are only meant to run the Fiat-Shamir argument in tests.
+fn sponge_params_for_test<F: PrimeField>(rng: &mut StdRng) -> ArithmeticSpongeParams<F> {
+    let width = SpongeParams::SPONGE_WIDTH;
+    let mds = (0..width)
+        .map(|i| {
+            (0..width)
+                .map(|j| F::from((i + width + j) as u64).inverse().unwrap())
+                .collect()
+        })
+        .collect();
+    let round_constants = (0..SpongeParams::PERM_ROUNDS_FULL)
+        .map(|_| (0..width).map(|_| F::rand(rng)).collect())
+        .collect();
+    ArithmeticSpongeParams {
+        round_constants,
+        mds,
+    }
+}
+
+#[test]
+fn test_kzg_proof() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+
+    let public = vec![Fr::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fr>; COLUMNS] = array_init(|_| vec![Fr::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    // the KZG commitments do not hide the polynomials, so the proofs cannot be zero-knowledge
+    let configuration = DomainConfiguration {
+        zk_mode: ZkMode::NonHiding,
+        ..DomainConfiguration::default()
+    };
+    let cs = ConstraintSystem::<Fr>::create_with_configuration(
+        gates,
+        vec![],
+        sponge_params_for_test(rng),
+        public.len(),
+        None,
+        configuration,
+    )
+    .unwrap();
+    let srs = KzgSrs::<Bn254>::create_insecure(cs.domain.d1.size as usize, rng);
+
+    // the circuit has no endomorphism gate, the coefficient is only needed to create the index
+    let index = ProverIndex::<G1Affine, KzgSrs<Bn254>>::create(
+        cs,
+        sponge_params_for_test(rng),
+        endo_coefficient(),
+        Arc::new(srs),
+    )
+    .unwrap();
+    let verifier_index = index.verifier_index();
+    index.cs.verify(&witness, &public).unwrap();
+
+    let group_map = <G1Affine as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
+    verify::<G1Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+
+    // a proof for other public inputs is rejected
+    let mut other = proof;
+    other.public[0] += Fr::from(1u8);
+    assert!(
+        verify::<G1Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &other)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_kzg_zero_knowledge() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+
+    let gates = create_circuit(0, 0);
+    let cs = ConstraintSystem::<Fr>::create(gates, vec![], sponge_params_for_test(rng), 0).unwrap();
+    assert_eq!(cs.zk_mode, ZkMode::ZeroKnowledge);
+    let srs = KzgSrs::<Bn254>::create_insecure(cs.domain.d1.size as usize, rng);
+
+    // the zero-knowledge mode is rejected, as the commitments would not be blinded
+    let index = ProverIndex::<G1Affine, KzgSrs<Bn254>>::create(
+        cs,
+        sponge_params_for_test(rng),
+        endo_coefficient(),
+        Arc::new(srs),
+    );
+    assert!(matches!(index, Err(SetupError::NonHidingCommitments)));
+}
@@ -1,14 +1,41 @@
 use super::framework::TestFramework;
 use crate::circuits::{
+    constraints::{DomainConfiguration, ZkMode},
     gate::{CircuitGate, GateType},
     lookup::tables::LookupTable,
-    wires::Wire,
+    wires::{Wire, COLUMNS},
 };
+use crate::proof::ProverProof;
+use crate::prover_index::testing::new_index_for_test_with_configuration;
+use crate::verifier::verify;
 use ark_ff::Zero;
 use array_init::array_init;
-use mina_curves::pasta::fp::Fp;
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
 
-fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_sizes: Vec<usize>) {
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+/// Creates a circuit of `num_lookups` lookup gates in random tables of the given sizes,
+/// along with its witness and its tables.
+pub(crate) fn lookup_circuit(
+    use_values_from_table: bool,
+    num_lookups: usize,
+    table_sizes: Vec<usize>,
+) -> (
+    Vec<CircuitGate<Fp>>,
+    [Vec<Fp>; COLUMNS],
+    Vec<LookupTable<Fp>>,
+) {
     let lookup_table_values: Vec<Vec<_>> = table_sizes
         .iter()
         .map(|size| (0..*size).map(|_| rand::random()).collect())
@@ -86,6 +113,12 @@ fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_siz
         ]
     };
 
+    (gates, witness, lookup_tables)
+}
+
+fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_sizes: Vec<usize>) {
+    let (gates, witness, lookup_tables) =
+        lookup_circuit(use_values_from_table, num_lookups, table_sizes);
     TestFramework::run_test_lookups(gates, witness, &[], lookup_tables);
 }
 
@@ -110,3 +143,51 @@ fn lookup_gate_proving_works_multiple_tables() {
 fn lookup_gate_rejects_bad_lookups_multiple_tables() {
     setup_lookup_proof(false, 500, vec![100, 50, 50, 2, 2])
 }
+
+#[test]
+fn lookup_gate_proving_works_with_zk_rows() {
+    let (gates, witness, lookup_tables) = lookup_circuit(true, 500, vec![100, 50]);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    for zk_rows in [1, 5] {
+        let configuration = DomainConfiguration {
+            zk_rows,
+            min_domain_size: None,
+            ..DomainConfiguration::default()
+        };
+        let index = new_index_for_test_with_configuration(
+            gates.clone(),
+            0,
+            lookup_tables.clone(),
+            configuration,
+        );
+        assert_eq!(index.cs.zk_rows, zk_rows);
+        let verifier_index = index.verifier_index();
+
+        let proof =
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.to_vec(), &index)
+                .unwrap();
+        verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+    }
+}
+
+#[test]
+fn lookup_gate_proving_works_without_zk_rows() {
+    // 511 lookup gates only leave the last row of the domain free,
+    // where the zero-knowledge mode would need 4 of them
+    let (gates, witness, lookup_tables) = lookup_circuit(true, 511, vec![100, 50]);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    let configuration = DomainConfiguration {
+        zk_mode: ZkMode::NonHiding,
+        ..DomainConfiguration::default()
+    };
+    let index = new_index_for_test_with_configuration(gates, 0, lookup_tables, configuration);
+    assert_eq!(index.cs.domain.d1.size, 512);
+    assert_eq!(index.cs.zk_rows, 0);
+    let verifier_index = index.verifier_index();
+
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+}
@@ -1,11 +1,17 @@
+mod batch;
 mod chacha;
+mod chunked;
 mod ec;
 mod endomul;
 mod endomul_scalar;
 mod framework;
 mod generic;
+mod kzg;
 mod lookup;
 mod poseidon;
+mod proof_structure;
 mod recursion;
+mod serialization;
 mod turshi;
 mod varbasemul;
+mod width;
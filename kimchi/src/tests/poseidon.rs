@@ -4,6 +4,7 @@ use crate::circuits::{
     polynomials::poseidon::ROUNDS_PER_ROW,
     wires::{Wire, COLUMNS},
 };
+use crate::prover_index::MemoryMode;
 use crate::tests::framework::TestFramework;
 use ark_ff::Zero;
 use array_init::array_init;
@@ -72,5 +73,9 @@ fn test_poseidon() {
         );
     }
 
-    TestFramework::run_test(gates, witness, &[]);
+    TestFramework::run_test(gates.clone(), witness.clone(), &[]);
+
+    // the round constants are read from the coefficient columns,
+    // which are then evaluated for the poseidon gate only
+    TestFramework::run_test_with_memory_mode(gates, witness, &[], MemoryMode::Bounded);
 }
@@ -0,0 +1,108 @@
+use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
+use crate::circuits::wires::COLUMNS;
+use crate::error::VerifyError;
+use crate::proof::ProverProof;
+use crate::prover_index::testing::new_index_for_test;
+use ark_ff::Zero;
+use array_init::array_init;
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+#[test]
+fn test_validate_structure() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let index = new_index_for_test(gates, public.len());
+    let verifier_index = index.verifier_index();
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
+
+    // an honest proof is well-formed
+    proof.validate_structure(&verifier_index).unwrap();
+
+    // wrong number of public inputs
+    let mut bad = proof.clone();
+    bad.public.pop();
+    assert!(matches!(
+        bad.validate_structure(&verifier_index),
+        Err(VerifyError::IncorrectPublicInputLength(4, 5))
+    ));
+
+    // missing chunk in the quotient commitment
+    let mut bad = proof.clone();
+    bad.commitments.t_comm.unshifted.pop();
+    assert!(matches!(
+        bad.validate_structure(&verifier_index),
+        Err(VerifyError::IncorrectCommitmentChunks("t", _, _))
+    ));
+
+    // point that is not on the curve
+    let mut bad = proof.clone();
+    let (x, y) = bad.commitments.z_comm.unshifted[0]
+        .to_coordinates()
+        .unwrap();
+    bad.commitments.z_comm.unshifted[0] = Affine::of_coordinates(x, y + y);
+    assert!(matches!(
+        bad.validate_structure(&verifier_index),
+        Err(VerifyError::PointNotOnCurve("z"))
+    ));
+
+    // missing round in the opening proof
+    let mut bad = proof.clone();
+    bad.proof.lr.pop();
+    assert!(matches!(
+        bad.validate_structure(&verifier_index),
+        Err(VerifyError::IncorrectOpeningProofRounds(_, _))
+    ));
+
+    // identity in the opening proof
+    let mut bad = proof.clone();
+    bad.proof.delta = Affine::zero();
+    assert!(matches!(
+        bad.validate_structure(&verifier_index),
+        Err(VerifyError::PointAtInfinity("delta"))
+    ));
+
+    // inconsistent evaluations
+    let mut bad = proof.clone();
+    bad.evals[1].w[0].chunks.push(Fp::zero());
+    assert!(matches!(
+        bad.validate_structure(&verifier_index),
+        Err(VerifyError::IncorrectEvaluationsLength("w"))
+    ));
+
+    // evaluations with fewer chunks than the commitment, even if consistent with each other
+    let mut bad = proof.clone();
+    bad.evals[0].z.chunks.clear();
+    bad.evals[1].z.chunks.clear();
+    assert!(matches!(
+        bad.validate_structure(&verifier_index),
+        Err(VerifyError::IncorrectEvaluationsLength("z"))
+    ));
+
+    let mut bad = proof;
+    for evals in &mut bad.evals {
+        evals.generic_selector.chunks.push(Fp::zero());
+    }
+    assert!(matches!(
+        bad.validate_structure(&verifier_index),
+        Err(VerifyError::IncorrectEvaluationsLength("generic selector"))
+    ));
+}
@@ -0,0 +1,163 @@
+use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
+use crate::circuits::{expr::PolishToken, wires::COLUMNS};
+use crate::error::SetupError;
+use crate::proof::ProverProof;
+use crate::prover_index::testing::new_index_for_test;
+use crate::verifier::verify;
+use crate::verifier_index::VerifierIndex;
+use ark_ff::Zero;
+use ark_serialize::CanonicalSerialize;
+use array_init::array_init;
+use commitment_dlog::{commitment::CommitmentCurve, evaluation_proof::OpeningProof};
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use serde::Serialize;
+use serde_with::{serde_as, Bytes, SerializeAs};
+use std::fs::File;
+use std::sync::Arc;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+#[test]
+fn test_serialized_linearization() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+
+    let index = new_index_for_test(gates, 0);
+    index.check_linearization().unwrap();
+    let verifier_index = index.verifier_index();
+
+    // write the verifier index to a file, and read it back
+    let path = std::env::temp_dir().join("kimchi_test_serialized_linearization.bin");
+    File::create(&path).unwrap();
+    verifier_index.to_file(&path, Some(true)).unwrap();
+    let loaded = VerifierIndex::<Affine>::from_file(
+        Arc::clone(&index.srs),
+        &path,
+        None,
+        verifier_index.endo,
+        oracle::pasta::fq_kimchi::params(),
+        oracle::pasta::fp_kimchi::params(),
+        None,
+    )
+    .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // the linearization is persisted along with the index
+    assert_eq!(loaded.linearization, verifier_index.linearization);
+    assert_eq!(loaded.powers_of_alpha, verifier_index.powers_of_alpha);
+    loaded.check_linearization(None).unwrap();
+
+    // and the loaded index can verify proofs
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &loaded, &proof).unwrap();
+
+    // a tampered linearization is detected
+    let mut tampered = loaded;
+    tampered
+        .linearization
+        .constant_term
+        .extend([PolishToken::Literal(Fp::from(1u32)), PolishToken::Add]);
+    assert!(matches!(
+        tampered.check_linearization(None),
+        Err(SetupError::InconsistentIndex("linearization"))
+    ));
+
+    // and rejected when the index is read back from a file
+    File::create(&path).unwrap();
+    tampered.to_file(&path, Some(true)).unwrap();
+    let loaded = VerifierIndex::<Affine>::from_file(
+        Arc::clone(&index.srs),
+        &path,
+        None,
+        verifier_index.endo,
+        oracle::pasta::fq_kimchi::params(),
+        oracle::pasta::fp_kimchi::params(),
+        None,
+    );
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        loaded.err(),
+        Some(SetupError::InconsistentIndex("linearization").to_string())
+    );
+}
+
+#[test]
+fn test_proof_serialization() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+
+    let index = new_index_for_test(gates, 0);
+    let verifier_index = index.verifier_index();
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
+
+    // the proof can be serialized, and the deserialized proof verifies
+    let bytes = rmp_serde::to_vec(&proof).unwrap();
+    let loaded: ProverProof<Affine> = rmp_serde::from_slice(&bytes).unwrap();
+    assert_eq!(
+        loaded.commitments.w_comm[0].unshifted,
+        proof.commitments.w_comm[0].unshifted
+    );
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &loaded).unwrap();
+
+    // the points of the proof are compressed
+    struct Uncompressed;
+    impl SerializeAs<Affine> for Uncompressed {
+        fn serialize_as<S: serde::Serializer>(
+            p: &Affine,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let mut bytes = vec![];
+            p.serialize_uncompressed(&mut bytes)
+                .map_err(serde::ser::Error::custom)?;
+            Bytes::serialize_as(&bytes, serializer)
+        }
+    }
+    #[serde_as]
+    #[derive(Serialize)]
+    struct UncompressedOpeningProof {
+        #[serde_as(as = "Vec<(Uncompressed, Uncompressed)>")]
+        lr: Vec<(Affine, Affine)>,
+        #[serde_as(as = "Uncompressed")]
+        delta: Affine,
+        #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+        z1: Fp,
+        #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+        z2: Fp,
+        #[serde_as(as = "Uncompressed")]
+        sg: Affine,
+    }
+    let OpeningProof {
+        lr,
+        delta,
+        z1,
+        z2,
+        sg,
+    } = proof.proof.clone();
+    let points = 2 * lr.len() + 2;
+    let uncompressed = rmp_serde::to_vec(&UncompressedOpeningProof {
+        lr,
+        delta,
+        z1,
+        z2,
+        sg,
+    })
+    .unwrap();
+    let compressed = rmp_serde::to_vec(&proof.proof).unwrap();
+    assert_eq!(uncompressed.len() - compressed.len(), points * 32);
+}
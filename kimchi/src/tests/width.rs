@@ -0,0 +1,219 @@
+use crate::circuits::argument::ArgumentType;
+use crate::circuits::constraints::{ConstraintSystem, DomainConfiguration, GateError};
+use crate::circuits::gate::{CircuitGate, GateType};
+use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
+use crate::circuits::wires::{Width, Wire, COLUMNS, PERMUTS};
+use crate::error::{ProverError, SetupError};
+use crate::proof::ProverProof;
+use crate::prover_index::{testing::new_index_for_test_with_configuration, ProverIndex};
+use crate::verifier::verify;
+use ark_ff::Zero;
+use array_init::array_init;
+use commitment_dlog::{commitment::CommitmentCurve, srs::endos, srs::SRS};
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    fp::Fp,
+    pallas::Affine as Other,
+    vesta::{Affine, VestaParameters},
+};
+use oracle::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use std::sync::Arc;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+fn configuration(columns: usize, permuts: usize) -> DomainConfiguration {
+    DomainConfiguration {
+        width: Width { columns, permuts },
+        ..DomainConfiguration::default()
+    }
+}
+
+fn create_cs(
+    gates: Vec<CircuitGate<Fp>>,
+    configuration: DomainConfiguration,
+) -> Result<ConstraintSystem<Fp>, SetupError> {
+    ConstraintSystem::create_with_configuration(
+        gates,
+        vec![],
+        oracle::pasta::fp_kimchi::params(),
+        0,
+        None,
+        configuration,
+    )
+}
+
+#[test]
+fn test_narrow_circuit() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // the generic gate only uses the first 6 columns, and none of its cells is wired to another one
+    let index = new_index_for_test_with_configuration(
+        gates.clone(),
+        public.len(),
+        vec![],
+        configuration(6, 3),
+    );
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.sigma_comm.len(), 3);
+    // the double generic gate still reads 10 coefficients
+    assert_eq!(verifier_index.coefficients_comm.len(), 10);
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.to_vec(), &index)
+            .unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+
+    // only the columns of the circuit are committed to and evaluated
+    assert_eq!(proof.commitments.w_comm.len(), 6);
+    for evals in &proof.evals {
+        assert_eq!(evals.w.len(), 6);
+        assert_eq!(evals.s.len(), 2);
+    }
+
+    // which makes the proof smaller than the one of the same circuit with the default width
+    let default_index = new_index_for_test_with_configuration(
+        gates,
+        public.len(),
+        vec![],
+        DomainConfiguration::default(),
+    );
+    let default_proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &default_index)
+            .unwrap();
+    assert!(
+        rmp_serde::to_vec(&proof).unwrap().len() < rmp_serde::to_vec(&default_proof).unwrap().len()
+    );
+
+    // a proof for another width is rejected
+    assert!(verify::<Affine, BaseSponge, ScalarSponge, _>(
+        &group_map,
+        &default_index.verifier_index(),
+        &proof
+    )
+    .is_err());
+}
+
+#[test]
+fn test_wide_circuit() {
+    let mut gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // wire the first cell to a column that is not permuted by default
+    gates[0].wires[0] = Wire { row: 1, col: 10 };
+    gates[1].wires[10] = Wire { row: 0, col: 0 };
+    witness[10][1] = witness[0][0];
+
+    assert!(matches!(
+        create_cs(gates.clone(), DomainConfiguration::default()),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+
+    let index =
+        new_index_for_test_with_configuration(gates, 0, vec![], configuration(COLUMNS, COLUMNS));
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.sigma_comm.len(), COLUMNS);
+
+    // the wiring is enforced
+    let mut disconnected = witness.clone();
+    disconnected[10][1] += Fp::from(1u8);
+    assert!(matches!(
+        index.cs.verify(&disconnected, &[]),
+        Err(GateError::DisconnectedWires(..))
+    ));
+
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness.into(), &index)
+        .unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+
+    // the quotient polynomial has one chunk per permuted column
+    let chunks = (index.cs.domain.d1.size as usize + index.max_poly_size - 1) / index.max_poly_size;
+    assert_eq!(proof.commitments.t_comm.unshifted.len(), COLUMNS * chunks);
+    for evals in &proof.evals {
+        assert_eq!(evals.s.len(), COLUMNS - 1);
+    }
+}
+
+#[test]
+fn test_circuit_wider_than_default() {
+    let columns = COLUMNS + 5;
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array_init(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // the columns after the default ones are not read by any gate, nor permuted
+    let mut witness = witness.to_vec();
+    let extra: Vec<Vec<Fp>> = (COLUMNS..columns)
+        .map(|col| vec![Fp::from(col as u64); gates.len()])
+        .collect();
+    witness.extend(extra);
+
+    let index =
+        new_index_for_test_with_configuration(gates, 0, vec![], configuration(columns, PERMUTS));
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.sigma_comm.len(), PERMUTS);
+    assert_eq!(verifier_index.coefficients_comm.len(), columns);
+
+    // the witness must have a column per column of the circuit
+    let short = witness[..columns - 1].to_vec();
+    assert!(matches!(
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, short, &index),
+        Err(ProverError::WitnessCsInconsistent)
+    ));
+
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &index).unwrap();
+    verify::<Affine, BaseSponge, ScalarSponge, _>(&group_map, &verifier_index, &proof).unwrap();
+
+    // all the columns of the circuit are committed to and evaluated
+    assert_eq!(proof.commitments.w_comm.len(), columns);
+    for evals in &proof.evals {
+        assert_eq!(evals.w.len(), columns);
+        assert_eq!(evals.s.len(), PERMUTS - 1);
+    }
+}
+
+#[test]
+fn test_width_errors() {
+    let gates = create_circuit(0, 0);
+
+    // the generic gate needs 6 columns, at least one column must be permuted,
+    // and the wires of a gate only connect the first `COLUMNS` columns
+    for (columns, permuts) in [(5, 3), (COLUMNS + 1, COLUMNS + 1), (6, 0), (6, 7)] {
+        assert!(matches!(
+            create_cs(gates.clone(), configuration(columns, permuts)),
+            Err(SetupError::Width(w)) if w == Width { columns, permuts }
+        ));
+    }
+
+    // the complete addition gate reads more columns than the circuit has
+    let mut gates = gates;
+    let row = gates.len();
+    gates.push(CircuitGate {
+        typ: GateType::CompleteAdd,
+        wires: Wire::new(row),
+        coeffs: vec![],
+    });
+    let cs = create_cs(gates, configuration(6, PERMUTS - 1)).unwrap();
+    let srs = Arc::new(SRS::<Affine>::create(cs.domain.d1.size as usize));
+    let (endo_q, _endo_r) = endos::<Other>();
+    let index = ProverIndex::<Affine>::create(cs, oracle::pasta::fq_kimchi::params(), endo_q, srs);
+    assert!(matches!(
+        index,
+        Err(SetupError::ConstraintColumns(
+            ArgumentType::Gate(GateType::CompleteAdd),
+            _,
+            6
+        ))
+    ));
+}
@@ -10,7 +10,6 @@ use crate::{
         lookup::{lookups::LookupsUsed, tables::combine_table},
         polynomials::{generic, permutation},
         scalars::RandomOracles,
-        wires::*,
     },
     error::VerifyError,
     plonk_sponge::FrSponge,
@@ -18,15 +17,26 @@ use crate::{
     verifier_index::VerifierIndex,
 };
 use ark_ff::{Field, One, PrimeField, Zero};
-use ark_poly::{EvaluationDomain, Polynomial};
-use commitment_dlog::commitment::{
-    b_poly, b_poly_coefficients, combined_inner_product, BatchEvaluationProof, CommitmentCurve,
-    Evaluation, PolyComm,
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Polynomial,
+    Radix2EvaluationDomain as D,
+};
+use commitment_dlog::{
+    commitment::{
+        b_poly, b_poly_coefficients, combined_inner_product, BatchEvaluationProof, CommitmentCurve,
+        Evaluation, PolyComm,
+    },
+    evaluation_proof::Accumulator,
+    srs::SRS,
+    PolynomialCommitment,
 };
 use itertools::izip;
-use o1_utils::types::fields::*;
+use o1_utils::{
+    chunked_evaluations::ChunkedEvaluations, types::fields::*, ExtendedDensePolynomial,
+};
 use oracle::{sponge::ScalarChallenge, FqSponge};
 use rand::thread_rng;
+use rayon::prelude::*;
 
 /// The result of a proof verification.
 pub type Result<T> = std::result::Result<T, VerifyError>;
@@ -60,13 +70,13 @@ where
     pub combined_inner_product: ScalarField<G>,
 }
 
-impl<G: CommitmentCurve> ProverProof<G>
+impl<G: CommitmentCurve, PCS: PolynomialCommitment<G>> ProverProof<G, PCS>
 where
     G::BaseField: PrimeField,
 {
     pub fn prev_chal_evals(
         &self,
-        index: &VerifierIndex<G>,
+        index: &VerifierIndex<G, PCS>,
         evaluation_points: &[ScalarField<G>],
         powers_of_eval_points_for_chunks: &[ScalarField<G>],
     ) -> Vec<Vec<Vec<ScalarField<G>>>> {
@@ -81,7 +91,7 @@ where
                 (0..2)
                     .map(|i| {
                         let full = b_poly(chals, evaluation_points[i]);
-                        if index.max_poly_size == b_len {
+                        if b_len <= index.max_poly_size {
                             return vec![full];
                         }
                         let mut betaacc = ScalarField::<G>::one();
@@ -109,14 +119,136 @@ where
             .collect()
     }
 
+    /// Checks that a (potentially deserialized) proof is well-formed with respect to a [VerifierIndex]:
+    /// commitments have the expected number of chunks and are made of valid curve points,
+    /// evaluations are consistent with the chunking,
+    /// and the public input is of the size expected by the circuit.
+    /// Note that this does not verify the proof.
+    pub fn validate_structure(&self, index: &VerifierIndex<G, PCS>) -> Result<()> {
+        // polynomials of degree less than the domain size are committed in that many chunks
+        let chunks = (index.domain.size() + index.max_poly_size - 1) / index.max_poly_size;
+
+        // public input
+        if self.public.len() != index.public {
+            return Err(VerifyError::IncorrectPublicInputLength(
+                self.public.len(),
+                index.public,
+            ));
+        }
+
+        // commitments, one per witness column of the circuit
+        let comms = &self.commitments;
+        if comms.w_comm.len() != index.width.columns {
+            return Err(VerifyError::IncorrectCommitmentLength("w"));
+        }
+        for w_comm in &comms.w_comm {
+            check_commitment("w", w_comm, chunks)?;
+        }
+        check_commitment("z", &comms.z_comm, chunks)?;
+        check_commitment("t", &comms.t_comm, index.width.quotient_chunks() * chunks)?;
+
+        match (&index.lookup_index, &comms.lookup) {
+            (None, None) => (),
+            (Some(_), None) => return Err(VerifyError::LookupCommitmentMissing),
+            (None, Some(_)) => return Err(VerifyError::ProofInconsistentLookup),
+            (Some(_), Some(lookup)) => {
+                for sorted in &lookup.sorted {
+                    check_commitment("lookup sorted", sorted, chunks)?;
+                }
+                check_commitment("lookup aggregation", &lookup.aggreg, chunks)?;
+            }
+        }
+
+        for (_, comm) in &self.prev_challenges {
+            check_points(
+                "previous challenges",
+                comm.unshifted.iter().chain(&comm.shifted),
+            )?;
+        }
+
+        // opening proof
+        index.srs.check_opening_proof(&self.proof)?;
+
+        // evaluations, one per witness column and one per permuted column but the last one
+        let [e0, e1] = &self.evals;
+        if e0.w.len() != index.width.columns || e1.w.len() != index.width.columns {
+            return Err(VerifyError::IncorrectEvaluationsLength("w"));
+        }
+        if e0.s.len() != index.width.permuts - 1 || e1.s.len() != index.width.permuts - 1 {
+            return Err(VerifyError::IncorrectEvaluationsLength("s"));
+        }
+        // each of them has as many chunks as the commitment of its polynomial
+        for (ev0, ev1) in e0.w.iter().zip(&e1.w) {
+            check_evaluations("w", ev0, ev1, chunks)?;
+        }
+        check_evaluations("z", &e0.z, &e1.z, chunks)?;
+        for ((ev0, ev1), sigma_comm) in e0.s.iter().zip(&e1.s).zip(&index.sigma_comm) {
+            check_evaluations("s", ev0, ev1, sigma_comm.unshifted.len())?;
+        }
+        check_evaluations(
+            "generic selector",
+            &e0.generic_selector,
+            &e1.generic_selector,
+            index.generic_comm.unshifted.len(),
+        )?;
+        check_evaluations(
+            "poseidon selector",
+            &e0.poseidon_selector,
+            &e1.poseidon_selector,
+            index.psm_comm.unshifted.len(),
+        )?;
+
+        match (&index.lookup_index, &e0.lookup, &e1.lookup) {
+            (None, None, None) => (),
+            (Some(li), Some(l0), Some(l1)) => {
+                let sorted_len = comms.lookup.as_ref().map_or(0, |l| l.sorted.len());
+                if l0.sorted.len() != sorted_len || l1.sorted.len() != sorted_len {
+                    return Err(VerifyError::ProofInconsistentLookup);
+                }
+                for (ev0, ev1) in l0.sorted.iter().zip(&l1.sorted) {
+                    check_evaluations("lookup sorted", ev0, ev1, chunks)?;
+                }
+                check_evaluations("lookup aggregation", &l0.aggreg, &l1.aggreg, chunks)?;
+                // the combined table is committed in as many chunks as its longest column
+                let table_chunks = li
+                    .lookup_table
+                    .iter()
+                    .chain(&li.table_ids)
+                    .map(|c| c.unshifted.len())
+                    .max()
+                    .unwrap_or(0);
+                check_evaluations("lookup table", &l0.table, &l1.table, table_chunks)?;
+            }
+            (Some(_), _, _) => return Err(VerifyError::LookupEvalsMissing),
+            (None, _, _) => return Err(VerifyError::ProofInconsistentLookup),
+        }
+
+        Ok(())
+    }
+
     /// This function runs the random oracle argument
     pub fn oracles<
         EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
         EFrSponge: FrSponge<ScalarField<G>>,
     >(
         &self,
-        index: &VerifierIndex<G>,
+        index: &VerifierIndex<G, PCS>,
+        p_comm: &PolyComm<G>,
+    ) -> Result<OraclesResult<G, EFqSponge>> {
+        self.oracles_with_public_polynomial::<EFqSponge, EFrSponge>(index, p_comm, None)
+    }
+
+    /// Runs the random oracle argument, reusing the negated public input polynomial
+    /// if the caller already interpolated it (see [ProverProof::oracles]).
+    /// It is only needed if the domain is larger than `max_poly_size`.
+    fn oracles_with_public_polynomial<
+        EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+        EFrSponge: FrSponge<ScalarField<G>>,
+    >(
+        &self,
+        index: &VerifierIndex<G, PCS>,
         p_comm: &PolyComm<G>,
+        public_poly: Option<&DensePolynomial<ScalarField<G>>>,
     ) -> Result<OraclesResult<G, EFqSponge>> {
         //~
         //~ #### Fiat-Shamir argument
@@ -152,7 +284,7 @@ where
             //~    - Derive the scalar joint combiner challenge $j$ from $j'$ using the endomorphism.
             //~    (TODO: specify endomorphism)
             let joint_combiner = ScalarChallenge(joint_combiner);
-            let joint_combiner = (joint_combiner, joint_combiner.to_field(&index.srs.endo_r));
+            let joint_combiner = (joint_combiner, joint_combiner.to_field(index.srs.endo_r()));
 
             //~    - absorb the commitments to the sorted polynomials.
             self.commitments.lookup.iter().for_each(|l| {
@@ -184,10 +316,13 @@ where
         let alpha_chal = ScalarChallenge(fq_sponge.challenge());
 
         //~ 10. Derive $\alpha$ from $\alpha'$ using the endomorphism (TODO: details).
-        let alpha = alpha_chal.to_field(&index.srs.endo_r);
+        let alpha = alpha_chal.to_field(index.srs.endo_r());
 
-        //~ 11. Enforce that the length of the $t$ commitment is of size `PERMUTS`.
-        if self.commitments.t_comm.unshifted.len() != PERMUTS {
+        //~ 11. Enforce that the length of the $t$ commitment is of size `max(PERMUTS, p)` times
+        //~     the number of chunks of a polynomial of degree less than the domain size,
+        //~     where `p` is the number of permuted columns.
+        let chunks = (index.domain.size() + index.max_poly_size - 1) / index.max_poly_size;
+        if self.commitments.t_comm.unshifted.len() != index.width.quotient_chunks() * chunks {
             return Err(VerifyError::IncorrectCommitmentLength("t"));
         }
 
@@ -198,7 +333,7 @@ where
         let zeta_chal = ScalarChallenge(fq_sponge.challenge());
 
         //~ 14. Derive $\zeta$ from $\zeta'$ using the endomorphism (TODO: specify).
-        let zeta = zeta_chal.to_field(&index.srs.endo_r);
+        let zeta = zeta_chal.to_field(index.srs.endo_r());
 
         //~ 15. Setup the Fr-Sponge.
         let digest = fq_sponge.clone().digest();
@@ -227,8 +362,23 @@ where
         ark_ff::fields::batch_inversion::<ScalarField<G>>(&mut zeta_minus_x);
 
         //~ 17. Evaluate the negated public polynomial (if present) at $\zeta$ and $\zeta\omega$.
-        //~     NOTE: this works only in the case when the poly segment size is not smaller than that of the domain.
-        let p_eval = if !self.public.is_empty() {
+        //~     If the domain is larger than `max_poly_size`, the public polynomial is chunked:
+        //~     interpolate it and chunk evaluate it instead.
+        let p_eval = if index.domain.size() > index.max_poly_size {
+            let interpolated;
+            let public_poly = match public_poly {
+                Some(public_poly) => public_poly,
+                None => {
+                    interpolated = public_polynomial(index, &self.public);
+                    &interpolated
+                }
+            };
+            let public_chunked = public_poly.to_chunked_polynomial(index.max_poly_size);
+            vec![
+                public_chunked.evaluate_chunks(zeta),
+                public_chunked.evaluate_chunks(zetaw),
+            ]
+        } else if !self.public.is_empty() {
             vec![
                 vec![
                     (self
@@ -262,8 +412,8 @@ where
         //~     - z
         //~     - generic selector
         //~     - poseidon selector
-        //~     - the 15 register/witness
-        //~     - 6 sigmas evaluations (the last one is not evaluated)
+        //~     - the registers/witness, one per column of the circuit (15 by default)
+        //~     - the sigmas evaluations, one per permuted column but the last one, which is not evaluated (6 by default)
         for (p, e) in p_eval.iter().zip(&self.evals) {
             fr_sponge.absorb_evaluations(p, e);
         }
@@ -275,13 +425,13 @@ where
         let v_chal = fr_sponge.challenge();
 
         //~ 21. Derive $v$ from $v'$ using the endomorphism (TODO: specify).
-        let v = v_chal.to_field(&index.srs.endo_r);
+        let v = v_chal.to_field(index.srs.endo_r());
 
         //~ 22. Sample $u'$ with the Fr-Sponge.
         let u_chal = fr_sponge.challenge();
 
         //~ 23. Derive $u$ from $u'$ using the endomorphism (TODO: specify).
-        let u = u_chal.to_field(&index.srs.endo_r);
+        let u = u_chal.to_field(index.srs.endo_r());
 
         //~ 24. Create a list of all polynomials that have an evaluation proof.
         let evaluation_points = [zeta, zetaw];
@@ -319,7 +469,7 @@ where
                 .next()
                 .expect("missing power of alpha for permutation");
 
-            let init = (evals[0].w[PERMUTS - 1] + gamma) * evals[1].z * alpha0 * zkp;
+            let init = (evals[0].w[index.width.permuts - 1] + gamma) * evals[1].z * alpha0 * zkp;
             let mut ft_eval0 = evals[0]
                 .w
                 .iter()
@@ -327,11 +477,8 @@ where
                 .map(|(w, s)| (beta * s) + w + gamma)
                 .fold(init, |x, y| x * y);
 
-            ft_eval0 -= if !p_eval[0].is_empty() {
-                p_eval[0][0]
-            } else {
-                ScalarField::<G>::zero()
-            };
+            ft_eval0 -=
+                DensePolynomial::eval_polynomial(&p_eval[0], powers_of_eval_points_for_chunks[0]);
 
             ft_eval0 -= evals[0]
                 .w
@@ -356,6 +503,7 @@ where
                 joint_combiner: joint_combiner.map(|j| j.1),
                 endo_coefficient: index.endo,
                 mds: index.fr_sponge_params.mds.clone(),
+                zk_rows: index.zk_rows,
             };
             ft_eval0 -= PolishToken::evaluate(
                 &index.linearization.constant_term,
@@ -379,30 +527,33 @@ where
             es.push((p_eval.clone(), None));
             es.push((vec![ft_eval0, ft_eval1], None));
             es.push((
-                self.evals.iter().map(|e| e.z.clone()).collect::<Vec<_>>(),
+                self.evals
+                    .iter()
+                    .map(|e| e.z.chunks.clone())
+                    .collect::<Vec<_>>(),
                 None,
             ));
             es.push((
                 self.evals
                     .iter()
-                    .map(|e| e.generic_selector.clone())
+                    .map(|e| e.generic_selector.chunks.clone())
                     .collect::<Vec<_>>(),
                 None,
             ));
             es.push((
                 self.evals
                     .iter()
-                    .map(|e| e.poseidon_selector.clone())
+                    .map(|e| e.poseidon_selector.chunks.clone())
                     .collect::<Vec<_>>(),
                 None,
             ));
             es.extend(
-                (0..COLUMNS)
+                (0..index.width.columns)
                     .map(|c| {
                         (
                             self.evals
                                 .iter()
-                                .map(|e| e.w[c].clone())
+                                .map(|e| e.w[c].chunks.clone())
                                 .collect::<Vec<_>>(),
                             None,
                         )
@@ -410,12 +561,12 @@ where
                     .collect::<Vec<_>>(),
             );
             es.extend(
-                (0..PERMUTS - 1)
+                (0..index.width.permuts - 1)
                     .map(|c| {
                         (
                             self.evals
                                 .iter()
-                                .map(|e| e.s[c].clone())
+                                .map(|e| e.s[c].chunks.clone())
                                 .collect::<Vec<_>>(),
                             None,
                         )
@@ -423,7 +574,7 @@ where
                     .collect::<Vec<_>>(),
             );
 
-            combined_inner_product::<G>(&evaluation_points, &v, &u, &es, index.srs.g.len())
+            combined_inner_product::<G>(&evaluation_points, &v, &u, &es, index.srs.max_poly_size())
         };
 
         let oracles = RandomOracles {
@@ -455,15 +606,75 @@ where
     }
 }
 
-fn to_batch<'a, G, EFqSponge, EFrSponge>(
-    index: &VerifierIndex<G>,
-    proof: &'a ProverProof<G>,
-) -> Result<BatchEvaluationProof<'a, G, EFqSponge>>
+/// Checks that all the given points are on the curve and in the prime-order subgroup.
+fn check_points<'a, G>(name: &'static str, points: impl IntoIterator<Item = &'a G>) -> Result<()>
+where
+    G: CommitmentCurve,
+{
+    for point in points {
+        if !point.is_on_curve() {
+            return Err(VerifyError::PointNotOnCurve(name));
+        }
+        if !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(VerifyError::PointNotInSubgroup(name));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the negated public input polynomial,
+/// the polynomial that evaluates to $-p_i$ for the first `public.len()` values of the domain, and $0$ for the rest.
+fn public_polynomial<G: CommitmentCurve, PCS>(
+    index: &VerifierIndex<G, PCS>,
+    public: &[ScalarField<G>],
+) -> DensePolynomial<ScalarField<G>> {
+    -Evaluations::<ScalarField<G>, D<ScalarField<G>>>::from_vec_and_domain(
+        public.to_vec(),
+        index.domain,
+    )
+    .interpolate()
+}
+
+/// Checks that a commitment is made of `chunks` valid curve points.
+fn check_commitment<G: CommitmentCurve>(
+    name: &'static str,
+    comm: &PolyComm<G>,
+    chunks: usize,
+) -> Result<()> {
+    if comm.unshifted.len() != chunks {
+        return Err(VerifyError::IncorrectCommitmentChunks(
+            name,
+            comm.unshifted.len(),
+            chunks,
+        ));
+    }
+    check_points(name, comm.unshifted.iter().chain(&comm.shifted))
+}
+
+/// Checks that the chunked evaluations of a polynomial at $\zeta$ and $\zeta\omega$
+/// have as many chunks as its commitment.
+fn check_evaluations<F>(
+    name: &'static str,
+    evals0: &ChunkedEvaluations<F>,
+    evals1: &ChunkedEvaluations<F>,
+    chunks: usize,
+) -> Result<()> {
+    if evals0.len() != chunks || evals1.len() != chunks {
+        return Err(VerifyError::IncorrectEvaluationsLength(name));
+    }
+    Ok(())
+}
+
+fn to_batch<'a, G, EFqSponge, EFrSponge, PCS>(
+    index: &VerifierIndex<G, PCS>,
+    proof: &'a ProverProof<G, PCS>,
+) -> Result<BatchEvaluationProof<'a, G, EFqSponge, PCS::OpeningProof>>
 where
     G: CommitmentCurve,
     G::BaseField: PrimeField,
     EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
     EFrSponge: FrSponge<ScalarField<G>>,
+    PCS: PolynomialCommitment<G>,
 {
     //~
     //~ #### Partial verification
@@ -474,22 +685,23 @@ where
     //~
 
     //~ 1. Commit to the negated public input polynomial.
-    let lgr_comm = index
-        .srs
-        .lagrange_bases
-        .get(&index.domain.size())
-        .expect("pre-computed committed lagrange bases not found");
-    let com: Vec<_> = lgr_comm
-        .iter()
-        .map(|c| PolyComm {
-            unshifted: vec![*c],
-            shifted: None,
-        })
-        .take(proof.public.len())
-        .collect();
-    let com_ref: Vec<_> = com.iter().collect();
-    let elm: Vec<_> = proof.public.iter().map(|s| -*s).collect();
-    let p_comm = PolyComm::<G>::multi_scalar_mul(&com_ref, &elm);
+    //~    If the domain is larger than `max_poly_size`, there are no Lagrange bases to commit with:
+    //~    interpolate the negated public input polynomial and commit to it in chunks instead.
+    // the polynomial is only interpolated if the domain is chunked,
+    // in which case it is also evaluated by the Fiat-Shamir argument
+    let public_poly = if index.domain.size() > index.max_poly_size {
+        Some(public_polynomial(index, &proof.public))
+    } else {
+        None
+    };
+    let p_comm = if let Some(public_poly) = &public_poly {
+        index.srs.commit_non_hiding(public_poly, None)
+    } else {
+        let elm: Vec<_> = proof.public.iter().map(|s| -*s).collect();
+        index
+            .srs
+            .commit_evaluations_prefix_non_hiding(index.domain, &elm)
+    };
 
     //~ 2. Run the [Fiat-Shamir argument](#fiat-shamir-argument).
     let OraclesResult {
@@ -502,7 +714,11 @@ where
         zeta1: zeta_to_domain_size,
         ft_eval0,
         ..
-    } = proof.oracles::<EFqSponge, EFrSponge>(index, &p_comm)?;
+    } = proof.oracles_with_public_polynomial::<EFqSponge, EFrSponge>(
+        index,
+        &p_comm,
+        public_poly.as_ref(),
+    )?;
 
     //~ 3. Combine the chunked polynomials' evaluations
     //~    (TODO: most likely only the quotient polynomial is chunked)
@@ -526,7 +742,7 @@ where
 
         let alphas = all_alphas.get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS);
 
-        let mut commitments = vec![&index.sigma_comm[PERMUTS - 1]];
+        let mut commitments = vec![&index.sigma_comm[index.width.permuts - 1]];
         let mut scalars = vec![ConstraintSystem::perm_scalars(
             &evals,
             oracles.beta,
@@ -561,6 +777,7 @@ where
                 joint_combiner: oracles.joint_combiner.map(|j| j.1),
                 endo_coefficient: index.endo,
                 mds: index.fr_sponge_params.mds.clone(),
+                zk_rows: index.zk_rows,
             };
 
             for (col, tokens) in &index.linearization.index_terms {
@@ -637,7 +854,9 @@ where
         }
 
         // MSM
-        PolyComm::multi_scalar_mul(&commitments, &scalars)
+        index
+            .srs
+            .commitments_multi_scalar_mul(&commitments, &scalars)
     };
 
     //~ 5. Compute the (chuncked) commitment of $ft$
@@ -677,7 +896,7 @@ where
     //~     - permutation commitment
     evaluations.push(Evaluation {
         commitment: proof.commitments.z_comm.clone(),
-        evaluations: proof.evals.iter().map(|e| e.z.clone()).collect(),
+        evaluations: proof.evals.iter().map(|e| e.z.chunks.clone()).collect(),
         degree_bound: None,
     });
 
@@ -687,7 +906,7 @@ where
         evaluations: proof
             .evals
             .iter()
-            .map(|e| e.generic_selector.clone())
+            .map(|e| e.generic_selector.chunks.clone())
             .collect(),
         degree_bound: None,
     });
@@ -696,7 +915,7 @@ where
         evaluations: proof
             .evals
             .iter()
-            .map(|e| e.poseidon_selector.clone())
+            .map(|e| e.poseidon_selector.chunks.clone())
             .collect(),
         degree_bound: None,
     });
@@ -708,12 +927,12 @@ where
             .w_comm
             .iter()
             .zip(
-                (0..COLUMNS)
+                (0..index.width.columns)
                     .map(|i| {
                         proof
                             .evals
                             .iter()
-                            .map(|e| e.w[i].clone())
+                            .map(|e| e.w[i].chunks.clone())
                             .collect::<Vec<_>>()
                     })
                     .collect::<Vec<_>>(),
@@ -731,12 +950,12 @@ where
             .sigma_comm
             .iter()
             .zip(
-                (0..PERMUTS - 1)
+                (0..index.width.permuts - 1)
                     .map(|i| {
                         proof
                             .evals
                             .iter()
-                            .map(|e| e.s[i].clone())
+                            .map(|e| e.s[i].chunks.clone())
                             .collect::<Vec<_>>()
                     })
                     .collect::<Vec<_>>(),
@@ -773,12 +992,12 @@ where
         // add evaluations of sorted polynomials
         for (comm, evals0, evals1) in izip!(
             &lookup_comms.sorted,
-            lookup_eval0.sorted.clone(),
-            lookup_eval1.sorted.clone()
+            &lookup_eval0.sorted,
+            &lookup_eval1.sorted
         ) {
             evaluations.push(Evaluation {
                 commitment: comm.clone(),
-                evaluations: vec![evals0, evals1],
+                evaluations: vec![evals0.chunks.clone(), evals1.chunks.clone()],
                 degree_bound: None,
             });
         }
@@ -786,7 +1005,10 @@ where
         // add evaluations of the aggreg polynomial
         evaluations.push(Evaluation {
             commitment: lookup_comms.aggreg.clone(),
-            evaluations: vec![lookup_eval0.aggreg.clone(), lookup_eval1.aggreg.clone()],
+            evaluations: vec![
+                lookup_eval0.aggreg.chunks.clone(),
+                lookup_eval1.aggreg.chunks.clone(),
+            ],
             degree_bound: None,
         });
 
@@ -807,7 +1029,10 @@ where
         // add evaluation of the table polynomial
         evaluations.push(Evaluation {
             commitment: table_comm,
-            evaluations: vec![lookup_eval0.table.clone(), lookup_eval1.table.clone()],
+            evaluations: vec![
+                lookup_eval0.table.chunks.clone(),
+                lookup_eval1.table.chunks.clone(),
+            ],
             degree_bound: None,
         });
     }
@@ -825,34 +1050,81 @@ where
 }
 
 /// Verify a proof [ProverProof] using a [VerifierIndex] and a `group_map`.
-pub fn verify<G, EFqSponge, EFrSponge>(
+pub fn verify<G, EFqSponge, EFrSponge, PCS>(
     group_map: &G::Map,
-    verifier_index: &VerifierIndex<G>,
-    proof: &ProverProof<G>,
+    verifier_index: &VerifierIndex<G, PCS>,
+    proof: &ProverProof<G, PCS>,
 ) -> Result<()>
 where
     G: CommitmentCurve,
     G::BaseField: PrimeField,
     EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
     EFrSponge: FrSponge<ScalarField<G>>,
+    PCS: PolynomialCommitment<G>,
 {
     let proofs = vec![(verifier_index, proof)];
-    batch_verify::<G, EFqSponge, EFrSponge>(group_map, &proofs)
+    batch_verify::<G, EFqSponge, EFrSponge, PCS>(group_map, &proofs)
 }
 
 /// This function verifies the batch of zk-proofs
 ///     proofs: vector of Plonk proofs
 ///     index: VerifierIndex
 ///     RETURN: verification status
-pub fn batch_verify<G, EFqSponge, EFrSponge>(
+pub fn batch_verify<G, EFqSponge, EFrSponge, PCS>(
     group_map: &G::Map,
-    proofs: &[(&VerifierIndex<G>, &ProverProof<G>)],
+    proofs: &[(&VerifierIndex<G, PCS>, &ProverProof<G, PCS>)],
 ) -> Result<()>
 where
     G: CommitmentCurve,
     G::BaseField: PrimeField,
     EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
     EFrSponge: FrSponge<ScalarField<G>>,
+    PCS: PolynomialCommitment<G>,
+{
+    batch_verify_with(group_map, proofs, |proofs| {
+        proofs
+            .iter()
+            .map(|(index, proof)| to_batch::<G, EFqSponge, EFrSponge, PCS>(index, proof))
+            .collect()
+    })
+}
+
+/// Same as [batch_verify], except that the proofs are partially verified in parallel.
+/// This requires the sponge to be [Send].
+pub fn par_batch_verify<G, EFqSponge, EFrSponge, PCS>(
+    group_map: &G::Map,
+    proofs: &[(&VerifierIndex<G, PCS>, &ProverProof<G, PCS>)],
+) -> Result<()>
+where
+    G: CommitmentCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + Send + FqSponge<BaseField<G>, G, ScalarField<G>>,
+    EFrSponge: FrSponge<ScalarField<G>>,
+    PCS: PolynomialCommitment<G> + Send + Sync,
+    PCS::OpeningProof: Sync,
+{
+    batch_verify_with(group_map, proofs, |proofs| {
+        proofs
+            .par_iter()
+            .map(|(index, proof)| to_batch::<G, EFqSponge, EFrSponge, PCS>(index, proof))
+            .collect()
+    })
+}
+
+/// Verifies a batch of proofs, using `to_batches` to partially verify each of them.
+fn batch_verify_with<'a, G, EFqSponge, PCS>(
+    group_map: &G::Map,
+    proofs: &[(&'a VerifierIndex<G, PCS>, &'a ProverProof<G, PCS>)],
+    to_batches: impl FnOnce(
+        &[(&'a VerifierIndex<G, PCS>, &'a ProverProof<G, PCS>)],
+    )
+        -> Result<Vec<BatchEvaluationProof<'a, G, EFqSponge, PCS::OpeningProof>>>,
+) -> Result<()>
+where
+    G: CommitmentCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+    PCS: PolynomialCommitment<G>,
 {
     //~ #### Batch verification of proofs
     //~
@@ -866,22 +1138,72 @@ where
         return Ok(());
     }
 
-    //~ 2. Ensure that all the proof's verifier index have a URS of the same length. (TODO: do they have to be the same URS though? should we check for that?)
-    // TODO: Account for the different SRS lengths
-    let srs = &proofs[0].0.srs;
+    //~ 2. Ensure that the URS of each proof's verifier index is compatible with the longest URS.
+    //~    With the inner product argument, it must be a prefix of the longest URS,
+    //~    and its length must be a power of two if it is shorter than the longest URS.
+    //~    With KZG, all the URS must be the same.
+    //~    The opening proofs are then verified against the longest URS.
+    let srs = proofs
+        .iter()
+        .map(|(index, _)| &index.srs)
+        .max_by_key(|srs| srs.max_poly_size())
+        .expect("the list of proofs is not empty");
     for (index, _) in proofs.iter() {
-        assert_eq!(index.srs.g.len(), srs.g.len());
+        if !index.srs.is_compatible_with(srs) {
+            return Err(VerifyError::IncompatibleSrs);
+        }
     }
 
-    //~ 3. Validate each proof separately following the [partial verification](#partial-verification) steps.
-    let mut batch = vec![];
+    //~ 3. Check that each proof is well-formed with respect to its verifier index
+    //~    (number of chunks in each commitment, points on the curve, lengths of the evaluations, size of the public input).
     for (index, proof) in proofs {
-        batch.push(to_batch::<G, EFqSponge, EFrSponge>(index, proof)?);
+        proof.validate_structure(index)?;
     }
 
-    //~ 4. Use the [`PolyCom.verify`](#polynomial-commitments) to verify the partially evaluated proofs.
+    //~ 4. Validate each proof separately following the [partial verification](#partial-verification) steps.
+    //~    As the proofs are independent, this can be done in parallel.
+    let mut batch = to_batches(proofs)?;
+
+    //~ 5. Use the [`PolyCom.verify`](#polynomial-commitments) to verify the partially evaluated proofs.
     match srs.verify::<EFqSponge, _>(group_map, &mut batch, &mut thread_rng()) {
         false => Err(VerifyError::OpenProof),
         true => Ok(()),
     }
 }
+
+/// Verifies a proof [ProverProof] using a [VerifierIndex] and a `group_map`,
+/// except for the expensive part of the opening proof verification
+/// which is deferred and returned as an [Accumulator].
+/// The accumulator must later be checked with [decide],
+/// or passed to a recursive proof with [Accumulator::to_prev_challenges].
+pub fn verify_succinct<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    verifier_index: &VerifierIndex<G>,
+    proof: &ProverProof<G>,
+) -> Result<Accumulator<G>>
+where
+    G: CommitmentCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+    EFrSponge: FrSponge<ScalarField<G>>,
+{
+    proof.validate_structure(verifier_index)?;
+    let mut batch = to_batch::<G, EFqSponge, EFrSponge, SRS<G>>(verifier_index, proof)?;
+    verifier_index
+        .srs
+        .succinct_check(group_map, &mut batch)
+        .ok_or(VerifyError::OpenProof)
+}
+
+/// Checks a number of [Accumulator]s (obtained via [verify_succinct]) at once.
+/// The accumulators can come from proofs using different prefixes of the given SRS.
+pub fn decide<G>(srs: &SRS<G>, accumulators: &[Accumulator<G>]) -> Result<()>
+where
+    G: CommitmentCurve,
+    G::BaseField: PrimeField,
+{
+    match srs.decide(accumulators, &mut thread_rng()) {
+        false => Err(VerifyError::Accumulator),
+        true => Ok(()),
+    }
+}
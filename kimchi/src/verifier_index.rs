@@ -2,13 +2,17 @@
 //! You can derive this struct from the [ProverIndex] struct.
 
 use crate::alphas::Alphas;
-use crate::circuits::lookup::lookups::LookupsUsed;
+use crate::circuits::lookup::{constraints::LookupConfiguration, lookups::LookupsUsed};
 use crate::circuits::polynomials::permutation::zk_polynomial;
 use crate::circuits::polynomials::permutation::zk_w3;
 use crate::circuits::{
+    constraints::{default_zk_rows, ZkMode},
     expr::{Linearization, PolishToken},
+    gate::GateType,
     wires::*,
 };
+use crate::error::SetupError;
+use crate::linearization::{check_linearization, json};
 use crate::prover_index::ProverIndex;
 use ark_ff::PrimeField;
 use ark_poly::{univariate::DensePolynomial, Radix2EvaluationDomain as D};
@@ -16,6 +20,7 @@ use array_init::array_init;
 use commitment_dlog::{
     commitment::{CommitmentCurve, PolyComm},
     srs::SRS,
+    PolynomialCommitment,
 };
 use o1_utils::types::fields::*;
 use oracle::poseidon::ArithmeticSpongeParams;
@@ -23,6 +28,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
 use std::io::SeekFrom::Start;
 use std::{
+    collections::BTreeSet,
     fs::{File, OpenOptions},
     io::{BufReader, BufWriter, Seek},
     path::Path,
@@ -50,7 +56,7 @@ pub struct LookupVerifierIndex<G: CommitmentCurve> {
 
 #[serde_as]
 #[derive(Serialize, Deserialize)]
-pub struct VerifierIndex<G: CommitmentCurve> {
+pub struct VerifierIndex<G: CommitmentCurve, PCS = SRS<G>> {
     /// evaluation domain
     #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub domain: D<ScalarField<G>>,
@@ -58,17 +64,22 @@ pub struct VerifierIndex<G: CommitmentCurve> {
     pub max_poly_size: usize,
     /// maximal size of the quotient polynomial according to the supported constraints
     pub max_quot_size: usize,
+    /// number of public inputs
+    pub public: usize,
+    /// number of rows at the end of the domain reserved for zero-knowledge
+    #[serde(default = "default_zk_rows")]
+    pub zk_rows: u64,
     /// polynomial commitment keys
     #[serde(skip)]
-    pub srs: Arc<SRS<G>>,
+    pub srs: Arc<PCS>,
 
     // index polynomial commitments
-    /// permutation commitment array
+    /// permutation commitment array, one per permuted column
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
-    pub sigma_comm: [PolyComm<G>; PERMUTS],
-    /// coefficient commitment array
+    pub sigma_comm: Vec<PolyComm<G>>,
+    /// coefficient commitment array, one per coefficient column (see [Width::coefficients])
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
-    pub coefficients_comm: [PolyComm<G>; COLUMNS],
+    pub coefficients_comm: Vec<PolyComm<G>>,
     /// coefficient commitment array
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub generic_comm: PolyComm<G>,
@@ -96,9 +107,9 @@ pub struct VerifierIndex<G: CommitmentCurve> {
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub chacha_comm: Option<[PolyComm<G>; 4]>,
 
-    /// wire coordinate shifts
-    #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
-    pub shift: [ScalarField<G>; PERMUTS],
+    /// wire coordinate shifts, one per permuted column
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub shift: Vec<ScalarField<G>>,
     /// zero-knowledge polynomial
     #[serde(skip)]
     pub zkpm: DensePolynomial<ScalarField<G>>,
@@ -113,12 +124,24 @@ pub struct VerifierIndex<G: CommitmentCurve> {
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub lookup_index: Option<LookupVerifierIndex<G>>,
 
-    #[serde(skip)]
+    /// The set of gate types used in the circuit, which the linearization is specialized to
+    pub gate_types: BTreeSet<GateType>,
+    #[serde(
+        bound = "Linearization<Vec<PolishToken<ScalarField<G>>>>: Serialize + DeserializeOwned"
+    )]
     pub linearization: Linearization<Vec<PolishToken<ScalarField<G>>>>,
     /// The mapping between powers of alpha and constraints
-    #[serde(skip)]
+    #[serde(bound = "Alphas<ScalarField<G>>: Serialize + DeserializeOwned")]
     pub powers_of_alpha: Alphas<ScalarField<G>>,
 
+    /// whether the proofs hide the witness
+    #[serde(default)]
+    pub zk_mode: ZkMode,
+
+    /// number of witness columns and of permuted columns
+    #[serde(default)]
+    pub width: Width,
+
     // random oracle argument parameters
     #[serde(skip)]
     pub fr_sponge_params: ArithmeticSpongeParams<ScalarField<G>>,
@@ -127,12 +150,12 @@ pub struct VerifierIndex<G: CommitmentCurve> {
 }
 //~spec:endcode
 
-impl<'a, G: CommitmentCurve> ProverIndex<G>
+impl<'a, G: CommitmentCurve, PCS: PolynomialCommitment<G>> ProverIndex<G, PCS>
 where
     G::BaseField: PrimeField,
 {
     /// Produces the [VerifierIndex] from the prover's [ProverIndex].
-    pub fn verifier_index(&self) -> VerifierIndex<G> {
+    pub fn verifier_index(&self) -> VerifierIndex<G, PCS> {
         let domain = self.cs.domain.d1;
         let lookup_index = {
             self.cs
@@ -158,71 +181,95 @@ where
                 })
         };
 
+        // the commitments only depend on the values of the index polynomials on the rows
+        let column_evaluations = self.row_evaluations();
+
         // TODO: Switch to commit_evaluations for all index polys
         VerifierIndex {
             domain,
             max_poly_size: self.max_poly_size,
             max_quot_size: self.max_quot_size,
+            public: self.cs.public,
+            zk_rows: self.cs.zk_rows,
             powers_of_alpha: self.powers_of_alpha.clone(),
             srs: Arc::clone(&self.srs),
 
-            sigma_comm: array_init(|i| self.srs.commit_non_hiding(&self.cs.sigmam[i], None)),
-            coefficients_comm: array_init(|i| {
-                self.srs
-                    .commit_evaluations_non_hiding(domain, &self.cs.coefficients8[i], None)
-            }),
+            sigma_comm: self
+                .cs
+                .sigmam
+                .iter()
+                .map(|sigma| self.srs.commit_non_hiding(sigma, None))
+                .collect(),
+            coefficients_comm: column_evaluations
+                .coefficients8
+                .iter()
+                .map(|c| self.srs.commit_evaluations_non_hiding(domain, c, None))
+                .collect(),
             generic_comm: self.srs.commit_non_hiding(&self.cs.genericm, None),
 
             psm_comm: self.srs.commit_non_hiding(&self.cs.psm, None),
 
             complete_add_comm: self.srs.commit_evaluations_non_hiding(
                 domain,
-                &self.cs.complete_addl4,
+                &column_evaluations.complete_addl4,
+                None,
+            ),
+            mul_comm: self.srs.commit_evaluations_non_hiding(
+                domain,
+                &column_evaluations.mull8,
+                None,
+            ),
+            emul_comm: self.srs.commit_evaluations_non_hiding(
+                domain,
+                &column_evaluations.emull,
                 None,
             ),
-            mul_comm: self
-                .srs
-                .commit_evaluations_non_hiding(domain, &self.cs.mull8, None),
-            emul_comm: self
-                .srs
-                .commit_evaluations_non_hiding(domain, &self.cs.emull, None),
 
             endomul_scalar_comm: self.srs.commit_evaluations_non_hiding(
                 domain,
-                &self.cs.endomul_scalar8,
+                &column_evaluations.endomul_scalar8,
                 None,
             ),
 
-            chacha_comm: self.cs.chacha8.as_ref().map(|c| {
+            chacha_comm: column_evaluations.chacha8.as_ref().map(|c| {
                 array_init(|i| self.srs.commit_evaluations_non_hiding(domain, &c[i], None))
             }),
 
-            shift: self.cs.shift,
+            shift: self.cs.shift.clone(),
             zkpm: self.cs.precomputations().zkpm.clone(),
-            w: zk_w3(self.cs.domain.d1),
+            w: zk_w3(self.cs.domain.d1, self.cs.zk_rows),
             endo: self.cs.endo,
             lookup_index,
+            gate_types: self.gate_types.clone(),
             linearization: self.linearization.clone(),
+            zk_mode: self.cs.zk_mode,
+            width: self.cs.width,
             fr_sponge_params: self.cs.fr_sponge_params.clone(),
             fq_sponge_params: self.fq_sponge_params.clone(),
         }
     }
 }
 
-impl<G> VerifierIndex<G>
+impl<G, PCS> VerifierIndex<G, PCS>
 where
     G: CommitmentCurve,
 {
     /// Deserializes a [VerifierIndex] from a file, given a pointer to an SRS and an optional offset in the file.
+    /// The linearization and the powers of alpha read from the file are checked against the gates of the circuit
+    /// (see [VerifierIndex::check_linearization]), which requires the lookup configuration of the circuit if it uses lookups.
     pub fn from_file(
-        srs: Arc<SRS<G>>,
+        srs: Arc<PCS>,
         path: &Path,
         offset: Option<u64>,
         // TODO: we shouldn't have to pass these
         endo: G::ScalarField,
         fq_sponge_params: ArithmeticSpongeParams<BaseField<G>>,
         fr_sponge_params: ArithmeticSpongeParams<ScalarField<G>>,
-    ) -> Result<Self, String> {
+        lookup_configuration: Option<&LookupConfiguration<ScalarField<G>>>,
+    ) -> Result<Self, String>
+    where
+        PCS: Default,
+    {
         // open file
         let file = File::open(path).map_err(|e| e.to_string())?;
 
@@ -236,18 +283,45 @@ where
         let mut verifier_index = Self::deserialize(&mut rmp_serde::Deserializer::new(reader))
             .map_err(|e| e.to_string())?;
 
+        // the permutation must match the width of the circuit
+        let permuts = verifier_index.width.permuts;
+        if verifier_index.sigma_comm.len() != permuts || verifier_index.shift.len() != permuts {
+            return Err(format!(
+                "the verifier index has {} permutation commitments and {} shifts, but {} permuted columns",
+                verifier_index.sigma_comm.len(),
+                verifier_index.shift.len(),
+                permuts
+            ));
+        }
+
+        // so must the coefficients
+        let coefficients = verifier_index.width.coefficients();
+        if verifier_index.coefficients_comm.len() != coefficients {
+            return Err(format!(
+                "the verifier index has {} coefficient commitments, but {} coefficient columns",
+                verifier_index.coefficients_comm.len(),
+                coefficients
+            ));
+        }
+
+        // a stale or tampered linearization would only show up as failing verifications
+        verifier_index
+            .check_linearization(lookup_configuration)
+            .map_err(|e| e.to_string())?;
+
         // fill in the rest
         verifier_index.srs = srs;
         verifier_index.endo = endo;
         verifier_index.fq_sponge_params = fq_sponge_params;
         verifier_index.fr_sponge_params = fr_sponge_params;
-        verifier_index.w = zk_w3(verifier_index.domain);
-        verifier_index.zkpm = zk_polynomial(verifier_index.domain);
+        verifier_index.w = zk_w3(verifier_index.domain, verifier_index.zk_rows);
+        verifier_index.zkpm = zk_polynomial(verifier_index.domain, verifier_index.zk_rows);
 
         Ok(verifier_index)
     }
 
     /// Writes a [VerifierIndex] to a file, potentially appending it to the already-existing content (if append is set to true)
+    /// Like the ones of proofs, its curve points are always compressed (see [o1_utils::serialization::SerdeAs]).
     // TODO: append should be a bool, not an option
     pub fn to_file(&self, path: &Path, append: Option<bool>) -> Result<(), String> {
         let append = append.unwrap_or(true);
@@ -261,4 +335,29 @@ where
         self.serialize(&mut rmp_serde::Serializer::new(writer))
             .map_err(|e| e.to_string())
     }
+
+    /// Checks that the linearization and the powers of alpha of the index,
+    /// which might have been deserialized, match the ones computed from the gates of the circuit.
+    /// As the lookup configuration of the circuit is not part of the verifier index,
+    /// it must be passed if the circuit uses lookups.
+    pub fn check_linearization(
+        &self,
+        lookup_configuration: Option<&LookupConfiguration<ScalarField<G>>>,
+    ) -> Result<(), SetupError> {
+        check_linearization(
+            self.domain,
+            self.zk_rows,
+            self.width,
+            &self.gate_types,
+            lookup_configuration,
+            &self.linearization,
+            &self.powers_of_alpha,
+        )
+    }
+
+    /// Exports the linearization and the powers of alpha of the index to JSON,
+    /// for verifiers implemented in other languages (see [crate::linearization::json] for the format).
+    pub fn linearization_json(&self) -> String {
+        json::to_json(self.zk_rows, &self.linearization, &self.powers_of_alpha)
+    }
 }
@@ -0,0 +1,46 @@
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand};
+use commitment_dlog::{
+    msm::{glv_multi_scalar_mul, GlvDecomposition},
+    srs::endos,
+};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode};
+use mina_curves::pasta::{
+    vesta::{Affine, VestaParameters},
+    Fp,
+};
+
+pub fn bench_msm(c: &mut Criterion) {
+    let mut group = c.benchmark_group("MSM");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    let mut rng = rand::thread_rng();
+    let (endo_q, endo_r) = endos::<Affine>();
+    let glv = GlvDecomposition::new(endo_r);
+
+    for log_size in [10, 14, 16] {
+        let size = 1 << log_size;
+        let bases = {
+            let bases: Vec<_> = (0..size)
+                .map(|_| Affine::prime_subgroup_generator().mul(Fp::rand(&mut rng)))
+                .collect();
+            <Affine as AffineCurve>::Projective::batch_normalization_into_affine(&bases)
+        };
+        let scalars: Vec<Fp> = (0..size).map(|_| Fp::rand(&mut rng)).collect();
+        let reprs: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+
+        group.bench_with_input(BenchmarkId::new("arkworks", size), &size, |b, _| {
+            b.iter(|| black_box(VariableBaseMSM::multi_scalar_mul(&bases, &reprs)))
+        });
+        group.bench_with_input(BenchmarkId::new("glv", size), &size, |b, _| {
+            b.iter(|| {
+                black_box(glv_multi_scalar_mul::<VestaParameters>(
+                    endo_q, &glv, &bases, &scalars,
+                ))
+            })
+        });
+    }
+}
+
+criterion_group!(benches, bench_msm);
+criterion_main!(benches);
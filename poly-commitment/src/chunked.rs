@@ -1,5 +1,6 @@
 use ark_ec::ProjectiveCurve;
-use ark_ff::{Field, Zero};
+use ark_ff::Field;
+use o1_utils::chunked_polynomial::combine_chunks;
 
 use crate::commitment::CommitmentCurve;
 use crate::PolyComm;
@@ -12,14 +13,11 @@ where
     /// Note that it ignores the shifted part.
     // TODO(mimoo): better name for this function
     pub fn chunk_commitment(&self, zeta_n: C::ScalarField) -> Self {
-        let mut res = C::Projective::zero();
-        // use Horner's to compute chunk[0] + z^n chunk[1] + z^2n chunk[2] + ...
-        // as ( chunk[-1] * z^n + chunk[-2] ) * z^n + chunk[-3]
-        // (https://en.wikipedia.org/wiki/Horner%27s_method)
-        for chunk in self.unshifted.iter().rev() {
-            res *= zeta_n;
-            res.add_assign_mixed(chunk);
-        }
+        let res = combine_chunks(self.unshifted.iter(), |mut acc: C::Projective, chunk| {
+            acc *= zeta_n;
+            acc.add_assign_mixed(chunk);
+            acc
+        });
 
         PolyComm {
             unshifted: vec![res.into_affine()],
@@ -36,14 +34,6 @@ where
     /// Note that it ignores the shifted part.
     // TODO(mimoo): better name for this function
     pub fn chunk_blinding(&self, zeta_n: F) -> F {
-        let mut res = F::zero();
-        // use Horner's to compute chunk[0] + z^n chunk[1] + z^2n chunk[2] + ...
-        // as ( chunk[-1] * z^n + chunk[-2] ) * z^n + chunk[-3]
-        // (https://en.wikipedia.org/wiki/Horner%27s_method)
-        for chunk in self.unshifted.iter().rev() {
-            res *= zeta_n;
-            res += chunk
-        }
-        res
+        combine_chunks(self.unshifted.iter(), |acc: F, chunk| acc * zeta_n + chunk)
     }
 }
@@ -24,6 +24,49 @@ use itertools::Itertools;
 use oracle::sponge::ScalarChallenge;
 use rayon::prelude::*;
 
+/// Returns the denominator of the slope used to add `p0` and `p1`,
+/// which must be inverted before calling [add_with_inverse].
+pub(crate) fn add_denominator<P: SWModelParameters>(
+    p0: &SWJAffine<P>,
+    p1: &SWJAffine<P>,
+) -> P::BaseField {
+    if p0.x == p1.x {
+        if p1.y.is_zero() {
+            P::BaseField::one()
+        } else {
+            p1.y.double()
+        }
+    } else {
+        p0.x - p1.x
+    }
+}
+
+/// Returns `p0 + p1`, given the inverse `d` of the denominator returned by [add_denominator].
+pub(crate) fn add_with_inverse<P: SWModelParameters>(
+    p0: &SWJAffine<P>,
+    p1: &SWJAffine<P>,
+    d: &P::BaseField,
+) -> SWJAffine<P> {
+    if p1.is_zero() {
+        *p0
+    } else if p0.is_zero() {
+        *p1
+    } else if p1.x == p0.x && (p1.y != p0.y || p1.y.is_zero()) {
+        SWJAffine::<P>::zero()
+    } else if p1.x == p0.x && p1.y == p0.y {
+        let sq = p0.x.square();
+        let s = (sq.double() + sq + P::COEFF_A) * d;
+        let x = s.square() - p0.x.double();
+        let y = -p0.y - (s * (x - p0.x));
+        SWJAffine::<P>::new(x, y, false)
+    } else {
+        let s = (p0.y - p1.y) * d;
+        let x = s.square() - p0.x - p1.x;
+        let y = -p0.y - (s * (x - p0.x));
+        SWJAffine::<P>::new(x, y, false)
+    }
+}
+
 fn add_pairs_in_place<P: SWModelParameters>(pairs: &mut Vec<SWJAffine<P>>) {
     let len = if pairs.len() % 2 == 0 {
         pairs.len()
@@ -32,45 +75,14 @@ fn add_pairs_in_place<P: SWModelParameters>(pairs: &mut Vec<SWJAffine<P>>) {
     };
     let mut denominators = pairs
         .chunks_exact_mut(2)
-        .map(|p| {
-            if p[0].x == p[1].x {
-                if p[1].y.is_zero() {
-                    P::BaseField::one()
-                } else {
-                    p[1].y.double()
-                }
-            } else {
-                p[0].x - p[1].x
-            }
-        })
+        .map(|p| add_denominator(&p[0], &p[1]))
         .collect::<Vec<_>>();
 
     ark_ff::batch_inversion::<P::BaseField>(&mut denominators);
 
     for (i, d) in (0..len).step_by(2).zip(denominators.iter()) {
         let j = i / 2;
-        if pairs[i + 1].is_zero() {
-            pairs[j] = pairs[i];
-        } else if pairs[i].is_zero() {
-            pairs[j] = pairs[i + 1];
-        } else if pairs[i + 1].x == pairs[i].x
-            && (pairs[i + 1].y != pairs[i].y || pairs[i + 1].y.is_zero())
-        {
-            pairs[j] = SWJAffine::<P>::zero();
-        } else if pairs[i + 1].x == pairs[i].x && pairs[i + 1].y == pairs[i].y {
-            let sq = pairs[i].x.square();
-            let s = (sq.double() + sq + P::COEFF_A) * d;
-            let x = s.square() - pairs[i].x.double();
-            let y = -pairs[i].y - (s * (x - pairs[i].x));
-            pairs[j].x = x;
-            pairs[j].y = y;
-        } else {
-            let s = (pairs[i].y - pairs[i + 1].y) * d;
-            let x = s.square() - pairs[i].x - pairs[i + 1].x;
-            let y = -pairs[i].y - (s * (x - pairs[i].x));
-            pairs[j].x = x;
-            pairs[j].y = y;
-        }
+        pairs[j] = add_with_inverse(&pairs[i], &pairs[i + 1], d);
     }
 
     let len = pairs.len();
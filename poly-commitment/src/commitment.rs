@@ -6,9 +6,11 @@
 //!     producing the batched opening proof
 //! 3. Verify batch of batched opening proofs
 
+use crate::msm::GlvDecomposition;
 use crate::srs::SRS;
 use ark_ec::{
-    models::short_weierstrass_jacobian::GroupAffine as SWJAffine, msm::VariableBaseMSM,
+    models::short_weierstrass_jacobian::{GroupAffine as SWJAffine, GroupProjective},
+    msm::VariableBaseMSM,
     AffineCurve, ProjectiveCurve, SWModelParameters,
 };
 use ark_ff::{
@@ -184,20 +186,33 @@ impl<C: AffineCurve> PolyComm<C> {
     }
 
     pub fn multi_scalar_mul(com: &[&PolyComm<C>], elm: &[C::ScalarField]) -> Self {
+        Self::multi_scalar_mul_with(com, elm, |points, scalars| {
+            let scalars: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+            VariableBaseMSM::multi_scalar_mul(points, &scalars)
+        })
+    }
+
+    /// Same as [PolyComm::multi_scalar_mul], but computes each chunk with the given `msm`
+    /// (e.g. [SRS::multi_scalar_mul] to use the MSM algorithm selected in the SRS).
+    pub fn multi_scalar_mul_with(
+        com: &[&PolyComm<C>],
+        elm: &[C::ScalarField],
+        msm: impl Fn(&[C], &[C::ScalarField]) -> C::Projective,
+    ) -> Self {
         assert_eq!(com.len(), elm.len());
         PolyComm::<C> {
             shifted: {
                 let pairs = com
                     .iter()
                     .zip(elm.iter())
-                    .filter_map(|(c, s)| c.shifted.map(|c| (c, s)))
+                    .filter_map(|(c, s)| c.shifted.map(|c| (c, *s)))
                     .collect::<Vec<_>>();
                 if pairs.is_empty() {
                     None
                 } else {
                     let points = pairs.iter().map(|(c, _)| *c).collect::<Vec<_>>();
-                    let scalars = pairs.iter().map(|(_, s)| s.into_repr()).collect::<Vec<_>>();
-                    Some(VariableBaseMSM::multi_scalar_mul(&points, &scalars).into_affine())
+                    let scalars = pairs.iter().map(|(_, s)| *s).collect::<Vec<_>>();
+                    Some(msm(&points, &scalars).into_affine())
                 }
             },
             unshifted: {
@@ -212,10 +227,10 @@ impl<C: AffineCurve> PolyComm<C> {
                             com.iter().zip(elm.iter()).for_each(|(p, s)| {
                                 if i < p.unshifted.len() {
                                     points.push(p.unshifted[i]);
-                                    scalars.push(s.into_repr())
+                                    scalars.push(*s)
                                 }
                             });
-                            VariableBaseMSM::multi_scalar_mul(&points, &scalars).into_affine()
+                            msm(&points, &scalars).into_affine()
                         })
                         .collect::<Vec<_>>()
                 }
@@ -298,6 +313,13 @@ pub trait CommitmentCurve: AffineCurve {
     fn to_coordinates(&self) -> Option<(Self::BaseField, Self::BaseField)>;
     fn of_coordinates(x: Self::BaseField, y: Self::BaseField) -> Self;
 
+    /// Returns `true` if the point satisfies the curve equation (the point at infinity does).
+    fn is_on_curve(&self) -> bool;
+
+    /// Returns `true` if the point is in the prime-order subgroup,
+    /// assuming that it is already known to be on the curve.
+    fn is_in_correct_subgroup_assuming_on_curve(&self) -> bool;
+
     /// Combine where x1 = one
     fn combine_one(g1: &[Self], g2: &[Self], x2: Self::ScalarField) -> Vec<Self> {
         crate::combine::window_combine(g1, g2, Self::ScalarField::one(), x2)
@@ -322,6 +344,19 @@ pub trait CommitmentCurve: AffineCurve {
     ) -> Vec<Self> {
         crate::combine::window_combine(g1, g2, x1, x2)
     }
+
+    /// Computes `sum_i scalars[i] bases[i]` with the GLV method (see [crate::msm]),
+    /// given the endomorphism `endo_q` and the decomposition of the scalars for its eigenvalue.
+    /// Curves that cannot use the GLV method fall back to the generic algorithm of arkworks.
+    fn glv_multi_scalar_mul(
+        _endo_q: Self::BaseField,
+        _glv: &GlvDecomposition<Self::ScalarField>,
+        bases: &[Self],
+        scalars: &[Self::ScalarField],
+    ) -> Self::Projective {
+        let scalars: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+        VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+    }
 }
 
 impl<P: SWModelParameters> CommitmentCurve for SWJAffine<P>
@@ -343,6 +378,14 @@ where
         SWJAffine::<P>::new(x, y, false)
     }
 
+    fn is_on_curve(&self) -> bool {
+        SWJAffine::<P>::is_on_curve(self)
+    }
+
+    fn is_in_correct_subgroup_assuming_on_curve(&self) -> bool {
+        SWJAffine::<P>::is_in_correct_subgroup_assuming_on_curve(self)
+    }
+
     fn combine_one(g1: &[Self], g2: &[Self], x2: Self::ScalarField) -> Vec<Self> {
         crate::combine::affine_window_combine_one(g1, g2, x2)
     }
@@ -365,6 +408,15 @@ where
     ) -> Vec<Self> {
         crate::combine::affine_window_combine(g1, g2, x1, x2)
     }
+
+    fn glv_multi_scalar_mul(
+        endo_q: P::BaseField,
+        glv: &GlvDecomposition<P::ScalarField>,
+        bases: &[Self],
+        scalars: &[P::ScalarField],
+    ) -> GroupProjective<P> {
+        crate::msm::glv_multi_scalar_mul(endo_q, glv, bases, scalars)
+    }
 }
 
 pub fn to_group<G: CommitmentCurve>(m: &G::Map, t: <G as AffineCurve>::BaseField) -> G {
@@ -438,9 +490,11 @@ where
     pub degree_bound: Option<usize>,
 }
 
-/// Contains the batch evaluation
+/// Contains the batch evaluation.
+/// The opening proof is the one of the inner product argument by default,
+/// other schemes can use their own (see [PolynomialCommitment::OpeningProof](crate::pcs::PolynomialCommitment::OpeningProof)).
 // TODO: I think we should really change this name to something more correct
-pub struct BatchEvaluationProof<'a, G, EFqSponge>
+pub struct BatchEvaluationProof<'a, G, EFqSponge, Proof = OpeningProof<G>>
 where
     G: AffineCurve,
     EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
@@ -454,7 +508,7 @@ where
     /// scaling factor for polynomials
     pub r: ScalarField<G>,
     /// batched opening proof
-    pub opening: &'a OpeningProof<G>,
+    pub opening: &'a Proof,
 }
 
 impl<G: CommitmentCurve> SRS<G> {
@@ -500,7 +554,14 @@ impl<G: CommitmentCurve> SRS<G> {
         plnm: &DensePolynomial<ScalarField<G>>,
         max: Option<usize>,
     ) -> PolyComm<G> {
-        Self::commit_helper(&plnm.coeffs[..], &self.g[..], None, plnm.is_zero(), max)
+        Self::commit_helper_with_msm(
+            &plnm.coeffs[..],
+            &self.g[..],
+            None,
+            plnm.is_zero(),
+            max,
+            |bases, scalars| self.multi_scalar_mul(bases, scalars),
+        )
     }
 
     pub fn commit_helper(
@@ -509,6 +570,21 @@ impl<G: CommitmentCurve> SRS<G> {
         n: Option<usize>,
         is_zero: bool,
         max: Option<usize>,
+    ) -> PolyComm<G> {
+        Self::commit_helper_with_msm(scalars, basis, n, is_zero, max, |bases, scalars| {
+            let scalars: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+            VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+        })
+    }
+
+    /// Same as [SRS::commit_helper], with the given multi-scalar multiplication.
+    fn commit_helper_with_msm(
+        scalars: &[ScalarField<G>],
+        basis: &[G],
+        n: Option<usize>,
+        is_zero: bool,
+        max: Option<usize>,
+        msm: impl Fn(&[G], &[ScalarField<G>]) -> G::Projective,
     ) -> PolyComm<G> {
         let n = match n {
             Some(n) => n,
@@ -521,16 +597,7 @@ impl<G: CommitmentCurve> SRS<G> {
             Vec::new()
         } else {
             (0..p / n + if p % n != 0 { 1 } else { 0 })
-                .map(|i| {
-                    VariableBaseMSM::multi_scalar_mul(
-                        basis,
-                        &scalars[i * n..p]
-                            .iter()
-                            .map(|s| s.into_repr())
-                            .collect::<Vec<_>>(),
-                    )
-                    .into_affine()
-                })
+                .map(|i| msm(basis, &scalars[i * n..p]).into_affine())
                 .collect()
         };
 
@@ -544,16 +611,7 @@ impl<G: CommitmentCurve> SRS<G> {
                 } else if max % n == 0 {
                     None
                 } else {
-                    Some(
-                        VariableBaseMSM::multi_scalar_mul(
-                            &basis[n - (max % n)..],
-                            &scalars[start..p]
-                                .iter()
-                                .map(|s| s.into_repr())
-                                .collect::<Vec<_>>(),
-                        )
-                        .into_affine(),
-                    )
+                    Some(msm(&basis[n - (max % n)..], &scalars[start..p]).into_affine())
                 }
             }
         };
@@ -561,27 +619,43 @@ impl<G: CommitmentCurve> SRS<G> {
         PolyComm::<G> { unshifted, shifted }
     }
 
+    /// Commits to a polynomial given by its evaluations over `domain` (or over a larger domain),
+    /// using the Lagrange bases of `domain` (see [SRS::add_lagrange_basis]).
+    /// If `domain` is larger than the SRS, there are no such Lagrange bases:
+    /// the polynomial is then interpolated and committed to in chunks, like [SRS::commit_non_hiding] does.
     pub fn commit_evaluations_non_hiding(
         &self,
         domain: D<ScalarField<G>>,
         plnm: &Evaluations<ScalarField<G>, D<ScalarField<G>>>,
         max: Option<usize>,
     ) -> PolyComm<G> {
+        if domain.size() > self.g.len() {
+            assert!(
+                domain.size <= plnm.domain().size,
+                "desired commitment domain size greater than evaluations' domain size"
+            );
+            let s = (plnm.domain().size / domain.size) as usize;
+            let evals = plnm.evals.iter().step_by(s).cloned().collect();
+            let plnm = Evaluations::from_vec_and_domain(evals, domain).interpolate();
+            return self.commit_non_hiding(&plnm, max);
+        }
+
         let is_zero = plnm.evals.iter().all(|x| x.is_zero());
         let basis = match self.lagrange_bases.get(&domain.size()) {
             None => panic!("lagrange bases for size {} not found", domain.size()),
             Some(v) => &v[..],
         };
+        let msm = |bases: &[G], scalars: &[ScalarField<G>]| self.multi_scalar_mul(bases, scalars);
         match domain.size.cmp(&plnm.domain().size) {
             std::cmp::Ordering::Less => {
                 let s = (plnm.domain().size / domain.size) as usize;
                 let v: Vec<_> = (0..(domain.size as usize))
                     .map(|i| plnm.evals[s * i])
                     .collect();
-                Self::commit_helper(&v[..], basis, None, is_zero, max)
+                Self::commit_helper_with_msm(&v[..], basis, None, is_zero, max, msm)
             }
             std::cmp::Ordering::Equal => {
-                Self::commit_helper(&plnm.evals[..], basis, None, is_zero, max)
+                Self::commit_helper_with_msm(&plnm.evals[..], basis, None, is_zero, max, msm)
             }
             std::cmp::Ordering::Greater => {
                 panic!("desired commitment domain size greater than evaluations' domain size")
@@ -609,6 +683,11 @@ impl<G: CommitmentCurve> SRS<G> {
     ///     oracle_params: parameters for the random oracle argument
     ///     randomness source context
     ///     RETURN: verification status
+    ///
+    /// The opening proofs do not need to have been created with this exact SRS:
+    /// a proof created with a prefix of this SRS is also accepted,
+    /// as long as the length of that prefix is a power of two (or is the length of this SRS).
+    /// The length of the prefix is deduced from the number of rounds of the opening proof.
     pub fn verify<EFqSponge, RNG>(
         &self,
         group_map: &G::Map,
@@ -646,7 +725,15 @@ impl<G: CommitmentCurve> SRS<G> {
 
         let padded_length = 1 << max_rounds;
 
-        // TODO: This will need adjusting
+        // proofs created with a shorter SRS have less rounds,
+        // they can't have more rounds than proofs created with this SRS
+        if batch
+            .iter()
+            .any(|proof| proof.opening.lr.len() > max_rounds)
+        {
+            return false;
+        }
+
         let padding = padded_length - nonzero_length;
         let mut points = vec![self.h];
         points.extend(self.g.clone());
@@ -662,76 +749,19 @@ impl<G: CommitmentCurve> SRS<G> {
         let mut rand_base_i = ScalarField::<G>::one();
         let mut sg_rand_base_i = ScalarField::<G>::one();
 
-        for BatchEvaluationProof {
-            sponge,
-            evaluation_points,
-            xi,
-            r,
-            evaluations,
-            opening,
-        } in batch.iter_mut()
-        {
-            // TODO: This computation is repeated in ProverProof::oracles
-            let combined_inner_product0 = {
-                let es: Vec<_> = evaluations
-                    .iter()
-                    .map(
-                        |Evaluation {
-                             commitment,
-                             evaluations,
-                             degree_bound,
-                         }| {
-                            let bound: Option<usize> = (|| {
-                                let b = (*degree_bound)?;
-                                let x = commitment.shifted?;
-                                if x.is_zero() {
-                                    None
-                                } else {
-                                    Some(b)
-                                }
-                            })();
-                            (evaluations.clone(), bound)
-                        },
-                    )
-                    .collect();
-                combined_inner_product::<G>(evaluation_points, xi, r, &es, self.g.len())
-            };
-
-            sponge.absorb_fr(&[shift_scalar::<G>(combined_inner_product0)]);
+        for proof in batch.iter_mut() {
+            // TERMS
+            // everything but the - rand_base_i z1 G_i term,
+            // where G_i is replaced by the sg component of the proof
+            let Accumulator { challenges, sg } =
+                self.succinct_check_terms(group_map, proof, rand_base_i, &mut points, &mut scalars);
 
-            let t = sponge.challenge_fq();
-            let u: G = to_group(group_map, t);
+            // the length of the prefix of the SRS that was used to create the proof
+            let srs_length = std::cmp::min(1 << challenges.len(), nonzero_length);
 
-            let Challenges { chal, chal_inv } =
-                opening.challenges::<EFqSponge>(&self.endo_r, sponge);
-
-            sponge.absorb_g(&[opening.delta]);
-            let c = ScalarChallenge(sponge.challenge()).to_field(&self.endo_r);
-
-            // < s, sum_i r^i pows(evaluation_point[i]) >
-            // ==
-            // sum_i r^i < s, pows(evaluation_point[i]) >
-            let b0 = {
-                let mut scale = ScalarField::<G>::one();
-                let mut res = ScalarField::<G>::zero();
-                for &e in evaluation_points.iter() {
-                    let term = b_poly(&chal, e);
-                    res += &(scale * term);
-                    scale *= *r;
-                }
-                res
-            };
-
-            let s = b_poly_coefficients(&chal);
-
-            let neg_rand_base_i = -rand_base_i;
-
-            // TERM
-            // - rand_base_i z1 G
-            //
-            // we also add -sg_rand_base_i * G to check correctness of sg.
-            points.push(opening.sg);
-            scalars.push(neg_rand_base_i * opening.z1 - sg_rand_base_i);
+            // we add -sg_rand_base_i * G to check correctness of sg.
+            points.push(sg);
+            scalars.push(-sg_rand_base_i);
 
             // Here we add
             // sg_rand_base_i * ( < s, self.g > )
@@ -739,87 +769,187 @@ impl<G: CommitmentCurve> SRS<G> {
             // < sg_rand_base_i s, self.g >
             //
             // to check correctness of the sg component.
+            // Note that only the first `srs_length` generators were used to create the proof
+            // (the remaining ones were padded with zeros).
             {
-                let terms: Vec<_> = s.par_iter().map(|s| sg_rand_base_i * s).collect();
+                let s = b_poly_coefficients(&challenges);
+                let terms: Vec<_> = s[..srs_length]
+                    .par_iter()
+                    .map(|s| sg_rand_base_i * s)
+                    .collect();
 
                 for (i, term) in terms.iter().enumerate() {
                     scalars[i + 1] += term;
                 }
             }
 
-            // TERM
-            // - rand_base_i * z2 * H
-            scalars[0] -= &(rand_base_i * opening.z2);
-
-            // TERM
-            // -rand_base_i * (z1 * b0 * U)
-            scalars.push(neg_rand_base_i * (opening.z1 * b0));
-            points.push(u);
-
-            // TERM
-            // rand_base_i c_i Q_i
-            // = rand_base_i c_i
-            //   (sum_j (chal_invs[j] L_j + chals[j] R_j) + P_prime)
-            // where P_prime = combined commitment + combined_inner_product * U
-            let rand_base_i_c_i = c * rand_base_i;
-            for ((l, r), (u_inv, u)) in opening.lr.iter().zip(chal_inv.iter().zip(chal.iter())) {
-                points.push(*l);
-                scalars.push(rand_base_i_c_i * u_inv);
-
-                points.push(*r);
-                scalars.push(rand_base_i_c_i * u);
+            rand_base_i *= &rand_base;
+            sg_rand_base_i *= &sg_rand_base;
+        }
+
+        // verify the equation
+        self.multi_scalar_mul(&points, &scalars) == G::Projective::zero()
+    }
+
+    /// Adds the terms of the check of an opening proof to a multi-scalar multiplication,
+    /// scaled by `rand_base_i`.
+    /// The `sg` component of the proof is used in place of the commitment to the "s" array,
+    /// and is returned (along with the challenges of the proof) without being checked.
+    /// The terms are all of size logarithmic in the size of the SRS.
+    pub(crate) fn succinct_check_terms<EFqSponge>(
+        &self,
+        group_map: &G::Map,
+        proof: &mut BatchEvaluationProof<G, EFqSponge>,
+        rand_base_i: ScalarField<G>,
+        points: &mut Vec<G>,
+        scalars: &mut Vec<ScalarField<G>>,
+    ) -> Accumulator<G>
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+        G::BaseField: PrimeField,
+    {
+        let BatchEvaluationProof {
+            sponge,
+            evaluation_points,
+            xi,
+            r,
+            evaluations,
+            opening,
+        } = proof;
+
+        // the length of the prefix of the SRS that was used to create the proof
+        let srs_length = std::cmp::min(1 << opening.lr.len(), self.g.len());
+
+        // TODO: This computation is repeated in ProverProof::oracles
+        let combined_inner_product0 = {
+            let es: Vec<_> = evaluations
+                .iter()
+                .map(
+                    |Evaluation {
+                         commitment,
+                         evaluations,
+                         degree_bound,
+                     }| {
+                        let bound: Option<usize> = (|| {
+                            let b = (*degree_bound)?;
+                            let x = commitment.shifted?;
+                            if x.is_zero() {
+                                None
+                            } else {
+                                Some(b)
+                            }
+                        })();
+                        (evaluations.clone(), bound)
+                    },
+                )
+                .collect();
+            combined_inner_product::<G>(evaluation_points, xi, r, &es, srs_length)
+        };
+
+        sponge.absorb_fr(&[shift_scalar::<G>(combined_inner_product0)]);
+
+        let t = sponge.challenge_fq();
+        let u: G = to_group(group_map, t);
+
+        let Challenges { chal, chal_inv } = opening.challenges::<EFqSponge>(&self.endo_r, sponge);
+
+        sponge.absorb_g(&[opening.delta]);
+        let c = ScalarChallenge(sponge.challenge()).to_field(&self.endo_r);
+
+        // < s, sum_i r^i pows(evaluation_point[i]) >
+        // ==
+        // sum_i r^i < s, pows(evaluation_point[i]) >
+        let b0 = {
+            let mut scale = ScalarField::<G>::one();
+            let mut res = ScalarField::<G>::zero();
+            for &e in evaluation_points.iter() {
+                let term = b_poly(&chal, e);
+                res += &(scale * term);
+                scale *= *r;
             }
+            res
+        };
 
-            // TERM
-            // sum_j r^j (sum_i xi^i f_i) (elm_j)
-            // == sum_j sum_i r^j xi^i f_i(elm_j)
-            // == sum_i xi^i sum_j r^j f_i(elm_j)
+        let neg_rand_base_i = -rand_base_i;
+
+        // TERM
+        // - rand_base_i z1 G
+        //
+        // where G is given by the sg component of the proof
+        points.push(opening.sg);
+        scalars.push(neg_rand_base_i * opening.z1);
+
+        // TERM
+        // - rand_base_i * z2 * H
+        points.push(self.h);
+        scalars.push(neg_rand_base_i * opening.z2);
+
+        // TERM
+        // -rand_base_i * (z1 * b0 * U)
+        scalars.push(neg_rand_base_i * (opening.z1 * b0));
+        points.push(u);
+
+        // TERM
+        // rand_base_i c_i Q_i
+        // = rand_base_i c_i
+        //   (sum_j (chal_invs[j] L_j + chals[j] R_j) + P_prime)
+        // where P_prime = combined commitment + combined_inner_product * U
+        let rand_base_i_c_i = c * rand_base_i;
+        for ((l, r), (u_inv, u)) in opening.lr.iter().zip(chal_inv.iter().zip(chal.iter())) {
+            points.push(*l);
+            scalars.push(rand_base_i_c_i * u_inv);
+
+            points.push(*r);
+            scalars.push(rand_base_i_c_i * u);
+        }
+
+        // TERM
+        // sum_j r^j (sum_i xi^i f_i) (elm_j)
+        // == sum_j sum_i r^j xi^i f_i(elm_j)
+        // == sum_i xi^i sum_j r^j f_i(elm_j)
+        {
+            let mut xi_i = ScalarField::<G>::one();
+
+            for Evaluation {
+                commitment,
+                degree_bound,
+                ..
+            } in evaluations
+                .iter()
+                .filter(|x| !x.commitment.unshifted.is_empty())
             {
-                let mut xi_i = ScalarField::<G>::one();
+                // iterating over the polynomial segments
+                for comm_ch in commitment.unshifted.iter() {
+                    scalars.push(rand_base_i_c_i * xi_i);
+                    points.push(*comm_ch);
 
-                for Evaluation {
-                    commitment,
-                    degree_bound,
-                    ..
-                } in evaluations
-                    .iter()
-                    .filter(|x| !x.commitment.unshifted.is_empty())
-                {
-                    // iterating over the polynomial segments
-                    for comm_ch in commitment.unshifted.iter() {
-                        scalars.push(rand_base_i_c_i * xi_i);
-                        points.push(*comm_ch);
-
-                        xi_i *= *xi;
-                    }
+                    xi_i *= *xi;
+                }
 
-                    if let Some(_m) = degree_bound {
-                        if let Some(comm_ch) = commitment.shifted {
-                            if !comm_ch.is_zero() {
-                                // xi^i sum_j r^j elm_j^{N - m} f(elm_j)
-                                scalars.push(rand_base_i_c_i * xi_i);
-                                points.push(comm_ch);
+                if let Some(_m) = degree_bound {
+                    if let Some(comm_ch) = commitment.shifted {
+                        if !comm_ch.is_zero() {
+                            // xi^i sum_j r^j elm_j^{N - m} f(elm_j)
+                            scalars.push(rand_base_i_c_i * xi_i);
+                            points.push(comm_ch);
 
-                                xi_i *= *xi;
-                            }
+                            xi_i *= *xi;
                         }
                     }
                 }
-            };
+            }
+        };
 
-            scalars.push(rand_base_i_c_i * combined_inner_product0);
-            points.push(u);
+        scalars.push(rand_base_i_c_i * combined_inner_product0);
+        points.push(u);
 
-            scalars.push(rand_base_i);
-            points.push(opening.delta);
+        scalars.push(rand_base_i);
+        points.push(opening.delta);
 
-            rand_base_i *= &rand_base;
-            sg_rand_base_i *= &sg_rand_base;
+        Accumulator {
+            challenges: chal,
+            sg: opening.sg,
         }
-
-        // verify the equation
-        let scalars: Vec<_> = scalars.iter().map(|x| x.into_repr()).collect();
-        VariableBaseMSM::multi_scalar_mul(&points, &scalars) == G::Projective::zero()
     }
 }
 
@@ -876,6 +1006,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_chunked_evaluations_commitments() {
+        let n = 64;
+        let domain = D::<Fp>::new(n).unwrap();
+        let domain4 = D::<Fp>::new(4 * n).unwrap();
+
+        // the SRS is too small for the lagrange bases of the domain
+        let srs = SRS::<VestaG>::create(n / 4);
+
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let p = DensePolynomial::<Fp>::rand(n - 1, &mut rng);
+        let expected = srs.commit_non_hiding(&p, None);
+        assert_eq!(expected.unshifted.len(), 4);
+
+        for d in [domain, domain4] {
+            let evals = p.evaluate_over_domain_by_ref(d);
+            let c = srs.commit_evaluations_non_hiding(domain, &evals, None);
+            assert_eq!(c.unshifted, expected.unshifted);
+            assert!(c.shifted.is_none());
+        }
+    }
+
     #[test]
     fn test_opening_proof() {
         // create two polynomials
@@ -10,6 +10,8 @@ use o1_utils::{
 use oracle::{sponge::ScalarChallenge, FqSponge};
 use rand_core::{CryptoRng, RngCore};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use std::iter::Iterator;
 
 enum OptShiftedPolynomial<P> {
@@ -66,6 +68,71 @@ impl<'a, F: Field> ScaledChunkedPolynomial<F, &'a [F]> {
     }
 }
 
+/// Combines the polynomials of a batch into a single polynomial `sum_i polyscale^i p_i`,
+/// where each polynomial is split in segments of `srs_length` coefficients,
+/// and where the last segment of a polynomial with a degree bound is also mixed in shifted to the right edge of the SRS.
+/// Also returns the combined blinding factor of the commitments, scaled in the same way.
+#[allow(clippy::type_complexity)]
+pub(crate) fn combine_polys<G: CommitmentCurve>(
+    plnms: &[(
+        &DensePolynomial<ScalarField<G>>,
+        Option<usize>,
+        PolyComm<ScalarField<G>>,
+    )],
+    polyscale: ScalarField<G>,
+    srs_length: usize,
+) -> (DensePolynomial<ScalarField<G>>, ScalarField<G>) {
+    let mut plnm = ScaledChunkedPolynomial::<ScalarField<G>, &[ScalarField<G>]>::default();
+
+    let mut omega = ScalarField::<G>::zero();
+    let mut scale = ScalarField::<G>::one();
+
+    // iterating over polynomials in the batch
+    // a polynomial is combined in as many chunks as its commitment, which can have more chunks than the polynomial
+    // (e.g. if it is zero, or padded to a number of chunks): the missing segments are zero
+    for (p_i, degree_bound, omegas) in plnms.iter() {
+        let mut offset = 0;
+        let mut j = 0;
+        // iterating over chunks of the polynomial
+        if let Some(m) = degree_bound {
+            assert!(p_i.coeffs.len() <= m + 1);
+            while j < omegas.unshifted.len() {
+                let segment = &p_i.coeffs[std::cmp::min(offset, p_i.coeffs.len())
+                    ..std::cmp::min(offset + srs_length, p_i.coeffs.len())];
+                // always mixing in the unshifted segments
+                plnm.add_unshifted(scale, segment);
+
+                omega += &(omegas.unshifted[j] * scale);
+                j += 1;
+                scale *= &polyscale;
+                offset += srs_length;
+                if offset > *m {
+                    // mixing in the shifted segment since degree is bounded
+                    plnm.add_shifted(scale, srs_length - m % srs_length, segment);
+                    omega += &(omegas.shifted.unwrap() * scale);
+                    scale *= &polyscale;
+                }
+            }
+        } else {
+            assert!(omegas.shifted.is_none());
+            while j < omegas.unshifted.len() {
+                let segment = &p_i.coeffs[std::cmp::min(offset, p_i.coeffs.len())
+                    ..std::cmp::min(offset + srs_length, p_i.coeffs.len())];
+
+                // always mixing in the unshifted segments
+                plnm.add_unshifted(scale, segment);
+                omega += &(omegas.unshifted[j] * scale);
+                j += 1;
+                scale *= &polyscale;
+                offset += srs_length;
+            }
+        }
+        assert_eq!(j, omegas.unshifted.len());
+    }
+
+    (plnm.to_dense_polynomial(), omega)
+}
+
 impl<G: CommitmentCurve> SRS<G> {
     /// This function opens polynomial commitments in batch
     ///     plnms: batch of polynomials to open commitments for with, optionally, max degrees
@@ -97,74 +164,9 @@ impl<G: CommitmentCurve> SRS<G> {
         RNG: RngCore + CryptoRng,
         G::BaseField: PrimeField,
     {
-        let rounds = math::ceil_log2(self.g.len());
-        let padded_length = 1 << rounds;
-
-        // TODO: Trim this to the degree of the largest polynomial
-        let padding = padded_length - self.g.len();
-        let mut g = self.g.clone();
-        g.extend(vec![G::zero(); padding]);
-
-        let (p, blinding_factor) = {
-            let mut plnm = ScaledChunkedPolynomial::<ScalarField<G>, &[ScalarField<G>]>::default();
-            // let mut plnm_chunks: Vec<(ScalarField<G>, OptShiftedPolynomial<_>)> = vec![];
-
-            let mut omega = ScalarField::<G>::zero();
-            let mut scale = ScalarField::<G>::one();
-
-            // iterating over polynomials in the batch
-            for (p_i, degree_bound, omegas) in plnms.iter().filter(|p| !p.0.is_zero()) {
-                let mut offset = 0;
-                let mut j = 0;
-                // iterating over chunks of the polynomial
-                if let Some(m) = degree_bound {
-                    assert!(p_i.coeffs.len() <= m + 1);
-                    while j < omegas.unshifted.len() {
-                        let segment = &p_i.coeffs[offset
-                            ..if offset + self.g.len() > p_i.coeffs.len() {
-                                p_i.coeffs.len()
-                            } else {
-                                offset + self.g.len()
-                            }];
-                        // always mixing in the unshifted segments
-                        plnm.add_unshifted(scale, segment);
-
-                        omega += &(omegas.unshifted[j] * scale);
-                        j += 1;
-                        scale *= &polyscale;
-                        offset += self.g.len();
-                        if offset > *m {
-                            // mixing in the shifted segment since degree is bounded
-                            plnm.add_shifted(scale, self.g.len() - m % self.g.len(), segment);
-                            omega += &(omegas.shifted.unwrap() * scale);
-                            scale *= &polyscale;
-                        }
-                    }
-                } else {
-                    assert!(omegas.shifted.is_none());
-                    while j < omegas.unshifted.len() {
-                        let segment = &p_i.coeffs[offset
-                            ..if offset + self.g.len() > p_i.coeffs.len() {
-                                p_i.coeffs.len()
-                            } else {
-                                offset + self.g.len()
-                            }];
-
-                        // always mixing in the unshifted segments
-                        plnm.add_unshifted(scale, segment);
-                        omega += &(omegas.unshifted[j] * scale);
-                        j += 1;
-                        scale *= &polyscale;
-                        offset += self.g.len();
-                    }
-                }
-                assert_eq!(j, omegas.unshifted.len());
-            }
-
-            (plnm.to_dense_polynomial(), omega)
-        };
+        let padded_length = 1 << math::ceil_log2(self.g.len());
 
-        let rounds = math::ceil_log2(self.g.len());
+        let (p, blinding_factor) = combine_polys::<G>(plnms, polyscale, self.g.len());
 
         // b_j = sum_i r^i elm_i^j
         let b_init = {
@@ -182,23 +184,46 @@ impl<G: CommitmentCurve> SRS<G> {
             res
         };
 
-        let combined_inner_product = p
-            .coeffs
-            .iter()
-            .zip(b_init.iter())
-            .map(|(a, b)| *a * b)
-            .fold(ScalarField::<G>::zero(), |acc, x| acc + x);
+        self.prove_inner_product(group_map, p.coeffs, b_init, blinding_factor, sponge, rng)
+    }
 
-        sponge.absorb_fr(&[shift_scalar::<G>(combined_inner_product)]);
+    /// Runs the inner product argument between the vector `a`,
+    /// committed over the SRS with the blinding factor `blinding_factor`,
+    /// and the public vector `b`.
+    /// Both vectors are padded with zeros to the next power of two of the size of the SRS.
+    pub(crate) fn prove_inner_product<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        mut a: Vec<ScalarField<G>>,
+        mut b: Vec<ScalarField<G>>,
+        blinding_factor: ScalarField<G>,
+        mut sponge: EFqSponge,
+        rng: &mut RNG,
+    ) -> OpeningProof<G>
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+        RNG: RngCore + CryptoRng,
+        G::BaseField: PrimeField,
+    {
+        let rounds = math::ceil_log2(self.g.len());
+        let padded_length = 1 << rounds;
 
-        let t = sponge.challenge_fq();
-        let u: G = to_group(group_map, t);
+        // TODO: Trim this to the degree of the largest polynomial
+        let padding = padded_length - self.g.len();
+        let mut g = self.g.clone();
+        g.extend(vec![G::zero(); padding]);
 
-        let mut a = p.coeffs;
         assert!(padded_length >= a.len());
         a.extend(vec![ScalarField::<G>::zero(); padded_length - a.len()]);
+        assert!(padded_length >= b.len());
+        b.extend(vec![ScalarField::<G>::zero(); padded_length - b.len()]);
+
+        let combined_inner_product = inner_prod(&a, &b);
+
+        sponge.absorb_fr(&[shift_scalar::<G>(combined_inner_product)]);
 
-        let mut b = b_init;
+        let t = sponge.challenge_fq();
+        let u: G = to_group(group_map, t);
 
         let mut lr = vec![];
 
@@ -310,13 +335,19 @@ impl<G: CommitmentCurve> SRS<G> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OpeningProof<G: AffineCurve> {
     /// vector of rounds of L & R commitments
+    #[serde_as(as = "Vec<(o1_utils::serialization::SerdeAs, o1_utils::serialization::SerdeAs)>")]
     pub lr: Vec<(G, G)>,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub delta: G,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub z1: G::ScalarField,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub z2: G::ScalarField,
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
     pub sg: G,
 }
 
@@ -365,3 +396,124 @@ impl<G: AffineCurve> OpeningProof<G> {
         Challenges { chal, chal_inv }
     }
 }
+
+/// The result of the succinct check of an opening proof:
+/// the challenges of the inner product argument and the claimed commitment `sg` to the
+/// polynomial `b_poly_coefficients(challenges)`.
+/// Checking `sg` is the only expensive (linear in the size of the SRS) part of the verification,
+/// it can be deferred and batched with other accumulators (see [SRS::decide]),
+/// or passed to a recursive proof (see [Accumulator::to_prev_challenges]).
+#[derive(Clone, Debug)]
+pub struct Accumulator<G: AffineCurve> {
+    /// the challenges of the inner product argument
+    pub challenges: Vec<G::ScalarField>,
+    /// the claimed commitment to the polynomial whose coefficients are derived from the challenges
+    pub sg: G,
+}
+
+impl<G: CommitmentCurve> Accumulator<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Converts the accumulator into a previous challenge,
+    /// as expected by a recursive prover using the given SRS.
+    /// The commitment of the previous challenge is `sg`, which is left to be checked by the recursive proof.
+    /// Returns `None` if the accumulator was created with a larger SRS,
+    /// in which case its commitment does not fit in a single chunk of the given SRS:
+    /// such an accumulator must be checked with [SRS::decide] instead.
+    pub fn to_prev_challenges(&self, srs: &SRS<G>) -> Option<(Vec<ScalarField<G>>, PolyComm<G>)> {
+        if 1 << self.challenges.len() > srs.g.len() {
+            return None;
+        }
+        let comm = PolyComm {
+            unshifted: vec![self.sg],
+            shifted: None,
+        };
+        Some((self.challenges.clone(), comm))
+    }
+}
+
+impl<G: CommitmentCurve> SRS<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Verifies a batched opening proof, except for its `sg` component
+    /// which is returned as part of an [Accumulator].
+    /// The cost of this check is logarithmic in the size of the SRS.
+    /// The accumulator must later be checked with [SRS::decide].
+    /// Returns `None` if the proof does not verify.
+    pub fn succinct_check<EFqSponge>(
+        &self,
+        group_map: &G::Map,
+        proof: &mut BatchEvaluationProof<G, EFqSponge>,
+    ) -> Option<Accumulator<G>>
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+    {
+        if proof.opening.lr.len() > math::ceil_log2(self.g.len()) {
+            return None;
+        }
+
+        let mut points = vec![];
+        let mut scalars = vec![];
+        let accumulator = self.succinct_check_terms(
+            group_map,
+            proof,
+            ScalarField::<G>::one(),
+            &mut points,
+            &mut scalars,
+        );
+
+        if self.multi_scalar_mul(&points, &scalars) == G::Projective::zero() {
+            Some(accumulator)
+        } else {
+            None
+        }
+    }
+
+    /// Checks that the `sg` component of each accumulator is the commitment
+    /// to the polynomial derived from its challenges,
+    /// using a single multi-scalar multiplication for all the accumulators.
+    /// As with [SRS::verify], the accumulators can come from proofs created with a prefix of this SRS.
+    pub fn decide<RNG>(&self, accumulators: &[Accumulator<G>], rng: &mut RNG) -> bool
+    where
+        RNG: RngCore + CryptoRng,
+    {
+        let nonzero_length = self.g.len();
+        let max_rounds = math::ceil_log2(nonzero_length);
+        if accumulators
+            .iter()
+            .any(|acc| acc.challenges.len() > max_rounds)
+        {
+            return false;
+        }
+
+        let mut points = self.g.clone();
+        let mut scalars = vec![ScalarField::<G>::zero(); nonzero_length];
+
+        // check that sum_i r^i (< s_i, self.g > - sg_i) == 0
+        let rand_base = ScalarField::<G>::rand(rng);
+        let mut rand_base_i = ScalarField::<G>::one();
+
+        for Accumulator { challenges, sg } in accumulators {
+            // the length of the prefix of the SRS that was used to create the proof
+            let srs_length = std::cmp::min(1 << challenges.len(), nonzero_length);
+
+            let s = b_poly_coefficients(challenges);
+            let terms: Vec<_> = s[..srs_length]
+                .par_iter()
+                .map(|s| rand_base_i * s)
+                .collect();
+            for (scalar, term) in scalars.iter_mut().zip(terms) {
+                *scalar += term;
+            }
+
+            points.push(*sg);
+            scalars.push(-rand_base_i);
+
+            rand_base_i *= &rand_base;
+        }
+
+        self.multi_scalar_mul(&points, &scalars) == G::Projective::zero()
+    }
+}
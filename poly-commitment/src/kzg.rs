@@ -0,0 +1,382 @@
+//! This module implements the KZG polynomial commitment scheme over a pairing-friendly curve.
+//! Its opening proofs are made of one group element per evaluation point,
+//! and are checked with two pairings, whatever the size of the polynomials.
+//! Unlike the inner product argument, it requires a trusted setup,
+//! and its openings cannot be accumulated in a recursive proof.
+//!
+//! The commitments are not hiding: the blinding factors returned by the scheme are always zero.
+
+use crate::{
+    commitment::{combined_inner_product, BatchEvaluationProof, CommitmentCurve, Evaluation},
+    evaluation_proof::combine_polys,
+    pcs::{check_points, OpeningProofError, PolynomialCommitment},
+    srs::{endos, SRS},
+    PolyComm,
+};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
+    UVPolynomial,
+};
+use o1_utils::types::fields::*;
+use oracle::FqSponge;
+use rand_core::{CryptoRng, RngCore};
+use rayon::prelude::*;
+
+/// The structured reference string of the KZG scheme,
+/// made of the powers of a secret `tau` in the first group, and of `tau` in the second group.
+#[derive(Debug, Clone)]
+pub struct KzgSrs<E: PairingEngine> {
+    /// The powers `[1, tau, tau^2, ...] * G` of the secret, for committing to polynomials in coefficient form
+    pub g: Vec<E::G1Affine>,
+    /// The generator `H` of the second group
+    pub h: E::G2Affine,
+    /// The secret in the second group, `tau * H`
+    pub tau_h: E::G2Affine,
+    /// The coefficient of the endomorphism of the first group in the scalar field
+    pub endo_r: E::Fr,
+}
+
+/// An empty setup, to be replaced after deserializing the indexes that do not contain it.
+impl<E: PairingEngine> Default for KzgSrs<E> {
+    fn default() -> Self {
+        KzgSrs {
+            g: vec![],
+            h: E::G2Affine::zero(),
+            tau_h: E::G2Affine::zero(),
+            endo_r: E::Fr::zero(),
+        }
+    }
+}
+
+/// An opening proof of the KZG scheme.
+#[derive(Debug, Clone)]
+pub struct KzgProof<E: PairingEngine> {
+    /// For each evaluation point `z`, the commitment to `(p(X) - p(z)) / (X - z)`,
+    /// where `p` is the combination of the opened polynomials
+    pub quotients: Vec<E::G1Affine>,
+}
+
+impl<E: PairingEngine> KzgSrs<E>
+where
+    E::G1Affine: CommitmentCurve,
+    <E::G1Affine as AffineCurve>::BaseField: PrimeField,
+{
+    /// Creates a setup for polynomials of up to `depth` coefficients from a secret sampled with `rng`.
+    ///
+    /// Whoever runs this function can learn the secret, and forge opening proofs with it:
+    /// this setup is only meant for tests. A real deployment must use the output of a setup ceremony.
+    pub fn create_insecure<RNG: RngCore + CryptoRng>(depth: usize, rng: &mut RNG) -> Self {
+        let tau = E::Fr::rand(rng);
+
+        let mut powers = Vec::with_capacity(depth);
+        let mut tau_i = E::Fr::one();
+        for _ in 0..depth {
+            powers.push(tau_i);
+            tau_i *= tau;
+        }
+
+        let generator = E::G1Affine::prime_subgroup_generator();
+        let g: Vec<_> = powers
+            .par_iter()
+            .map(|tau_i| generator.mul(*tau_i))
+            .collect();
+        let g = E::G1Projective::batch_normalization_into_affine(&g);
+
+        let h = E::G2Affine::prime_subgroup_generator();
+        let tau_h = h.mul(tau).into_affine();
+
+        let (_, endo_r) = endos::<E::G1Affine>();
+
+        KzgSrs {
+            g,
+            h,
+            tau_h,
+            endo_r,
+        }
+    }
+}
+
+/// Divides `p` by `X - z`, dropping the remainder `p(z)`.
+fn divide_by_linear<F: PrimeField>(p: &[F], z: F) -> Vec<F> {
+    if p.is_empty() {
+        return vec![];
+    }
+    // q_{i-1} = p_i + z q_i
+    let mut q = vec![F::zero(); p.len() - 1];
+    let mut acc = F::zero();
+    for i in (1..p.len()).rev() {
+        acc = p[i] + z * acc;
+        q[i - 1] = acc;
+    }
+    q
+}
+
+impl<E: PairingEngine> PolynomialCommitment<E::G1Affine> for KzgSrs<E>
+where
+    E::G1Affine: CommitmentCurve,
+{
+    type OpeningProof = KzgProof<E>;
+
+    fn max_poly_size(&self) -> usize {
+        self.g.len()
+    }
+
+    fn endo_r(&self) -> &E::Fr {
+        &self.endo_r
+    }
+
+    /// The polynomials are committed in chunks of the size of the setup,
+    /// so only a setup of the same size can verify the opening proofs.
+    fn is_compatible_with(&self, larger: &Self) -> bool {
+        self.g == larger.g && self.h == larger.h && self.tau_h == larger.tau_h
+    }
+
+    /// The commitments are not blinded.
+    fn is_hiding(&self) -> bool {
+        false
+    }
+
+    fn commit_non_hiding(
+        &self,
+        plnm: &DensePolynomial<E::Fr>,
+        max: Option<usize>,
+    ) -> PolyComm<E::G1Affine> {
+        SRS::<E::G1Affine>::commit_helper(&plnm.coeffs[..], &self.g[..], None, plnm.is_zero(), max)
+    }
+
+    fn commit(
+        &self,
+        plnm: &DensePolynomial<E::Fr>,
+        max: Option<usize>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PolyComm<E::G1Affine>, PolyComm<E::Fr>) {
+        self.mask(self.commit_non_hiding(plnm, max), rng)
+    }
+
+    /// The commitments are left as is, with zero blinding factors.
+    fn mask(
+        &self,
+        comm: PolyComm<E::G1Affine>,
+        _rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PolyComm<E::G1Affine>, PolyComm<E::Fr>) {
+        let blinders = comm.map(|_| E::Fr::zero());
+        (comm, blinders)
+    }
+
+    fn commit_blinded_zero(&self, _rng: &mut (impl RngCore + CryptoRng)) -> (E::G1Affine, E::Fr) {
+        (E::G1Affine::zero(), E::Fr::zero())
+    }
+
+    /// Interpolates the evaluations over `domain`, and commits to the resulting polynomial.
+    fn commit_evaluations_non_hiding(
+        &self,
+        domain: D<E::Fr>,
+        plnm: &Evaluations<E::Fr, D<E::Fr>>,
+        max: Option<usize>,
+    ) -> PolyComm<E::G1Affine> {
+        let evals = match domain.size.cmp(&plnm.domain().size) {
+            std::cmp::Ordering::Less => {
+                let s = (plnm.domain().size / domain.size) as usize;
+                (0..domain.size()).map(|i| plnm.evals[s * i]).collect()
+            }
+            std::cmp::Ordering::Equal => plnm.evals.clone(),
+            std::cmp::Ordering::Greater => {
+                panic!("desired commitment domain size greater than evaluations' domain size")
+            }
+        };
+        let p = Evaluations::from_vec_and_domain(evals, domain).interpolate();
+        self.commit_non_hiding(&p, max)
+    }
+
+    fn commit_evaluations(
+        &self,
+        domain: D<E::Fr>,
+        plnm: &Evaluations<E::Fr, D<E::Fr>>,
+        max: Option<usize>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PolyComm<E::G1Affine>, PolyComm<E::Fr>) {
+        self.mask(self.commit_evaluations_non_hiding(domain, plnm, max), rng)
+    }
+
+    /// The polynomials are combined as in the inner product argument,
+    /// and the combination is opened at each point with its own quotient.
+    /// As the proof does not depend on any challenge, `evalscale` and the sponge are not used.
+    fn open<EFqSponge, RNG>(
+        &self,
+        _group_map: &<E::G1Affine as CommitmentCurve>::Map,
+        plnms: &[(&DensePolynomial<E::Fr>, Option<usize>, PolyComm<E::Fr>)],
+        elm: &[E::Fr],
+        polyscale: E::Fr,
+        _evalscale: E::Fr,
+        _sponge: EFqSponge,
+        _rng: &mut RNG,
+    ) -> KzgProof<E>
+    where
+        EFqSponge: Clone + FqSponge<BaseField<E::G1Affine>, E::G1Affine, E::Fr>,
+        RNG: RngCore + CryptoRng,
+        <E::G1Affine as AffineCurve>::BaseField: PrimeField,
+    {
+        let (p, blinding_factor) = combine_polys::<E::G1Affine>(plnms, polyscale, self.g.len());
+        assert!(
+            blinding_factor.is_zero(),
+            "KZG commitments cannot be opened with non-zero blinding factors"
+        );
+
+        let quotients = elm
+            .par_iter()
+            .map(|z| {
+                let q = divide_by_linear(&p.coeffs, *z);
+                let scalars: Vec<_> = q.iter().map(|x| x.into_repr()).collect();
+                VariableBaseMSM::multi_scalar_mul(&self.g[..q.len()], &scalars).into_affine()
+            })
+            .collect();
+
+        KzgProof { quotients }
+    }
+
+    /// The proof has one quotient per evaluation point, which is only checked when verifying it.
+    fn check_opening_proof(&self, proof: &KzgProof<E>) -> Result<(), OpeningProofError> {
+        check_points("opening proof", &proof.quotients)
+    }
+
+    /// Checks, for each proof and each evaluation point `z` with combined evaluation `y`, that
+    /// `e(C - y G + z Q, H) == e(Q, tau H)`,
+    /// where `C` is the combined commitment and `Q` the commitment to the quotient.
+    /// All the checks are combined with random powers, so that only two pairings are computed.
+    fn verify<EFqSponge, RNG>(
+        &self,
+        _group_map: &<E::G1Affine as CommitmentCurve>::Map,
+        batch: &mut [BatchEvaluationProof<E::G1Affine, EFqSponge, KzgProof<E>>],
+        rng: &mut RNG,
+    ) -> bool
+    where
+        EFqSponge: FqSponge<BaseField<E::G1Affine>, E::G1Affine, E::Fr>,
+        RNG: RngCore + CryptoRng,
+        <E::G1Affine as AffineCurve>::BaseField: PrimeField,
+    {
+        let srs_length = self.g.len();
+
+        // terms of sum_i r^i (C_i - y_i G + z_i Q_i)
+        let mut points = vec![self.g[0]];
+        let mut scalars = vec![E::Fr::zero()];
+        // terms of sum_i r^i Q_i
+        let mut quotients = vec![];
+        let mut quotient_scalars = vec![];
+
+        let rand_base = E::Fr::rand(rng);
+        let mut rand_base_i = E::Fr::one();
+
+        for proof in batch.iter() {
+            let BatchEvaluationProof {
+                evaluation_points,
+                xi,
+                r,
+                evaluations,
+                opening,
+                ..
+            } = proof;
+
+            if opening.quotients.len() != evaluation_points.len()
+                || evaluations
+                    .iter()
+                    .any(|e| e.evaluations.len() != evaluation_points.len())
+            {
+                return false;
+            }
+
+            // the commitments, with the same scaling as the polynomials when opening them
+            let mut combined_commitment = vec![];
+            {
+                let mut xi_i = E::Fr::one();
+                for Evaluation {
+                    commitment,
+                    degree_bound,
+                    ..
+                } in evaluations
+                    .iter()
+                    .filter(|x| !x.commitment.unshifted.is_empty())
+                {
+                    for comm_ch in commitment.unshifted.iter() {
+                        combined_commitment.push((*comm_ch, xi_i));
+                        xi_i *= *xi;
+                    }
+
+                    if let Some(_m) = degree_bound {
+                        if let Some(comm_ch) = commitment.shifted {
+                            if !comm_ch.is_zero() {
+                                combined_commitment.push((comm_ch, xi_i));
+                                xi_i *= *xi;
+                            }
+                        }
+                    }
+                }
+            }
+
+            for (j, (z, quotient)) in evaluation_points
+                .iter()
+                .zip(opening.quotients.iter())
+                .enumerate()
+            {
+                // the evaluation of the combined polynomial at z
+                let es: Vec<_> = evaluations
+                    .iter()
+                    .map(
+                        |Evaluation {
+                             commitment,
+                             evaluations,
+                             degree_bound,
+                         }| {
+                            let bound = match (degree_bound, commitment.shifted) {
+                                (Some(b), Some(x)) if !x.is_zero() => Some(*b),
+                                _ => None,
+                            };
+                            (vec![evaluations[j].clone()], bound)
+                        },
+                    )
+                    .collect();
+                let y = combined_inner_product::<E::G1Affine>(&[*z], xi, r, &es, srs_length);
+
+                for (comm_ch, scale) in combined_commitment.iter() {
+                    points.push(*comm_ch);
+                    scalars.push(rand_base_i * scale);
+                }
+                scalars[0] -= rand_base_i * y;
+                points.push(*quotient);
+                scalars.push(rand_base_i * z);
+
+                quotients.push(*quotient);
+                quotient_scalars.push(rand_base_i.into_repr());
+
+                rand_base_i *= rand_base;
+            }
+        }
+
+        let scalars: Vec<_> = scalars.iter().map(|x| x.into_repr()).collect();
+        let lhs = VariableBaseMSM::multi_scalar_mul(&points, &scalars).into_affine();
+        let rhs = VariableBaseMSM::multi_scalar_mul(&quotients, &quotient_scalars).into_affine();
+
+        E::pairing(lhs, self.h) == E::pairing(rhs, self.tau_h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_poly::Polynomial;
+
+    #[test]
+    fn test_divide_by_linear() {
+        let mut rng = rand::thread_rng();
+        let p = DensePolynomial::<Fr>::rand(10, &mut rng);
+        let z = Fr::rand(&mut rng);
+
+        let q = DensePolynomial::from_coefficients_vec(divide_by_linear(&p.coeffs, z));
+        let x_minus_z = DensePolynomial::from_coefficients_vec(vec![-z, Fr::one()]);
+        let y = DensePolynomial::from_coefficients_vec(vec![p.evaluate(&z)]);
+
+        assert_eq!(&(&q * &x_minus_z) + &y, p);
+    }
+}
@@ -2,6 +2,11 @@ pub mod chunked;
 mod combine;
 pub mod commitment;
 pub mod evaluation_proof;
+pub mod kzg;
+pub mod msm;
+pub mod pcs;
 pub mod srs;
+pub mod vector_commitment;
 
 pub use commitment::PolyComm;
+pub use pcs::PolynomialCommitment;
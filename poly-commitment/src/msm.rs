@@ -0,0 +1,416 @@
+//! Multi-scalar multiplication using the endomorphism of the curve.
+//!
+//! The curves of the Pasta cycle have an endomorphism `phi(x, y) = (endo_q x, y)`,
+//! which acts on the points as the multiplication by a scalar `lambda` (the `endo_r` of [crate::srs::endos]).
+//! Following Gallant, Lambert and Vanstone (GLV), each scalar `k` is decomposed as `k = k1 + k2 lambda`
+//! with `k1` and `k2` of about half the size of `k`,
+//! so that `k P = k1 P + k2 phi(P)` is a multi-scalar multiplication with twice as many points but half-size scalars.
+//!
+//! The multi-scalar multiplication itself uses the bucket method of Pippenger,
+//! where the points of each bucket are summed in affine coordinates (see [crate::combine]).
+
+use crate::combine::{add_denominator, add_with_inverse};
+use ark_ec::{
+    models::short_weierstrass_jacobian::{GroupAffine as SWJAffine, GroupProjective},
+    AffineCurve, ProjectiveCurve, SWModelParameters,
+};
+use ark_ff::{BigInteger, FpParameters, PrimeField, Zero};
+use num_bigint::{BigInt, BigUint, Sign};
+use o1_utils::math;
+use rayon::prelude::*;
+
+/// The algorithm used to compute multi-scalar multiplications
+/// (see [SRS::set_msm_algorithm](crate::srs::SRS::set_msm_algorithm)).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MsmAlgorithm {
+    /// The generic algorithm of arkworks
+    Arkworks,
+    /// The GLV method, for curves with an efficiently computable endomorphism
+    Glv,
+}
+
+impl Default for MsmAlgorithm {
+    fn default() -> Self {
+        MsmAlgorithm::Arkworks
+    }
+}
+
+/// The decomposition of scalars `k = k1 + k2 lambda`,
+/// computed from a short basis `(a1, b1), (a2, b2)` of the lattice `{(a, b) | a + b lambda = 0}`.
+#[derive(Clone, Debug)]
+pub struct GlvDecomposition<F: PrimeField> {
+    a1: F,
+    b1: F,
+    a2: F,
+    b2: F,
+    /// `round(b2 2^SHIFT / r)`, with its sign
+    g1: (bool, Vec<u64>),
+    /// `round(-b1 2^SHIFT / r)`, with its sign
+    g2: (bool, Vec<u64>),
+}
+
+/// The number of limbs by which the approximations of `b2 / r` and `-b1 / r` are shifted.
+fn shift_limbs<F: PrimeField>() -> usize {
+    F::BigInt::NUM_LIMBS + 1
+}
+
+fn to_biguint<F: PrimeField>(x: F) -> BigUint {
+    BigUint::from_bytes_le(&x.into_repr().to_bytes_le())
+}
+
+fn from_bigint<F: PrimeField>(x: &BigInt) -> F {
+    let (sign, bytes) = x.to_bytes_le();
+    let x = F::from_le_bytes_mod_order(&bytes);
+    if sign == Sign::Minus {
+        -x
+    } else {
+        x
+    }
+}
+
+/// Returns `round(x / r)` with its sign, as little-endian limbs.
+fn round_div(x: BigInt, r: &BigInt) -> (bool, Vec<u64>) {
+    let q = (x * 2 + r) / (r * 2);
+    let (sign, q) = q.into_parts();
+    (sign == Sign::Minus, q.to_u64_digits())
+}
+
+impl<F: PrimeField> GlvDecomposition<F> {
+    /// Computes the decomposition for the eigenvalue `lambda` of the endomorphism,
+    /// with the extended Euclidean algorithm (see Algorithm 3.74 of the Guide to Elliptic Curve Cryptography).
+    pub fn new(lambda: F) -> Self {
+        assert!(
+            !lambda.is_zero(),
+            "the eigenvalue of the endomorphism is zero"
+        );
+
+        let r = BigInt::from(BigUint::from_bytes_le(&F::Params::MODULUS.to_bytes_le()));
+        let sqrt_r = r.sqrt();
+
+        // the remainders r_i and the coefficients t_i, such that r_i = t_i lambda mod r
+        let mut rs = vec![r.clone(), BigInt::from(to_biguint(lambda))];
+        let mut ts = vec![BigInt::from(0), BigInt::from(1)];
+        while rs[rs.len() - 1].sign() != Sign::NoSign {
+            let (r0, r1) = (&rs[rs.len() - 2], &rs[rs.len() - 1]);
+            let q = r0 / r1;
+            let r2 = r0 - &q * r1;
+            let t2 = &ts[ts.len() - 2] - &q * &ts[ts.len() - 1];
+            rs.push(r2);
+            ts.push(t2);
+        }
+
+        // m is the largest index such that r_m >= sqrt(r),
+        // the basis is made of (r_{m+1}, -t_{m+1}) and of the shortest of (r_m, -t_m) and (r_{m+2}, -t_{m+2})
+        let m = rs.iter().rposition(|ri| *ri >= sqrt_r).unwrap();
+        let (a1, b1) = (rs[m + 1].clone(), -&ts[m + 1]);
+        let (a2, b2) = match rs.get(m + 2) {
+            Some(r2) if r2 * r2 + &ts[m + 2] * &ts[m + 2] < &rs[m] * &rs[m] + &ts[m] * &ts[m] => {
+                (r2.clone(), -&ts[m + 2])
+            }
+            _ => (rs[m].clone(), -&ts[m]),
+        };
+
+        let shift = BigInt::from(1) << (64 * shift_limbs::<F>());
+        let g1 = round_div(&b2 * &shift, &r);
+        let g2 = round_div(-&b1 * &shift, &r);
+
+        let res = GlvDecomposition {
+            a1: from_bigint(&a1),
+            b1: from_bigint(&b1),
+            a2: from_bigint(&a2),
+            b2: from_bigint(&b2),
+            g1,
+            g2,
+        };
+        assert!((res.a1 + res.b1 * lambda).is_zero());
+        assert!((res.a2 + res.b2 * lambda).is_zero());
+        res
+    }
+
+    /// Returns `round(k g / 2^SHIFT)`, where `g` is given with its sign.
+    fn round_mul_shift(k: &[u64], (negative, g): &(bool, Vec<u64>)) -> F {
+        let mut product = vec![0u64; k.len() + g.len() + 1];
+        for (i, ki) in k.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, gj) in g.iter().enumerate() {
+                let t = (*ki as u128) * (*gj as u128) + (product[i + j] as u128) + carry;
+                product[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            product[i + g.len()] = carry as u64;
+        }
+
+        let shift = shift_limbs::<F>();
+        let round_up = product[shift - 1] >> 63 == 1;
+        let two_64 = F::from(u64::MAX) + F::one();
+        let mut res = product[shift..]
+            .iter()
+            .rev()
+            .fold(F::zero(), |acc, limb| acc * two_64 + F::from(*limb));
+        if round_up {
+            res += F::one();
+        }
+        if *negative {
+            -res
+        } else {
+            res
+        }
+    }
+
+    /// Decomposes `k` as `k1 + k2 lambda`, and returns `k1` and `k2` as signed integers
+    /// (with a flag set when they are negative) of about half the size of the scalar field.
+    pub fn decompose(&self, k: F) -> ((bool, F::BigInt), (bool, F::BigInt)) {
+        let k_repr = k.into_repr();
+        let c1 = Self::round_mul_shift(k_repr.as_ref(), &self.g1);
+        let c2 = Self::round_mul_shift(k_repr.as_ref(), &self.g2);
+
+        // k1 + k2 lambda = k - c1 (a1 + b1 lambda) - c2 (a2 + b2 lambda) = k
+        let k1 = k - c1 * self.a1 - c2 * self.a2;
+        let k2 = -(c1 * self.b1 + c2 * self.b2);
+
+        let signed = |x: F| {
+            if x.into_repr() > F::Params::MODULUS_MINUS_ONE_DIV_TWO {
+                (true, (-x).into_repr())
+            } else {
+                (false, x.into_repr())
+            }
+        };
+        (signed(k1), signed(k2))
+    }
+}
+
+/// Returns the `c` bits of `limbs` starting at bit `shift`.
+fn get_bits(limbs: &[u64], shift: usize, c: usize) -> usize {
+    let (limb, offset) = (shift / 64, shift % 64);
+    if limb >= limbs.len() {
+        return 0;
+    }
+    let mut bits = limbs[limb] >> offset;
+    if offset + c > 64 && limb + 1 < limbs.len() {
+        bits |= limbs[limb + 1] << (64 - offset);
+    }
+    (bits & ((1 << c) - 1)) as usize
+}
+
+/// Sums the points of each bucket, adding all the pairs of points of all the buckets
+/// with a single batch inversion at each round.
+fn batch_sum_buckets<P: SWModelParameters>(buckets: &mut [Vec<SWJAffine<P>>]) -> Vec<SWJAffine<P>> {
+    let mut denominators = vec![];
+    loop {
+        denominators.clear();
+        for bucket in buckets.iter() {
+            for pair in bucket.chunks_exact(2) {
+                denominators.push(add_denominator(&pair[0], &pair[1]));
+            }
+        }
+        if denominators.is_empty() {
+            break;
+        }
+
+        ark_ff::batch_inversion::<P::BaseField>(&mut denominators);
+
+        let mut denominators = denominators.iter();
+        for bucket in buckets.iter_mut() {
+            let len = bucket.len();
+            for j in 0..len / 2 {
+                bucket[j] = add_with_inverse(
+                    &bucket[2 * j],
+                    &bucket[2 * j + 1],
+                    denominators.next().unwrap(),
+                );
+            }
+            if len % 2 == 1 {
+                bucket[len / 2] = bucket[len - 1];
+                bucket.truncate(len / 2 + 1);
+            } else {
+                bucket.truncate(len / 2);
+            }
+        }
+    }
+
+    buckets
+        .iter()
+        .map(|bucket| bucket.first().copied().unwrap_or_else(SWJAffine::<P>::zero))
+        .collect()
+}
+
+/// The natural logarithm of `a`, computed as arkworks does without floats:
+/// $\ln(a) = \log_2(a) \ln(2)$, with $\log_2(a)$ rounded up and $\ln(2) \approx 0.69$.
+fn ln_without_floats(a: usize) -> usize {
+    math::ceil_log2(a) * 69 / 100
+}
+
+/// Computes `sum_i k_i P_i` for the bits `[shift, shift + c)` of the scalars `k_i`.
+fn window_sum<P: SWModelParameters>(
+    points: &[SWJAffine<P>],
+    scalars: &[<P::ScalarField as PrimeField>::BigInt],
+    shift: usize,
+    c: usize,
+) -> GroupProjective<P> {
+    let mut buckets = vec![vec![]; (1 << c) - 1];
+    for (p, k) in points.iter().zip(scalars) {
+        let bucket = get_bits(k.as_ref(), shift, c);
+        if bucket != 0 {
+            buckets[bucket - 1].push(*p);
+        }
+    }
+
+    // sum_j j B_j, computed as the sum of the partial sums B_n + ... + B_j
+    let mut running_sum = GroupProjective::<P>::zero();
+    let mut res = GroupProjective::<P>::zero();
+    for b in batch_sum_buckets(&mut buckets).iter().rev() {
+        running_sum.add_assign_mixed(b);
+        res += &running_sum;
+    }
+    res
+}
+
+/// Computes `sum_i scalars[i] bases[i]` with the GLV method, where `endo_q` defines the endomorphism
+/// and `glv` decomposes the scalars according to its eigenvalue.
+/// As with arkworks' `VariableBaseMSM`, the extra bases or scalars are ignored if their numbers differ.
+pub fn glv_multi_scalar_mul<P: SWModelParameters>(
+    endo_q: P::BaseField,
+    glv: &GlvDecomposition<P::ScalarField>,
+    bases: &[SWJAffine<P>],
+    scalars: &[P::ScalarField],
+) -> GroupProjective<P> {
+    let (points, scalars): (Vec<_>, Vec<_>) = bases
+        .par_iter()
+        .zip(scalars.par_iter())
+        .filter(|(p, k)| !p.is_zero() && !k.is_zero())
+        .flat_map_iter(|(p, k)| {
+            let ((neg1, k1), (neg2, k2)) = glv.decompose(*k);
+            let endo_p = SWJAffine::<P>::new(endo_q * p.x, p.y, false);
+            let p1 = if neg1 { -*p } else { *p };
+            let p2 = if neg2 { -endo_p } else { endo_p };
+            [(p1, k1), (p2, k2)]
+        })
+        .unzip();
+
+    let num_bits = scalars.iter().map(|k| k.num_bits()).max().unwrap_or(0) as usize;
+    if num_bits == 0 {
+        return GroupProjective::<P>::zero();
+    }
+
+    // the size of the windows, as chosen by arkworks' `VariableBaseMSM::multi_scalar_mul`
+    let c = if points.len() < 32 {
+        3
+    } else {
+        ln_without_floats(points.len()) + 2
+    };
+
+    let window_sums: Vec<_> = (0..num_bits)
+        .step_by(c)
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|shift| window_sum(&points, &scalars, shift, c))
+        .collect();
+
+    // sum_w 2^{c w} W_w, starting from the highest window
+    let mut res = GroupProjective::<P>::zero();
+    for (i, w) in window_sums.iter().rev().enumerate() {
+        if i != 0 {
+            for _ in 0..c {
+                res.double_in_place();
+            }
+        }
+        res += w;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commitment::{CommitmentCurve, PolyComm},
+        srs::{endos, SRS},
+    };
+    use ark_ec::msm::VariableBaseMSM;
+    use ark_ff::UniformRand;
+    use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+    use mina_curves::pasta::{
+        vesta::{Affine, VestaParameters},
+        Fp,
+    };
+
+    #[test]
+    fn test_glv_decomposition() {
+        let mut rng = rand::thread_rng();
+        let (_, endo_r) = endos::<Affine>();
+        let glv = GlvDecomposition::new(endo_r);
+
+        for k in [Fp::zero(), Fp::from(1u64), -Fp::from(1u64), endo_r]
+            .into_iter()
+            .chain((0..100).map(|_| Fp::rand(&mut rng)))
+        {
+            let ((neg1, k1), (neg2, k2)) = glv.decompose(k);
+            assert!(k1.num_bits() <= 130 && k2.num_bits() <= 130);
+
+            let k1 = Fp::from_repr(k1).unwrap();
+            let k2 = Fp::from_repr(k2).unwrap();
+            let k1 = if neg1 { -k1 } else { k1 };
+            let k2 = if neg2 { -k2 } else { k2 };
+            assert_eq!(k1 + k2 * endo_r, k);
+        }
+    }
+
+    #[test]
+    fn test_glv_multi_scalar_mul() {
+        let mut rng = rand::thread_rng();
+        let (endo_q, endo_r) = endos::<Affine>();
+        let glv = GlvDecomposition::new(endo_r);
+
+        for n in [0, 1, 7, 100, 1000] {
+            let mut bases: Vec<Affine> = (0..n)
+                .map(|_| {
+                    Affine::prime_subgroup_generator()
+                        .mul(Fp::rand(&mut rng))
+                        .into_affine()
+                })
+                .collect();
+            let mut scalars: Vec<Fp> = (0..n).map(|_| Fp::rand(&mut rng)).collect();
+            if n > 2 {
+                // zero scalars, points at infinity and repeated points
+                scalars[0] = Fp::zero();
+                bases[1] = Affine::zero();
+                bases[2] = bases[n - 1];
+            }
+            // an extra base, which must be ignored
+            bases.push(Affine::prime_subgroup_generator());
+
+            let expected = {
+                let scalars: Vec<_> = scalars.iter().map(|k| k.into_repr()).collect();
+                VariableBaseMSM::multi_scalar_mul(&bases, &scalars)
+            };
+            let res = glv_multi_scalar_mul::<VestaParameters>(endo_q, &glv, &bases, &scalars);
+            assert_eq!(res, expected);
+            assert_eq!(
+                <Affine as CommitmentCurve>::glv_multi_scalar_mul(endo_q, &glv, &bases, &scalars),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_srs_msm_algorithm() {
+        let mut rng = rand::thread_rng();
+        let mut srs = SRS::<Affine>::create(1 << 6);
+        let poly = DensePolynomial::<Fp>::rand(200, &mut rng);
+        let expected = srs.commit_non_hiding(&poly, Some(201));
+
+        srs.set_msm_algorithm(MsmAlgorithm::Glv);
+        assert_eq!(srs.msm_algorithm(), MsmAlgorithm::Glv);
+        let res = srs.commit_non_hiding(&poly, Some(201));
+        assert_eq!(res.unshifted, expected.unshifted);
+        assert_eq!(res.shifted, expected.shifted);
+
+        // linear combinations of commitments also use the selected algorithm
+        let other = srs.commit_non_hiding(&DensePolynomial::<Fp>::rand(100, &mut rng), None);
+        let scalars = [Fp::rand(&mut rng), Fp::rand(&mut rng)];
+        let expected = PolyComm::multi_scalar_mul(&[&res, &other], &scalars);
+        let res = srs.commitments_multi_scalar_mul(&[&res, &other], &scalars);
+        assert_eq!(res.unshifted, expected.unshifted);
+        assert_eq!(res.shifted, expected.shifted);
+    }
+}
@@ -0,0 +1,342 @@
+//! This module defines the interface of a polynomial commitment scheme,
+//! implemented by the inner product argument of [SRS] and by the KZG scheme of [KzgSrs](crate::kzg::KzgSrs).
+
+use crate::{
+    commitment::{BatchEvaluationProof, CommitmentCurve},
+    evaluation_proof::OpeningProof,
+    srs::SRS,
+    PolyComm,
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
+};
+use o1_utils::types::fields::*;
+use oracle::FqSponge;
+use rand_core::{CryptoRng, RngCore};
+
+/// The ways in which an opening proof can be malformed (see [PolynomialCommitment::check_opening_proof]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpeningProofError {
+    /// The proof has the first number of rounds, but the setup expects the second one.
+    IncorrectRounds(usize, usize),
+    /// The named part of the proof contains a point that is not on the curve.
+    PointNotOnCurve(&'static str),
+    /// The named part of the proof contains a point that is not in the prime-order subgroup.
+    PointNotInSubgroup(&'static str),
+    /// The named part of the proof is the point at infinity.
+    PointAtInfinity(&'static str),
+}
+
+/// Checks that all the given points are on the curve and in the prime-order subgroup.
+pub(crate) fn check_points<'a, G: CommitmentCurve + 'a>(
+    name: &'static str,
+    points: impl IntoIterator<Item = &'a G>,
+) -> Result<(), OpeningProofError> {
+    for point in points {
+        if !point.is_on_curve() {
+            return Err(OpeningProofError::PointNotOnCurve(name));
+        }
+        if !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(OpeningProofError::PointNotInSubgroup(name));
+        }
+    }
+    Ok(())
+}
+
+/// A polynomial commitment scheme with batched openings.
+/// Polynomials larger than [PolynomialCommitment::max_poly_size] are committed in chunks,
+/// and a degree bound can be enforced by committing to the last chunk shifted to the right edge of the setup.
+pub trait PolynomialCommitment<G: CommitmentCurve> {
+    /// The proof that a batch of commitments opens to some evaluations.
+    type OpeningProof: Clone;
+
+    /// The size of the chunks in which the polynomials are committed.
+    fn max_poly_size(&self) -> usize;
+
+    /// The coefficient of the endomorphism of the curve in the scalar field,
+    /// used to turn the challenges squeezed from a sponge into scalars.
+    fn endo_r(&self) -> &ScalarField<G>;
+
+    /// Returns whether the opening proofs made with this setup can be verified with `larger`,
+    /// a setup of at least the same size.
+    fn is_compatible_with(&self, larger: &Self) -> bool;
+
+    /// Returns whether the commitments can be blinded, so that they do not reveal the committed polynomials.
+    /// If not, [PolynomialCommitment::mask] and [PolynomialCommitment::commit_blinded_zero] return zero blinding factors.
+    fn is_hiding(&self) -> bool;
+
+    /// Commits to a polynomial, with an optional degree bound, without blinding the commitment.
+    fn commit_non_hiding(
+        &self,
+        plnm: &DensePolynomial<ScalarField<G>>,
+        max: Option<usize>,
+    ) -> PolyComm<G>;
+
+    /// Commits to a polynomial, with an optional degree bound,
+    /// and returns the commitment along with its blinding factors.
+    fn commit(
+        &self,
+        plnm: &DensePolynomial<ScalarField<G>>,
+        max: Option<usize>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PolyComm<G>, PolyComm<ScalarField<G>>);
+
+    /// Blinds a commitment computed without hiding, and returns the blinding factors.
+    /// The factors are zero if the scheme does not hide its commitments.
+    fn mask(
+        &self,
+        comm: PolyComm<G>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PolyComm<G>, PolyComm<ScalarField<G>>);
+
+    /// Returns a chunk of a commitment to the zero polynomial along with its blinding factor.
+    /// Unlike a masked commitment to the zero polynomial, the chunk is blinded too if the scheme hides its commitments.
+    fn commit_blinded_zero(&self, rng: &mut (impl RngCore + CryptoRng)) -> (G, ScalarField<G>);
+
+    /// Commits to a polynomial given by its evaluations over a domain at least as large as `domain`,
+    /// without blinding the commitment.
+    fn commit_evaluations_non_hiding(
+        &self,
+        domain: D<ScalarField<G>>,
+        plnm: &Evaluations<ScalarField<G>, D<ScalarField<G>>>,
+        max: Option<usize>,
+    ) -> PolyComm<G>;
+
+    /// Commits to a polynomial given by its evaluations over a domain at least as large as `domain`,
+    /// and returns the commitment along with its blinding factors.
+    fn commit_evaluations(
+        &self,
+        domain: D<ScalarField<G>>,
+        plnm: &Evaluations<ScalarField<G>, D<ScalarField<G>>>,
+        max: Option<usize>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PolyComm<G>, PolyComm<ScalarField<G>>);
+
+    /// Commits, without blinding, to the polynomial that evaluates to `evals` over the first elements of `domain`,
+    /// and to zero over the others.
+    fn commit_evaluations_prefix_non_hiding(
+        &self,
+        domain: D<ScalarField<G>>,
+        evals: &[ScalarField<G>],
+    ) -> PolyComm<G> {
+        let mut evals = evals.to_vec();
+        evals.resize(domain.size(), ScalarField::<G>::zero());
+        let evals = Evaluations::from_vec_and_domain(evals, domain);
+        self.commit_evaluations_non_hiding(domain, &evals, None)
+    }
+
+    /// Computes the linear combination of the commitments `com` with the scalars `elm`.
+    fn commitments_multi_scalar_mul(
+        &self,
+        com: &[&PolyComm<G>],
+        elm: &[ScalarField<G>],
+    ) -> PolyComm<G> {
+        PolyComm::multi_scalar_mul(com, elm)
+    }
+
+    /// Opens a batch of polynomials, given with their degree bound and blinding factors, at the points `elm`.
+    /// The polynomials are combined with the powers of `polyscale`,
+    /// and the evaluation points with the powers of `evalscale`.
+    #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::type_complexity)]
+    fn open<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        plnms: &[(
+            &DensePolynomial<ScalarField<G>>,
+            Option<usize>,
+            PolyComm<ScalarField<G>>,
+        )],
+        elm: &[ScalarField<G>],
+        polyscale: ScalarField<G>,
+        evalscale: ScalarField<G>,
+        sponge: EFqSponge,
+        rng: &mut RNG,
+    ) -> Self::OpeningProof
+    where
+        EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+        RNG: RngCore + CryptoRng,
+        G::BaseField: PrimeField;
+
+    /// Checks that an opening proof has the size expected by the setup,
+    /// and that its points are valid, before it is verified.
+    fn check_opening_proof(&self, proof: &Self::OpeningProof) -> Result<(), OpeningProofError>;
+
+    /// Verifies a batch of batched opening proofs.
+    fn verify<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        batch: &mut [BatchEvaluationProof<G, EFqSponge, Self::OpeningProof>],
+        rng: &mut RNG,
+    ) -> bool
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+        RNG: RngCore + CryptoRng,
+        G::BaseField: PrimeField;
+}
+
+/// The inner product argument.
+/// Committing to evaluations requires the Lagrange bases of the domain
+/// (see [SRS::add_lagrange_basis](crate::srs::SRS::add_lagrange_basis)).
+impl<G: CommitmentCurve> PolynomialCommitment<G> for SRS<G> {
+    type OpeningProof = OpeningProof<G>;
+
+    fn max_poly_size(&self) -> usize {
+        self.g.len()
+    }
+
+    fn endo_r(&self) -> &ScalarField<G> {
+        &self.endo_r
+    }
+
+    /// A shorter setup must be a prefix of `larger` of a power-of-two size.
+    fn is_compatible_with(&self, larger: &Self) -> bool {
+        let len = self.g.len();
+        self.h == larger.h
+            && larger.g.starts_with(&self.g)
+            && (len == larger.g.len() || len.is_power_of_two())
+    }
+
+    fn is_hiding(&self) -> bool {
+        true
+    }
+
+    fn commit_non_hiding(
+        &self,
+        plnm: &DensePolynomial<ScalarField<G>>,
+        max: Option<usize>,
+    ) -> PolyComm<G> {
+        SRS::commit_non_hiding(self, plnm, max)
+    }
+
+    fn commit(
+        &self,
+        plnm: &DensePolynomial<ScalarField<G>>,
+        max: Option<usize>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PolyComm<G>, PolyComm<ScalarField<G>>) {
+        SRS::commit(self, plnm, max, rng)
+    }
+
+    fn mask(
+        &self,
+        comm: PolyComm<G>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PolyComm<G>, PolyComm<ScalarField<G>>) {
+        SRS::mask(self, comm, rng)
+    }
+
+    fn commit_blinded_zero(&self, rng: &mut (impl RngCore + CryptoRng)) -> (G, ScalarField<G>) {
+        let w = ScalarField::<G>::rand(rng);
+        (self.h.mul(w).into_affine(), w)
+    }
+
+    fn commit_evaluations_non_hiding(
+        &self,
+        domain: D<ScalarField<G>>,
+        plnm: &Evaluations<ScalarField<G>, D<ScalarField<G>>>,
+        max: Option<usize>,
+    ) -> PolyComm<G> {
+        SRS::commit_evaluations_non_hiding(self, domain, plnm, max)
+    }
+
+    fn commit_evaluations(
+        &self,
+        domain: D<ScalarField<G>>,
+        plnm: &Evaluations<ScalarField<G>, D<ScalarField<G>>>,
+        max: Option<usize>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PolyComm<G>, PolyComm<ScalarField<G>>) {
+        SRS::commit_evaluations(self, domain, plnm, max, rng)
+    }
+
+    /// Only the Lagrange bases of the first elements of the domain are used.
+    fn commit_evaluations_prefix_non_hiding(
+        &self,
+        domain: D<ScalarField<G>>,
+        evals: &[ScalarField<G>],
+    ) -> PolyComm<G> {
+        let lgr_comm = self
+            .lagrange_bases
+            .get(&domain.size())
+            .expect("pre-computed committed lagrange bases not found");
+        let com: Vec<_> = lgr_comm
+            .iter()
+            .map(|c| PolyComm {
+                unshifted: vec![*c],
+                shifted: None,
+            })
+            .take(evals.len())
+            .collect();
+        let com_ref: Vec<_> = com.iter().collect();
+        SRS::commitments_multi_scalar_mul(self, &com_ref, evals)
+    }
+
+    fn commitments_multi_scalar_mul(
+        &self,
+        com: &[&PolyComm<G>],
+        elm: &[ScalarField<G>],
+    ) -> PolyComm<G> {
+        SRS::commitments_multi_scalar_mul(self, com, elm)
+    }
+
+    fn open<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        plnms: &[(
+            &DensePolynomial<ScalarField<G>>,
+            Option<usize>,
+            PolyComm<ScalarField<G>>,
+        )],
+        elm: &[ScalarField<G>],
+        polyscale: ScalarField<G>,
+        evalscale: ScalarField<G>,
+        sponge: EFqSponge,
+        rng: &mut RNG,
+    ) -> OpeningProof<G>
+    where
+        EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+        RNG: RngCore + CryptoRng,
+        G::BaseField: PrimeField,
+    {
+        SRS::open(
+            self, group_map, plnms, elm, polyscale, evalscale, sponge, rng,
+        )
+    }
+
+    /// The proof must have one round per halving of the setup, and none of its points can be the point at infinity.
+    fn check_opening_proof(&self, proof: &OpeningProof<G>) -> Result<(), OpeningProofError> {
+        let rounds = o1_utils::math::ceil_log2(self.g.len());
+        if proof.lr.len() != rounds {
+            return Err(OpeningProofError::IncorrectRounds(proof.lr.len(), rounds));
+        }
+        check_points("opening proof", proof.lr.iter().flat_map(|(l, r)| [l, r]))?;
+        check_points("opening proof", [&proof.delta, &proof.sg])?;
+        if proof.lr.iter().any(|(l, r)| l.is_zero() || r.is_zero()) {
+            return Err(OpeningProofError::PointAtInfinity("lr"));
+        }
+        if proof.delta.is_zero() {
+            return Err(OpeningProofError::PointAtInfinity("delta"));
+        }
+        if proof.sg.is_zero() {
+            return Err(OpeningProofError::PointAtInfinity("sg"));
+        }
+        Ok(())
+    }
+
+    fn verify<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        batch: &mut [BatchEvaluationProof<G, EFqSponge>],
+        rng: &mut RNG,
+    ) -> bool
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+        RNG: RngCore + CryptoRng,
+        G::BaseField: PrimeField,
+    {
+        SRS::verify(self, group_map, batch, rng)
+    }
+}
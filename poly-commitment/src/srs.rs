@@ -1,15 +1,44 @@
 //! This module implements the Marlin structured reference string primitive
 
-use crate::commitment::CommitmentCurve;
-use ark_ec::{AffineCurve, ProjectiveCurve};
-use ark_ff::{BigInteger, PrimeField};
-use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use crate::commitment::{CommitmentCurve, PolyComm};
+use crate::msm::{GlvDecomposition, MsmAlgorithm};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use array_init::array_init;
 use blake2::{Blake2b512, Digest};
 use groupmap::GroupMap;
+use rand::Rng;
+use rand_core::{CryptoRng, RngCore};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::ops::Range;
+use std::path::Path;
+
+/// The first bytes of a file written by [SRS::write].
+const SRS_FILE_MAGIC: &[u8] = b"kimchi-srs";
+
+/// The version of the format of the files written by [SRS::write].
+const SRS_FILE_VERSION: u64 = 1;
+
+/// How the points of an SRS are encoded in a file (see [SRS::write]).
+/// Note that the points of proofs and verifier indexes are always compressed
+/// (see [o1_utils::serialization::SerdeAs]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointEncoding {
+    /// Both coordinates of each point.
+    /// The file can be read quickly, and the points are only checked if the source is not trusted.
+    Uncompressed,
+    /// The x-coordinate of each point, with the sign of y as a flag.
+    /// The file is about half the size, but reading it recovers y with a square root per point,
+    /// and always checks the points.
+    Compressed,
+}
 
 #[serde_as]
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -31,6 +60,52 @@ pub struct SRS<G: CommitmentCurve> {
     /// Coefficient for the curve endomorphism
     #[serde(skip)]
     pub endo_q: G::BaseField,
+    /// The decomposition of the scalars for the GLV multi-scalar multiplication,
+    /// set if it was selected with [SRS::set_msm_algorithm]
+    #[serde(skip)]
+    pub glv: Option<GlvDecomposition<G::ScalarField>>,
+}
+
+impl<G: CommitmentCurve> SRS<G> {
+    /// Selects the algorithm of the multi-scalar multiplications used to commit and to verify opening proofs.
+    pub fn set_msm_algorithm(&mut self, algorithm: MsmAlgorithm) {
+        self.glv = match algorithm {
+            MsmAlgorithm::Arkworks => None,
+            MsmAlgorithm::Glv => Some(GlvDecomposition::new(self.endo_r)),
+        };
+    }
+
+    /// Returns the algorithm of the multi-scalar multiplications used to commit and to verify opening proofs.
+    pub fn msm_algorithm(&self) -> MsmAlgorithm {
+        match self.glv {
+            None => MsmAlgorithm::Arkworks,
+            Some(_) => MsmAlgorithm::Glv,
+        }
+    }
+
+    /// Computes `sum_i scalars[i] bases[i]` with the selected algorithm.
+    /// The extra bases or scalars are ignored if their numbers differ.
+    pub fn multi_scalar_mul(&self, bases: &[G], scalars: &[G::ScalarField]) -> G::Projective {
+        match &self.glv {
+            None => {
+                let scalars: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+                VariableBaseMSM::multi_scalar_mul(bases, &scalars)
+            }
+            Some(glv) => G::glv_multi_scalar_mul(self.endo_q, glv, bases, scalars),
+        }
+    }
+
+    /// Computes `sum_i elm[i] com[i]` chunk by chunk (see [PolyComm::multi_scalar_mul]),
+    /// with the selected algorithm.
+    pub fn commitments_multi_scalar_mul(
+        &self,
+        com: &[&PolyComm<G>],
+        elm: &[G::ScalarField],
+    ) -> PolyComm<G> {
+        PolyComm::multi_scalar_mul_with(com, elm, |bases, scalars| {
+            self.multi_scalar_mul(bases, scalars)
+        })
+    }
 }
 
 pub fn endos<G: CommitmentCurve>() -> (G::BaseField, G::ScalarField)
@@ -163,23 +238,11 @@ where
     pub fn create(depth: usize) -> Self {
         let m = G::Map::setup();
 
-        let g: Vec<_> = (0..depth)
-            .map(|i| {
-                let mut h = Blake2b512::new();
-                h.update(&(i as u32).to_be_bytes());
-                point_of_random_bytes(&m, &h.finalize())
-            })
-            .collect();
+        let g = derive_gs(0..depth);
 
         let (endo_q, endo_r) = endos::<G>();
 
-        const MISC: usize = 1;
-        let [h]: [G; MISC] = array_init(|i| {
-            let mut h = Blake2b512::new();
-            h.update("srs_misc".as_bytes());
-            h.update(&(i as u32).to_be_bytes());
-            point_of_random_bytes(&m, &h.finalize())
-        });
+        let h = derive_h(&m);
 
         SRS {
             g,
@@ -187,6 +250,237 @@ where
             lagrange_bases: HashMap::new(),
             endo_r,
             endo_q,
+            glv: None,
+        }
+    }
+
+    /// Extends the SRS to `depth` generators, deriving only the missing ones.
+    /// The result is the same as the SRS created by [SRS::create] with that depth,
+    /// and the cached Lagrange bases are still valid.
+    /// Does nothing if the SRS already has at least `depth` generators.
+    pub fn extend(&mut self, depth: usize) {
+        if depth > self.g.len() {
+            let mut g = derive_gs(self.g.len()..depth);
+            self.g.append(&mut g);
+        }
+    }
+
+    /// Writes the SRS, along with its Lagrange bases for the given domain sizes
+    /// (which must have been computed with [SRS::add_lagrange_basis]),
+    /// with the points encoded as specified.
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W,
+        lagrange_bases: &[usize],
+        encoding: PointEncoding,
+    ) -> Result<(), String> {
+        let mut sizes = lagrange_bases.to_vec();
+        sizes.sort_unstable();
+        sizes.dedup();
+
+        let bases = sizes
+            .iter()
+            .map(|n| match self.lagrange_bases.get(n) {
+                Some(basis) => Ok((*n as u64, basis)),
+                None => Err(format!(
+                    "the Lagrange basis for size {} was not computed",
+                    n
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        writer
+            .write_all(SRS_FILE_MAGIC)
+            .map_err(|e| e.to_string())?;
+        (|| -> Result<(), SerializationError> {
+            SRS_FILE_VERSION.serialize(&mut writer)?;
+            u8::from(encoding == PointEncoding::Compressed).serialize(&mut writer)?;
+            let points = |v: &Vec<G>, writer: &mut W| match encoding {
+                PointEncoding::Uncompressed => v.serialize_unchecked(writer),
+                PointEncoding::Compressed => v.serialize(writer),
+            };
+            points(&self.g, &mut writer)?;
+            match encoding {
+                PointEncoding::Uncompressed => self.h.serialize_unchecked(&mut writer)?,
+                PointEncoding::Compressed => self.h.serialize(&mut writer)?,
+            };
+            (bases.len() as u64).serialize(&mut writer)?;
+            for (n, basis) in bases {
+                n.serialize(&mut writer)?;
+                points(basis, &mut writer)?;
+            }
+            Ok(())
+        })()
+        .map_err(|e| e.to_string())
+    }
+
+    /// Reads an SRS written by [SRS::write].
+    /// Unless the source is `trusted` (and the points uncompressed),
+    /// every point is checked to be on the curve and in the prime-order subgroup.
+    /// In any case, the points are not checked to be the ones derived by [SRS::create]
+    /// (see [SRS::verify_against_seed]).
+    pub fn read<R: Read>(mut reader: R, trusted: bool) -> Result<Self, String> {
+        let mut magic = [0u8; SRS_FILE_MAGIC.len()];
+        reader.read_exact(&mut magic).map_err(|e| e.to_string())?;
+        if &magic != SRS_FILE_MAGIC {
+            return Err("not an SRS file".to_string());
+        }
+
+        let (encoding, g, h, lagrange_bases) = (|| -> Result<_, SerializationError> {
+            if u64::deserialize(&mut reader)? != SRS_FILE_VERSION {
+                return Err(SerializationError::InvalidData);
+            }
+            let encoding = match u8::deserialize(&mut reader)? {
+                0 => PointEncoding::Uncompressed,
+                1 => PointEncoding::Compressed,
+                _ => return Err(SerializationError::InvalidData),
+            };
+            // decompressing the points checks them
+            let points = |reader: &mut R| match encoding {
+                PointEncoding::Uncompressed => Vec::<G>::deserialize_unchecked(reader),
+                PointEncoding::Compressed => Vec::<G>::deserialize(reader),
+            };
+            let g = points(&mut reader)?;
+            let h = match encoding {
+                PointEncoding::Uncompressed => G::deserialize_unchecked(&mut reader)?,
+                PointEncoding::Compressed => G::deserialize(&mut reader)?,
+            };
+            let count = u64::deserialize(&mut reader)?;
+            let mut lagrange_bases = HashMap::new();
+            for _ in 0..count {
+                let n = u64::deserialize(&mut reader)? as usize;
+                let basis = points(&mut reader)?;
+                if basis.len() != n || n > g.len() {
+                    return Err(SerializationError::InvalidData);
+                }
+                lagrange_bases.insert(n, basis);
+            }
+            Ok((encoding, g, h, lagrange_bases))
+        })()
+        .map_err(|e| e.to_string())?;
+
+        if !trusted && encoding == PointEncoding::Uncompressed {
+            let valid = |p: &G| p.is_on_curve() && p.is_in_correct_subgroup_assuming_on_curve();
+            let all_valid = valid(&h)
+                && g.par_iter().all(valid)
+                && lagrange_bases
+                    .values()
+                    .all(|basis| basis.par_iter().all(valid));
+            if !all_valid {
+                return Err("the SRS contains an invalid point".to_string());
+            }
+        }
+
+        let (endo_q, endo_r) = endos::<G>();
+
+        Ok(SRS {
+            g,
+            h,
+            lagrange_bases,
+            endo_r,
+            endo_q,
+            glv: None,
+        })
+    }
+
+    /// Writes the SRS to a file (see [SRS::write]).
+    pub fn to_file(
+        &self,
+        path: &Path,
+        lagrange_bases: &[usize],
+        encoding: PointEncoding,
+    ) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        let mut writer = BufWriter::new(file);
+        self.write(&mut writer, lagrange_bases, encoding)?;
+        writer.flush().map_err(|e| e.to_string())
+    }
+
+    /// Reads an SRS from a file (see [SRS::read]).
+    pub fn from_file(path: &Path, trusted: bool) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        Self::read(BufReader::new(file), trusted)
+    }
+
+    /// Checks that the SRS is the one created by [SRS::create], by deriving again
+    /// the blinding generator `h` and `samples` generators of `g` picked at random.
+    /// The cached Lagrange bases are checked to be consistent with `g`,
+    /// by committing to a random polynomial both in coefficient and in evaluation form.
+    pub fn verify_against_seed<RNG: RngCore + CryptoRng>(
+        &self,
+        samples: usize,
+        rng: &mut RNG,
+    ) -> bool {
+        let m = G::Map::setup();
+
+        if self.h != derive_h(&m) {
+            return false;
+        }
+
+        if !self.g.is_empty() {
+            let indices: Vec<_> = (0..samples)
+                .map(|_| rng.gen_range(0..self.g.len()))
+                .collect();
+            if !indices
+                .par_iter()
+                .map_init(G::Map::setup, |m, i| self.g[*i] == derive_g(m, *i))
+                .all(|valid| valid)
+            {
+                return false;
+            }
         }
+
+        self.lagrange_bases.iter().all(|(n, basis)| {
+            let domain = match D::<G::ScalarField>::new(*n) {
+                Some(domain) if domain.size() == *n && basis.len() == *n && *n <= self.g.len() => {
+                    domain
+                }
+                _ => return false,
+            };
+            let evals: Vec<_> = (0..*n).map(|_| G::ScalarField::rand(rng)).collect();
+            let coeffs = Evaluations::from_vec_and_domain(evals.clone(), domain).interpolate();
+
+            let evals: Vec<_> = evals.iter().map(|x| x.into_repr()).collect();
+            let coeffs: Vec<_> = coeffs.coeffs.iter().map(|x| x.into_repr()).collect();
+            VariableBaseMSM::multi_scalar_mul(basis, &evals)
+                == VariableBaseMSM::multi_scalar_mul(&self.g[..coeffs.len()], &coeffs)
+        })
     }
 }
+
+/// Derives the `i`-th generator of `g` from the Blake2b hash of its index.
+fn derive_g<G: CommitmentCurve>(m: &G::Map, i: usize) -> G
+where
+    G::BaseField: PrimeField,
+{
+    let mut h = Blake2b512::new();
+    h.update(&(i as u32).to_be_bytes());
+    point_of_random_bytes(m, &h.finalize())
+}
+
+/// Derives the generators of `g` of the given indices, in parallel.
+fn derive_gs<G: CommitmentCurve>(indices: Range<usize>) -> Vec<G>
+where
+    G::BaseField: PrimeField,
+{
+    // the group map is set up once per rayon job, as it is not required to be shareable between threads
+    indices
+        .into_par_iter()
+        .map_init(G::Map::setup, |m, i| derive_g(m, i))
+        .collect()
+}
+
+/// Derives the blinding generator `h` from the Blake2b hash of a domain separator.
+fn derive_h<G: CommitmentCurve>(m: &G::Map) -> G
+where
+    G::BaseField: PrimeField,
+{
+    const MISC: usize = 1;
+    let [h]: [G; MISC] = array_init(|i| {
+        let mut h = Blake2b512::new();
+        h.update("srs_misc".as_bytes());
+        h.update(&(i as u32).to_be_bytes());
+        point_of_random_bytes(m, &h.finalize())
+    });
+    h
+}
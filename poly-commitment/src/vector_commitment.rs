@@ -0,0 +1,397 @@
+//! This module implements commitments to vectors of field elements given in Lagrange form over a domain,
+//! and proofs of their entries or of their inner products with public vectors.
+//! The proofs are the ones of the inner product argument (see [OpeningProof]),
+//! made non-interactive with their own sponge: the statement is absorbed in a fresh sponge
+//! given by the caller, which the verifier has to initialize in the same way.
+
+use crate::commitment::*;
+use crate::evaluation_proof::{Challenges, OpeningProof};
+use crate::srs::SRS;
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{One, PrimeField, UniformRand, Zero};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
+use o1_utils::{
+    math,
+    types::{BaseField, ScalarField},
+};
+use oracle::{sponge::ScalarChallenge, FqSponge};
+use rand_core::{CryptoRng, RngCore};
+
+/// A vector of field elements in Lagrange form over a domain,
+/// along with its commitment and the blinding factor of the commitment.
+#[derive(Clone, Debug)]
+pub struct CommittedVector<G: AffineCurve> {
+    /// the domain over which the vector is given
+    pub domain: D<G::ScalarField>,
+    /// the entries of the vector, one per element of the domain
+    pub values: Vec<G::ScalarField>,
+    /// the commitment to the vector
+    pub commitment: G,
+    /// the blinding factor of the commitment (zero for a non-hiding commitment)
+    pub blinding_factor: G::ScalarField,
+}
+
+/// The public vectors that a committed vector is opened against.
+enum Queries<'a, F> {
+    /// the unit vectors of the given positions
+    Positions(&'a [usize]),
+    /// arbitrary vectors in Lagrange form over the domain
+    Vectors(&'a [Vec<F>]),
+}
+
+impl<'a, F: PrimeField> Queries<'a, F> {
+    /// Whether the queries are well-formed for vectors over `domain`.
+    fn fit(&self, domain: D<F>) -> bool {
+        let n = domain.size();
+        match self {
+            Queries::Positions(positions) => positions.iter().all(|&i| i < n),
+            Queries::Vectors(vectors) => vectors.iter().all(|v| v.len() == n),
+        }
+    }
+
+    /// The number of queries.
+    fn len(&self) -> usize {
+        match self {
+            Queries::Positions(positions) => positions.len(),
+            Queries::Vectors(vectors) => vectors.len(),
+        }
+    }
+
+    /// The answers to the queries on the vector `values`.
+    fn answers(&self, values: &[F]) -> Vec<F> {
+        match self {
+            Queries::Positions(positions) => positions.iter().map(|&i| values[i]).collect(),
+            Queries::Vectors(vectors) => vectors.iter().map(|v| inner_prod(values, v)).collect(),
+        }
+    }
+
+    /// Absorbs the queries in the sponge.
+    fn absorb<G, EFqSponge>(&self, sponge: &mut EFqSponge)
+    where
+        G: AffineCurve<ScalarField = F>,
+        EFqSponge: FqSponge<BaseField<G>, G, F>,
+    {
+        match self {
+            Queries::Positions(positions) => {
+                let positions: Vec<_> = positions.iter().map(|&i| F::from(i as u64)).collect();
+                sponge.absorb_fr(&positions);
+            }
+            Queries::Vectors(vectors) => {
+                for v in vectors.iter() {
+                    sponge.absorb_fr(v);
+                }
+            }
+        }
+    }
+
+    /// The queries combined with the powers of `rho`, in the coefficient basis:
+    /// the inner product of a vector in Lagrange form with the combined query
+    /// is the inner product of the coefficients of its interpolation with the returned vector,
+    /// which is of size `length` (at least the size of the domain).
+    fn combine(&self, domain: D<F>, rho: F, length: usize) -> Vec<F> {
+        let n = domain.size();
+        let mut res = vec![F::zero(); n];
+        let mut scale = F::one();
+        match self {
+            Queries::Positions(positions) => {
+                // the powers of the position are needed beyond the domain,
+                // as this is what the verifier succinctly computes
+                res.resize(length, F::zero());
+                for &i in positions.iter() {
+                    for (r, t) in res.iter_mut().zip(pows(length, domain.element(i))) {
+                        *r += scale * t;
+                    }
+                    scale *= rho;
+                }
+            }
+            Queries::Vectors(vectors) => {
+                for v in vectors.iter() {
+                    for (r, t) in res.iter_mut().zip(v) {
+                        *r += scale * t;
+                    }
+                    scale *= rho;
+                }
+                // sum_i u_i p(w^i) = sum_j p_j sum_i u_i w^{ij}
+                domain.fft_in_place(&mut res);
+                res.resize(length, F::zero());
+            }
+        }
+        res
+    }
+
+    /// The inner product of the combined query (see [Queries::combine])
+    /// with the coefficients `b_poly_coefficients(chal)`.
+    /// This is succinct for positions, and linear in the size of the SRS for vectors.
+    fn combined_b0(&self, domain: D<F>, rho: F, chal: &[F]) -> F {
+        match self {
+            Queries::Positions(positions) => {
+                let mut scale = F::one();
+                let mut res = F::zero();
+                for &i in positions.iter() {
+                    res += scale * b_poly(chal, domain.element(i));
+                    scale *= rho;
+                }
+                res
+            }
+            Queries::Vectors(_) => {
+                let s = b_poly_coefficients(chal);
+                inner_prod(&s, &self.combine(domain, rho, s.len()))
+            }
+        }
+    }
+}
+
+impl<G: CommitmentCurve> SRS<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Commits to a vector given in Lagrange form over `domain`, without blinding the commitment.
+    /// The domain cannot be larger than the SRS, and its Lagrange basis has to be present
+    /// (see [SRS::add_lagrange_basis]).
+    pub fn commit_vector_non_hiding(
+        &self,
+        domain: D<ScalarField<G>>,
+        values: Vec<ScalarField<G>>,
+    ) -> CommittedVector<G> {
+        assert!(domain.size() <= self.g.len());
+        assert_eq!(values.len(), domain.size());
+        let evals = Evaluations::from_vec_and_domain(values, domain);
+        let comm = self.commit_evaluations_non_hiding(domain, &evals, None);
+        CommittedVector {
+            domain,
+            values: evals.evals,
+            commitment: comm.unshifted.first().copied().unwrap_or_else(G::zero),
+            blinding_factor: ScalarField::<G>::zero(),
+        }
+    }
+
+    /// Commits to a vector given in Lagrange form over `domain`, and blinds the commitment.
+    pub fn commit_vector(
+        &self,
+        domain: D<ScalarField<G>>,
+        values: Vec<ScalarField<G>>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> CommittedVector<G> {
+        let mut vector = self.commit_vector_non_hiding(domain, values);
+        let w = ScalarField::<G>::rand(rng);
+        vector.commitment = (vector.commitment.into_projective() + self.h.mul(w)).into_affine();
+        vector.blinding_factor = w;
+        vector
+    }
+
+    /// Proves the entries of a committed vector at the given positions.
+    /// Returns the entries, along with the proof.
+    pub fn prove_positions<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        vector: &CommittedVector<G>,
+        positions: &[usize],
+        sponge: EFqSponge,
+        rng: &mut RNG,
+    ) -> (Vec<ScalarField<G>>, OpeningProof<G>)
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+        RNG: RngCore + CryptoRng,
+    {
+        self.prove_queries(
+            group_map,
+            vector,
+            Queries::Positions(positions),
+            sponge,
+            rng,
+        )
+    }
+
+    /// Verifies that the vector committed in `commitment` has the entries `values` at the given positions.
+    /// This is linear in the size of the SRS, as the commitment `sg` of the proof is checked
+    /// against the challenges with a multi-scalar multiplication over the whole SRS.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_positions<EFqSponge>(
+        &self,
+        group_map: &G::Map,
+        domain: D<ScalarField<G>>,
+        commitment: G,
+        positions: &[usize],
+        values: &[ScalarField<G>],
+        proof: &OpeningProof<G>,
+        sponge: EFqSponge,
+    ) -> bool
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+    {
+        self.verify_queries(
+            group_map,
+            domain,
+            commitment,
+            Queries::Positions(positions),
+            values,
+            proof,
+            sponge,
+        )
+    }
+
+    /// Proves the inner products of a committed vector with the given vectors,
+    /// which are also given in Lagrange form over the domain of the committed vector.
+    /// Returns the inner products, along with the proof.
+    pub fn prove_inner_products<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        vector: &CommittedVector<G>,
+        vectors: &[Vec<ScalarField<G>>],
+        sponge: EFqSponge,
+        rng: &mut RNG,
+    ) -> (Vec<ScalarField<G>>, OpeningProof<G>)
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+        RNG: RngCore + CryptoRng,
+    {
+        self.prove_queries(group_map, vector, Queries::Vectors(vectors), sponge, rng)
+    }
+
+    /// Verifies that the vector committed in `commitment` has the inner products `values` with the given vectors.
+    /// Like [SRS::verify_positions], this is linear in the size of the SRS,
+    /// and the public vectors are also combined in time linear in the size of the SRS.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_inner_products<EFqSponge>(
+        &self,
+        group_map: &G::Map,
+        domain: D<ScalarField<G>>,
+        commitment: G,
+        vectors: &[Vec<ScalarField<G>>],
+        values: &[ScalarField<G>],
+        proof: &OpeningProof<G>,
+        sponge: EFqSponge,
+    ) -> bool
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+    {
+        self.verify_queries(
+            group_map,
+            domain,
+            commitment,
+            Queries::Vectors(vectors),
+            values,
+            proof,
+            sponge,
+        )
+    }
+
+    /// Absorbs the statement in the sponge, and samples the challenge combining the queries.
+    /// The size of the domain is part of the statement,
+    /// as a position is the same point in a domain and in the ones it is a subgroup of.
+    fn absorb_statement<EFqSponge>(
+        &self,
+        domain: D<ScalarField<G>>,
+        commitment: G,
+        queries: &Queries<ScalarField<G>>,
+        values: &[ScalarField<G>],
+        sponge: &mut EFqSponge,
+    ) -> ScalarField<G>
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+    {
+        sponge.absorb_fr(&[ScalarField::<G>::from(domain.size() as u64)]);
+        sponge.absorb_g(&[commitment]);
+        queries.absorb::<G, _>(sponge);
+        sponge.absorb_fr(values);
+        ScalarChallenge(sponge.challenge()).to_field(&self.endo_r)
+    }
+
+    fn prove_queries<EFqSponge, RNG>(
+        &self,
+        group_map: &G::Map,
+        vector: &CommittedVector<G>,
+        queries: Queries<ScalarField<G>>,
+        mut sponge: EFqSponge,
+        rng: &mut RNG,
+    ) -> (Vec<ScalarField<G>>, OpeningProof<G>)
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+        RNG: RngCore + CryptoRng,
+    {
+        let domain = vector.domain;
+        assert!(queries.fit(domain), "queries do not fit the domain");
+
+        let values = queries.answers(&vector.values);
+        let rho = self.absorb_statement(domain, vector.commitment, &queries, &values, &mut sponge);
+
+        let a = domain.ifft(&vector.values);
+        let b = queries.combine(domain, rho, 1 << math::ceil_log2(self.g.len()));
+        let proof = self.prove_inner_product(group_map, a, b, vector.blinding_factor, sponge, rng);
+
+        (values, proof)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn verify_queries<EFqSponge>(
+        &self,
+        group_map: &G::Map,
+        domain: D<ScalarField<G>>,
+        commitment: G,
+        queries: Queries<ScalarField<G>>,
+        values: &[ScalarField<G>],
+        proof: &OpeningProof<G>,
+        mut sponge: EFqSponge,
+    ) -> bool
+    where
+        EFqSponge: FqSponge<BaseField<G>, G, ScalarField<G>>,
+    {
+        let rounds = math::ceil_log2(self.g.len());
+        if domain.size() > self.g.len()
+            || !queries.fit(domain)
+            || queries.len() != values.len()
+            || proof.lr.len() != rounds
+        {
+            return false;
+        }
+
+        let rho = self.absorb_statement(domain, commitment, &queries, values, &mut sponge);
+
+        // the claimed inner product of the committed vector with the combined query
+        let combined_inner_product = {
+            let mut scale = ScalarField::<G>::one();
+            let mut res = ScalarField::<G>::zero();
+            for v in values {
+                res += scale * v;
+                scale *= rho;
+            }
+            res
+        };
+
+        // same transcript as in the inner product argument
+        sponge.absorb_fr(&[shift_scalar::<G>(combined_inner_product)]);
+        let t = sponge.challenge_fq();
+        let u: G = to_group(group_map, t);
+
+        let Challenges { chal, chal_inv } = proof.challenges(&self.endo_r, &mut sponge);
+
+        sponge.absorb_g(&[proof.delta]);
+        let c = ScalarChallenge(sponge.challenge()).to_field(&self.endo_r);
+
+        let b0 = queries.combined_b0(domain, rho, &chal);
+
+        // c (P + <a, b> U + sum_j (u_j^{-1} L_j + u_j R_j)) + delta
+        // == z1 (sg + b0 U) + z2 H
+        let mut points = vec![commitment, u, proof.delta, proof.sg, self.h];
+        let mut scalars = vec![
+            c,
+            c * combined_inner_product - proof.z1 * b0,
+            ScalarField::<G>::one(),
+            -proof.z1,
+            -proof.z2,
+        ];
+        for ((l, r), (u_inv, u)) in proof.lr.iter().zip(chal_inv.iter().zip(chal.iter())) {
+            points.push(*l);
+            scalars.push(c * u_inv);
+            points.push(*r);
+            scalars.push(c * u);
+        }
+        if !self.multi_scalar_mul(&points, &scalars).is_zero() {
+            return false;
+        }
+
+        // sg is the commitment to the coefficients derived from the challenges
+        let s = b_poly_coefficients(&chal);
+        self.multi_scalar_mul(&self.g, &s[..self.g.len()]) == proof.sg.into_projective()
+    }
+}
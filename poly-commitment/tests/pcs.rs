@@ -0,0 +1,117 @@
+use ark_ff::{One, UniformRand, Zero};
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use commitment_dlog::{
+    commitment::{BatchEvaluationProof, CommitmentCurve, Evaluation},
+    kzg::KzgSrs,
+    srs::SRS,
+    PolyComm, PolynomialCommitment,
+};
+use groupmap::GroupMap;
+use mina_curves::pasta::vesta::{Affine, VestaParameters};
+use o1_utils::{types::fields::*, ExtendedDensePolynomial as _};
+use oracle::constants::PlonkSpongeConstantsKimchi as SC;
+use oracle::poseidon::ArithmeticSpongeParams;
+use oracle::sponge::DefaultFqSponge;
+use oracle::FqSponge;
+use rand::Rng;
+
+/// Commits to a few random polynomials (some of them larger than the setup, some of them with a degree bound),
+/// opens them at a few random points, and verifies the opening.
+/// If `tamper` is set, one of the claimed evaluations is modified before verifying.
+fn commit_open_verify<G, PCS, EFqSponge>(pcs: &PCS, sponge: EFqSponge, tamper: bool) -> bool
+where
+    G: CommitmentCurve,
+    G::BaseField: ark_ff::PrimeField,
+    PCS: PolynomialCommitment<G>,
+    EFqSponge: Clone + FqSponge<BaseField<G>, G, ScalarField<G>>,
+{
+    let mut rng = rand::thread_rng();
+    let group_map = G::Map::setup();
+    let n = pcs.max_poly_size();
+
+    let eval_points: Vec<ScalarField<G>> = (0..3).map(|_| UniformRand::rand(&mut rng)).collect();
+
+    let mut polys = vec![];
+    let mut evaluations = vec![];
+    for i in 0..6 {
+        let len = rng.gen_range(0..3 * n);
+        let poly = if len == 0 {
+            DensePolynomial::zero()
+        } else {
+            DensePolynomial::<ScalarField<G>>::rand(len, &mut rng)
+        };
+        let bound = if i % 2 == 0 {
+            Some(poly.coeffs.len())
+        } else {
+            None
+        };
+
+        let (commitment, blinders) = pcs.commit(&poly, bound, &mut rng);
+        let evals = eval_points
+            .iter()
+            .map(|x| poly.to_chunked_polynomial(n).evaluate_chunks(*x))
+            .collect();
+
+        evaluations.push(Evaluation {
+            commitment,
+            evaluations: evals,
+            degree_bound: bound,
+        });
+        polys.push((poly, bound, blinders));
+    }
+
+    if tamper {
+        let e = evaluations
+            .iter_mut()
+            .find(|e| !e.evaluations[0].is_empty())
+            .unwrap();
+        e.evaluations[0][0] += ScalarField::<G>::one();
+    }
+
+    let polyscale = UniformRand::rand(&mut rng);
+    let evalscale = UniformRand::rand(&mut rng);
+    let plnms: Vec<(_, _, PolyComm<_>)> = polys
+        .iter()
+        .map(|(poly, bound, blinders)| (poly, *bound, blinders.clone()))
+        .collect();
+    let proof = pcs.open(
+        &group_map,
+        &plnms,
+        &eval_points,
+        polyscale,
+        evalscale,
+        sponge.clone(),
+        &mut rng,
+    );
+
+    let mut batch = vec![BatchEvaluationProof {
+        sponge,
+        evaluations,
+        evaluation_points: eval_points,
+        xi: polyscale,
+        r: evalscale,
+        opening: &proof,
+    }];
+    pcs.verify(&group_map, &mut batch, &mut rng)
+}
+
+#[test]
+fn test_ipa_polynomial_commitment() {
+    let srs = SRS::<Affine>::create(1 << 5);
+    let sponge = DefaultFqSponge::<VestaParameters, SC>::new(oracle::pasta::fq_kimchi::params());
+
+    assert!(commit_open_verify(&srs, sponge.clone(), false));
+    assert!(!commit_open_verify(&srs, sponge, true));
+}
+
+#[test]
+fn test_kzg_polynomial_commitment() {
+    let mut rng = rand::thread_rng();
+    let srs = KzgSrs::<ark_bn254::Bn254>::create_insecure(1 << 5, &mut rng);
+    // the KZG scheme does not use the sponge, there are no Poseidon parameters for this curve
+    let sponge =
+        DefaultFqSponge::<ark_bn254::g1::Parameters, SC>::new(ArithmeticSpongeParams::default());
+
+    assert!(commit_open_verify(&srs, sponge.clone(), false));
+    assert!(!commit_open_verify(&srs, sponge, true));
+}
@@ -0,0 +1,105 @@
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use commitment_dlog::srs::{PointEncoding, SRS};
+use mina_curves::pasta::{vesta::Affine, Fp};
+
+#[test]
+fn test_srs_write_read() {
+    let mut srs = SRS::<Affine>::create(1 << 6);
+    let domain = D::<Fp>::new(1 << 5).unwrap();
+    srs.add_lagrange_basis(domain);
+
+    let mut sizes = vec![];
+    for encoding in [PointEncoding::Uncompressed, PointEncoding::Compressed] {
+        let mut bytes = vec![];
+        srs.write(&mut bytes, &[domain.size()], encoding).unwrap();
+        sizes.push(bytes.len());
+
+        for trusted in [false, true] {
+            let read = SRS::<Affine>::read(&bytes[..], trusted).unwrap();
+            assert_eq!(read.g, srs.g);
+            assert_eq!(read.h, srs.h);
+            assert_eq!(read.lagrange_bases, srs.lagrange_bases);
+            assert_eq!(read.endo_r, srs.endo_r);
+            assert_eq!(read.endo_q, srs.endo_q);
+        }
+    }
+
+    // compressing saves the y-coordinate of every point
+    let points = srs.g.len() + 1 + domain.size();
+    assert_eq!(sizes[0] - sizes[1], points * 32);
+
+    // only the bases that were computed can be written
+    assert!(srs
+        .write(&mut vec![], &[1 << 4], PointEncoding::Compressed)
+        .is_err());
+}
+
+#[test]
+fn test_srs_compressed_points_are_checked() {
+    let srs = SRS::<Affine>::create(1 << 4);
+    let mut bytes = vec![];
+    srs.write(&mut bytes, &[], PointEncoding::Compressed)
+        .unwrap();
+
+    // the first generator starts after the magic bytes, the version, the encoding and the number of generators
+    let offset = b"kimchi-srs".len() + 8 + 1 + 8;
+    // some of the modified x-coordinates have no point on the curve,
+    // which is detected even if the source is trusted
+    let rejected = (1..32u8).any(|i| {
+        let mut tampered = bytes.clone();
+        tampered[offset] = tampered[offset].wrapping_add(i);
+        SRS::<Affine>::read(&tampered[..], true).is_err()
+    });
+    assert!(rejected);
+}
+
+#[test]
+fn test_srs_file() {
+    let srs = SRS::<Affine>::create(1 << 4);
+    let path = std::env::temp_dir().join("kimchi_test_srs_file.srs");
+
+    srs.to_file(&path, &[], PointEncoding::Compressed).unwrap();
+    let read = SRS::<Affine>::from_file(&path, false).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(read.g, srs.g);
+    assert!(read.lagrange_bases.is_empty());
+}
+
+#[test]
+fn test_srs_verify_against_seed() {
+    let mut rng = rand::thread_rng();
+    let mut srs = SRS::<Affine>::create(1 << 5);
+    srs.add_lagrange_basis(D::<Fp>::new(1 << 4).unwrap());
+    assert!(srs.verify_against_seed(1 << 5, &mut rng));
+
+    // every generator is checked when sampling many of them
+    let mut tampered = srs.clone();
+    tampered.g[3] = tampered.g[3] + tampered.g[3];
+    assert!(!tampered.verify_against_seed(1 << 10, &mut rng));
+
+    // the Lagrange bases are checked against the generators
+    let mut tampered = srs.clone();
+    let basis = tampered.lagrange_bases.get_mut(&(1 << 4)).unwrap();
+    basis.swap(0, 1);
+    assert!(!tampered.verify_against_seed(0, &mut rng));
+
+    let mut tampered = srs;
+    tampered.h = tampered.g[0];
+    assert!(!tampered.verify_against_seed(0, &mut rng));
+}
+
+#[test]
+fn test_srs_extend() {
+    let mut srs = SRS::<Affine>::create(10);
+    srs.add_lagrange_basis(D::<Fp>::new(1 << 3).unwrap());
+    let lagrange_bases = srs.lagrange_bases.clone();
+
+    srs.extend(1 << 5);
+    assert_eq!(srs.g, SRS::<Affine>::create(1 << 5).g);
+    assert_eq!(srs.lagrange_bases, lagrange_bases);
+
+    // an SRS is never shrunk
+    srs.extend(4);
+    assert_eq!(srs.g.len(), 1 << 5);
+}
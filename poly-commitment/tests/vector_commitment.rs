@@ -0,0 +1,144 @@
+use ark_ff::{One, UniformRand};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use commitment_dlog::{commitment::CommitmentCurve, srs::SRS};
+use groupmap::GroupMap;
+use mina_curves::pasta::{
+    vesta::{Affine, VestaParameters},
+    Fp,
+};
+use oracle::constants::PlonkSpongeConstantsKimchi as SC;
+use oracle::sponge::DefaultFqSponge;
+use oracle::FqSponge;
+use rand::Rng;
+
+type VestaSponge = DefaultFqSponge<VestaParameters, SC>;
+
+fn sponge() -> VestaSponge {
+    VestaSponge::new(oracle::pasta::fq_kimchi::params())
+}
+
+#[test]
+fn test_vector_positions() {
+    let mut rng = rand::thread_rng();
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    // the SRS is not a power of two, and the vectors can be smaller than the SRS
+    let mut srs = SRS::<Affine>::create(40);
+    for size in [16, 32] {
+        let domain = D::<Fp>::new(size).unwrap();
+        srs.add_lagrange_basis(domain);
+
+        let values: Vec<Fp> = (0..size).map(|_| Fp::rand(&mut rng)).collect();
+        let vector = srs.commit_vector(domain, values.clone(), &mut rng);
+        let positions: Vec<usize> = (0..4).map(|_| rng.gen_range(0..size)).collect();
+
+        let (entries, proof) =
+            srs.prove_positions(&group_map, &vector, &positions, sponge(), &mut rng);
+        let expected: Vec<_> = positions.iter().map(|&i| values[i]).collect();
+        assert_eq!(entries, expected);
+        assert!(srs.verify_positions(
+            &group_map,
+            domain,
+            vector.commitment,
+            &positions,
+            &entries,
+            &proof,
+            sponge()
+        ));
+
+        // wrong entries
+        let mut wrong = entries.clone();
+        wrong[1] += Fp::one();
+        assert!(!srs.verify_positions(
+            &group_map,
+            domain,
+            vector.commitment,
+            &positions,
+            &wrong,
+            &proof,
+            sponge()
+        ));
+
+        // a position outside of the domain
+        assert!(!srs.verify_positions(
+            &group_map,
+            domain,
+            vector.commitment,
+            &[size],
+            &entries[..1],
+            &proof,
+            sponge()
+        ));
+
+        // the proof is bound to the domain, even for a position that is the same point in a smaller domain
+        let (entry, proof) = srs.prove_positions(&group_map, &vector, &[0], sponge(), &mut rng);
+        assert!(!srs.verify_positions(
+            &group_map,
+            D::<Fp>::new(size / 2).unwrap(),
+            vector.commitment,
+            &[0],
+            &entry,
+            &proof,
+            sponge()
+        ));
+    }
+}
+
+#[test]
+fn test_vector_inner_products() {
+    let mut rng = rand::thread_rng();
+    let group_map = <Affine as CommitmentCurve>::Map::setup();
+
+    let size = 32;
+    let domain = D::<Fp>::new(size).unwrap();
+    let mut srs = SRS::<Affine>::create(size);
+    srs.add_lagrange_basis(domain);
+
+    let values: Vec<Fp> = (0..size).map(|_| Fp::rand(&mut rng)).collect();
+    let vector = srs.commit_vector_non_hiding(domain, values.clone());
+    let vectors: Vec<Vec<Fp>> = (0..3)
+        .map(|_| (0..size).map(|_| Fp::rand(&mut rng)).collect())
+        .collect();
+
+    let (inner_products, proof) =
+        srs.prove_inner_products(&group_map, &vector, &vectors, sponge(), &mut rng);
+    for (v, ip) in vectors.iter().zip(&inner_products) {
+        let expected: Fp = v.iter().zip(&values).map(|(a, b)| *a * b).sum();
+        assert_eq!(*ip, expected);
+    }
+    assert!(srs.verify_inner_products(
+        &group_map,
+        domain,
+        vector.commitment,
+        &vectors,
+        &inner_products,
+        &proof,
+        sponge()
+    ));
+
+    // the proof is bound to the public vectors
+    let mut other_vectors = vectors.clone();
+    other_vectors[0][0] += Fp::one();
+    assert!(!srs.verify_inner_products(
+        &group_map,
+        domain,
+        vector.commitment,
+        &other_vectors,
+        &inner_products,
+        &proof,
+        sponge()
+    ));
+
+    // and to the transcript
+    let mut other_sponge = sponge();
+    other_sponge.absorb_fr(&[Fp::one()]);
+    assert!(!srs.verify_inner_products(
+        &group_map,
+        domain,
+        vector.commitment,
+        &vectors,
+        &inner_products,
+        &proof,
+        other_sponge
+    ));
+}
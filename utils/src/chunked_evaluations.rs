@@ -0,0 +1,99 @@
+//! This module implements [ChunkedEvaluations], the evaluations of the chunks of a polynomial
+//! (see [ChunkedPolynomial](crate::chunked_polynomial::ChunkedPolynomial)).
+
+use crate::chunked_polynomial::combine_chunks;
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+
+/// The evaluations at a point $x$ of the chunks of a polynomial
+/// $f(x) = f_0(x) + x^n f_1(x) + x^{2n} f_2(x) + \cdots$,
+/// that is $(f_0(x), f_1(x), f_2(x), \ldots)$.
+/// Note that a zero polynomial has no chunks.
+///
+/// This is serialized as the vector of its chunks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChunkedEvaluations<F> {
+    /// The evaluations of the chunks.
+    pub chunks: Vec<F>,
+}
+
+impl<F: Field> ChunkedEvaluations<F> {
+    /// Combines the evaluations of the chunks at $x$ into the evaluation of the polynomial at $x$,
+    /// given $x^n$ where $n$ is the size of the chunks.
+    pub fn combine(&self, x_to_n: F) -> F {
+        combine_chunks(self.chunks.iter(), |acc: F, chunk| acc * x_to_n + chunk)
+    }
+}
+
+impl<F> ChunkedEvaluations<F> {
+    /// The number of chunks.
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Whether there are no chunks (i.e. the polynomial is zero).
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+}
+
+impl<F> From<Vec<F>> for ChunkedEvaluations<F> {
+    fn from(chunks: Vec<F>) -> Self {
+        Self { chunks }
+    }
+}
+
+impl<F> FromIterator<F> for ChunkedEvaluations<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        Self {
+            chunks: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<F: CanonicalSerialize> CanonicalSerialize for ChunkedEvaluations<F> {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        self.chunks.serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.chunks.serialized_size()
+    }
+}
+
+impl<F: CanonicalDeserialize> CanonicalDeserialize for ChunkedEvaluations<F> {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Vec::<F>::deserialize(reader).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExtendedDensePolynomial;
+    use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+    use mina_curves::pasta::fp::Fp;
+
+    #[test]
+    fn test_combine_chunks() {
+        // a polynomial of degree 9, in 3 chunks of size 4
+        let coeffs: Vec<_> = (1..=10u64).map(Fp::from).collect();
+        let f = DensePolynomial::from_coefficients_vec(coeffs);
+        let x = Fp::from(3u64);
+
+        let evals: ChunkedEvaluations<_> = f.to_chunked_polynomial(4).evaluate_chunks(x).into();
+        assert_eq!(evals.len(), 3);
+        assert_eq!(evals.combine(x.pow(&[4u64])), f.evaluate(&x));
+
+        // the serialization is the one of the vector of chunks
+        let mut bytes = vec![];
+        evals.serialize(&mut bytes).unwrap();
+        let mut expected = vec![];
+        evals.chunks.serialize(&mut expected).unwrap();
+        assert_eq!(bytes, expected);
+        assert_eq!(
+            ChunkedEvaluations::<Fp>::deserialize(&mut &bytes[..]).unwrap(),
+            evals
+        );
+    }
+}
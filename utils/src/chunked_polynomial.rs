@@ -1,5 +1,18 @@
-use ark_ff::Field;
+use ark_ff::{Field, Zero};
 use ark_poly::polynomial::{univariate::DensePolynomial, Polynomial};
+use ark_poly::UVPolynomial;
+
+/// Combines the chunks $c_0, c_1, c_2, \ldots$ of a polynomial, of its evaluations or of its commitments,
+/// into $c_0 + x^n c_1 + x^{2n} c_2 + \cdots$,
+/// using [Horner's method](https://en.wikipedia.org/wiki/Horner%27s_method).
+/// Starting from zero, `mul_add(acc, chunk)` is called on the chunks from the last to the first,
+/// and must return $acc \cdot x^n + chunk$.
+pub fn combine_chunks<T: Zero, C>(
+    chunks: impl DoubleEndedIterator<Item = C>,
+    mul_add: impl FnMut(T, C) -> T,
+) -> T {
+    chunks.rev().fold(T::zero(), mul_add)
+}
 
 /// This struct contains multiple chunk polynomials with degree `size-1`.
 pub struct ChunkedPolynomial<F: Field> {
@@ -26,22 +39,12 @@ impl<F: Field> ChunkedPolynomial<F> {
     /// (where f0, f1, f2 are of degree n-1), then this function returns the new semi-evaluated
     /// `f'(x) = f0(x) + zeta^n f1(x) + zeta^2n f2(x)`.
     pub fn linearize(&self, zeta_n: F) -> DensePolynomial<F> {
-        let mut scale = F::one();
-        let mut coeffs = vec![F::zero(); self.size];
-
-        for poly in self.polys.iter() {
-            for (coeff, poly_coeff) in coeffs.iter_mut().zip(&poly.coeffs) {
-                *coeff += scale * poly_coeff;
-            }
-
-            scale *= zeta_n;
-        }
-
-        while coeffs.last().map_or(false, |c| c.is_zero()) {
-            coeffs.pop();
-        }
-
-        DensePolynomial { coeffs }
+        let f = combine_chunks(self.polys.iter(), |mut acc: DensePolynomial<F>, poly| {
+            acc.coeffs.iter_mut().for_each(|coeff| *coeff *= zeta_n);
+            acc += poly;
+            acc
+        });
+        DensePolynomial::from_coefficients_vec(f.coeffs)
     }
 }
 
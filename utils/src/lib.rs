@@ -1,4 +1,5 @@
 pub mod adjacent_pairs;
+pub mod chunked_evaluations;
 pub mod chunked_polynomial;
 pub mod dense_polynomial;
 pub mod evaluations;
@@ -49,6 +49,11 @@ pub mod ser {
 /// or containers of types that implement these traits (Vec, arrays, etc.)
 /// Simply add annotations like `#[serde_as(as = "o1_utils::serialization::SerdeAs")]`
 /// See <https://docs.rs/serde_with/1.10.0/serde_with/guide/serde_as/index.html#switching-from-serdes-with-to-serde_as>
+///
+/// Curve points are encoded compressed: the x-coordinate, with the sign of y and the point at infinity as flags in its last byte.
+/// Deserializing a point recovers y, and fails if the point is not on the curve or not in the prime-order subgroup.
+/// This is the only encoding of the points of proofs and verifier indexes,
+/// the uncompressed one is only available for SRS files (see `commitment_dlog::srs::PointEncoding`).
 pub struct SerdeAs;
 
 impl<T> serde_with::SerializeAs<T> for SerdeAs
@@ -79,3 +84,55 @@ where
         T::deserialize(&mut &bytes[..]).map_err(serde::de::Error::custom)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::Zero;
+    use mina_curves::pasta::{pallas::Affine, Fq};
+    use serde::{Deserialize, Serialize};
+    use serde_with::serde_as;
+
+    #[serde_as]
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Points {
+        #[serde_as(as = "Vec<SerdeAs>")]
+        points: Vec<Affine>,
+    }
+
+    #[test]
+    fn test_compressed_points() {
+        let g = Affine::prime_subgroup_generator();
+        let points: Vec<Affine> = (1..=4u64)
+            .map(|i| g.mul(Fq::from(i)).into_affine())
+            .chain([Affine::zero()])
+            .collect();
+        let encoded = Points {
+            points: points.clone(),
+        };
+        let bytes = bcs::to_bytes(&encoded).unwrap();
+        let decoded: Points = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, encoded);
+
+        // the compressed encoding is half the size of the uncompressed one
+        for p in &points {
+            let (mut compressed, mut uncompressed) = (vec![], vec![]);
+            p.serialize(&mut compressed).unwrap();
+            p.serialize_uncompressed(&mut uncompressed).unwrap();
+            assert_eq!(2 * compressed.len(), uncompressed.len());
+        }
+
+        // the x-coordinates with no point on the curve are rejected
+        let mut bytes = vec![];
+        g.serialize(&mut bytes).unwrap();
+        let rejected = (1..32u8)
+            .filter(|i| {
+                let mut x = bytes.clone();
+                x[0] = x[0].wrapping_add(*i);
+                Affine::deserialize(&mut &x[..]).is_err()
+            })
+            .count();
+        assert!(rejected > 0);
+    }
+}